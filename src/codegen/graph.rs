@@ -0,0 +1,266 @@
+use crate::{Cmp, Const, Expr, FuncName, Num, Pow, Sym};
+
+/// lhs cmp rhs of node indices
+pub type Condition = (usize, Cmp, usize);
+
+/// Node of an expression graph over f64 where children are indices of earlier nodes
+#[derive(PartialEq, Clone, Debug)]
+pub enum Node {
+    Num(f64),
+    Arg(usize),
+    Const(Const),
+    Add(Vec<usize>),
+    /// Product of the first factors divided by the product of the second
+    Mul(Vec<usize>, Vec<usize>),
+    Neg(usize),
+    /// x^n for n>=2 written by multiplications
+    PowI(usize, u32),
+    Sqrt(usize),
+    /// Real q-th root for odd q such as cbrt which is negative for negative x
+    Root(usize, u32),
+    Pow(usize, usize),
+    Func(FuncName, usize),
+    /// Value of the first piece whose condition holds otherwise NaN
+    Piecewise(Vec<(usize, Option<Condition>)>),
+}
+
+impl Node {
+    pub fn children(&self) -> Vec<usize> {
+        match self {
+            Node::Num(_) | Node::Arg(_) | Node::Const(_) => vec![],
+            Node::Add(terms) => terms.clone(),
+            Node::Mul(num, den) => num.iter().chain(den).copied().collect(),
+            // The sign is written by comparing its argument twice
+            Node::Func(FuncName::Sign, x) => vec![*x, *x],
+            Node::Neg(x) | Node::Sqrt(x) | Node::Root(x, 3) | Node::Func(_, x) => vec![*x],
+            // Other roots are written by the sign and the absolute value of their argument
+            Node::Root(x, _) => vec![*x, *x],
+            Node::PowI(x, n) => vec![*x; *n as usize],
+            Node::Pow(x, y) => vec![*x, *y],
+            Node::Piecewise(pieces) => pieces
+                .iter()
+                .flat_map(|(value, cond)| match cond {
+                    Some((lhs, _, rhs)) => vec![*value, *lhs, *rhs],
+                    None => vec![*value],
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Expression graph where equal subexpressions share one node
+#[derive(Clone, Debug)]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    pub root: usize,
+}
+
+impl Graph {
+    /// Fail when expr has symbols other than args, complex numbers, matrices
+    /// or integrals, limits and sums without closed forms
+    pub fn new(expr: &Expr, args: &[Sym]) -> Result<Self, &'static str> {
+        let mut graph = Graph {
+            nodes: vec![],
+            root: 0,
+        };
+        graph.root = graph.lower(expr, args)?;
+        Ok(graph)
+    }
+
+    fn push(&mut self, node: Node) -> usize {
+        match self.nodes.iter().position(|n| *n == node) {
+            Some(id) => id,
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn num(&mut self, x: Num) -> Result<usize, &'static str> {
+        match x.is_real() {
+            true => Ok(self.push(Node::Num(x.num as f64 / x.den as f64))),
+            false => Err("Cannot generate code for complex numbers"),
+        }
+    }
+
+    fn recip(&mut self, x: usize) -> usize {
+        let one = self.push(Node::Num(1.0));
+        self.push(Node::Mul(vec![one], vec![x]))
+    }
+
+    fn lower(&mut self, expr: &Expr, args: &[Sym]) -> Result<usize, &'static str> {
+        let node = match expr {
+            Expr::Num(x) => return self.num(*x),
+            Expr::Sym(x) => Node::Arg(
+                args.iter()
+                    .position(|arg| arg == x)
+                    .ok_or("Symbol is not an argument")?,
+            ),
+            Expr::Const(c) => Node::Const(*c),
+            Expr::Add(add) => Node::Add(
+                add.exprs
+                    .iter()
+                    .map(|e| self.lower(e, args))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Expr::Mul(_) => {
+                let (coeff, mul) = expr.detach_coeff();
+                if !coeff.is_real() {
+                    return Err("Cannot generate code for complex numbers");
+                }
+                let (mut num, mut den) = (vec![], vec![]);
+                if coeff.abs().num != 1 {
+                    num.push(self.num(Num::new(coeff.abs().num))?);
+                }
+                if coeff.den != 1 {
+                    den.push(self.num(Num::new(coeff.den))?);
+                }
+                for e in &mul.exprs {
+                    match e {
+                        Expr::Pow(pow) => match *pow.pow {
+                            Expr::Num(n) if n.is_negative() => {
+                                let pow = Pow::new(*pow.body.clone(), Expr::Num(-n));
+                                den.push(self.lower(&Expr::Pow(pow), args)?)
+                            }
+                            _ => num.push(self.lower(e, args)?),
+                        },
+                        e => num.push(self.lower(e, args)?),
+                    }
+                }
+                let product = match (&num[..], &den[..]) {
+                    ([], []) => self.push(Node::Num(1.0)),
+                    ([x], []) => *x,
+                    ([], _) => {
+                        let one = self.push(Node::Num(1.0));
+                        self.push(Node::Mul(vec![one], den))
+                    }
+                    _ => self.push(Node::Mul(num, den)),
+                };
+                return Ok(match coeff.is_negative() {
+                    true => self.push(Node::Neg(product)),
+                    false => product,
+                });
+            }
+            Expr::Pow(pow) => {
+                let body = self.lower(&pow.body, args)?;
+                match *pow.pow {
+                    Expr::Num(n) if n.is_one() => return Ok(body),
+                    Expr::Num(n) if n.is_integer() && n.num.abs() >= 2 && n.num.abs() <= 16 => {
+                        let power = self.push(Node::PowI(body, n.num.unsigned_abs() as u32));
+                        match n.is_negative() {
+                            true => return Ok(self.recip(power)),
+                            false => return Ok(power),
+                        }
+                    }
+                    Expr::Num(n) if n == Num::new(-1) => return Ok(self.recip(body)),
+                    Expr::Num(n) if n.abs() == Num::frac(1, 2) => {
+                        let root = self.push(Node::Sqrt(body));
+                        match n.is_negative() {
+                            true => return Ok(self.recip(root)),
+                            false => return Ok(root),
+                        }
+                    }
+                    // x^{p/q}->root(x,q)^p since powf is NaN for negative x
+                    Expr::Num(n)
+                        if n.is_real() && n.den % 2 == 1 && n.den > 1 && n.num.abs() <= 16 =>
+                    {
+                        let root = self.push(Node::Root(body, n.den as u32));
+                        let power = match n.num.abs() {
+                            1 => root,
+                            p => self.push(Node::PowI(root, p as u32)),
+                        };
+                        match n.is_negative() {
+                            true => return Ok(self.recip(power)),
+                            false => return Ok(power),
+                        }
+                    }
+                    _ => Node::Pow(body, self.lower(&pow.pow, args)?),
+                }
+            }
+            Expr::Func(func) => Node::Func(func.name, self.lower(&func.arg, args)?),
+            Expr::Piecewise(piecewise) => {
+                let mut pieces = vec![];
+                for (value, cond) in &piecewise.pieces {
+                    let cond = match cond {
+                        Some(c) => {
+                            Some((self.lower(&c.lhs, args)?, c.cmp, self.lower(&c.rhs, args)?))
+                        }
+                        None => None,
+                    };
+                    pieces.push((self.lower(value, args)?, cond));
+                }
+                Node::Piecewise(pieces)
+            }
+            Expr::Matrix(_) => return Err("Cannot generate code for matrices"),
+            // Integrals, limits and sums are generated if they have closed forms
+            x => match x.collect() {
+                Expr::Integral(_) | Expr::Limit(_) | Expr::Sum(_) => {
+                    return Err("Cannot generate code for unevaluated expression")
+                }
+                x => return self.lower(&x, args),
+            },
+        };
+        Ok(self.push(node))
+    }
+
+    /// Number of times each node is referenced from the root
+    pub fn uses(&self) -> Vec<usize> {
+        let mut uses = vec![0; self.nodes.len()];
+        let mut reached = vec![false; self.nodes.len()];
+        reached[self.root] = true;
+        uses[self.root] = 1;
+        // Parents come after their children so a reverse sweep visits parents first
+        for id in (0..self.nodes.len()).rev() {
+            if reached[id] {
+                self.nodes[id].children().into_iter().for_each(|child| {
+                    reached[child] = true;
+                    uses[child] += 1;
+                });
+            }
+        }
+        uses
+    }
+
+    /// Common subexpressions in evaluation order, which are nodes other than
+    /// numbers, arguments and constants used more than once
+    pub fn common(&self) -> Vec<usize> {
+        self.uses()
+            .iter()
+            .enumerate()
+            .filter(|(id, &uses)| {
+                uses > 1
+                    && !matches!(
+                        self.nodes[*id],
+                        Node::Num(_) | Node::Arg(_) | Node::Const(_)
+                    )
+            })
+            .map(|(id, _)| id)
+            .collect()
+    }
+}
+
+#[test]
+fn test_graph() {
+    use crate::parser::latex_to_expr;
+    let (x, y) = (Sym::new("x"), Sym::new("y"));
+    let expr = latex_to_expr("\\sin(x+y)^{2}+\\frac{\\sqrt{x+y}}{3}").collect();
+    let graph = Graph::new(&expr, &[x, y]).unwrap();
+    assert_eq!(
+        graph
+            .nodes
+            .iter()
+            .filter(|n| matches!(n, Node::Add(_)))
+            .count(),
+        2
+    );
+    assert_eq!(graph.common().len(), 2);
+    assert_eq!(
+        Graph::new(&latex_to_expr("x+z"), &[x, y]).unwrap_err(),
+        "Symbol is not an argument"
+    );
+    assert_eq!(
+        Graph::new(&latex_to_expr("x+2i"), &[x]).unwrap_err(),
+        "Cannot generate code for complex numbers"
+    );
+}
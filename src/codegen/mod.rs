@@ -0,0 +1,416 @@
+mod graph;
+mod program;
+mod sample;
+use crate::parser::precedence::{Printer, ADD, ATOM, MUL, POW};
+use crate::{Cmp, Const, Expr, FuncName, Sym};
+use graph::{Graph, Node};
+pub use program::{compile, Instruction, Program};
+pub use sample::sample;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Language {
+    Rust,
+    C,
+    JavaScript,
+}
+
+impl Language {
+    /// rust, c and js or javascript
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rust" => Some(Language::Rust),
+            "c" => Some(Language::C),
+            "js" | "javascript" => Some(Language::JavaScript),
+            _ => None,
+        }
+    }
+}
+
+/// Legal identifier of a symbol such as x_1 for x_{1} and alpha for \alpha
+pub fn identifier(x: &Sym) -> String {
+    let symbol = x.symbol().trim_start_matches('\\');
+    match x.sub() {
+        "" => symbol.to_string(),
+        sub => {
+            let sub: String = sub
+                .chars()
+                .filter(|&c| c != '\\')
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            format!("{}_{}", symbol, sub)
+        }
+    }
+}
+
+struct Emitter<'b> {
+    graph: &'b Graph,
+    language: Language,
+    /// Temporaries holding common subexpressions by node
+    names: Vec<Option<String>>,
+    args: Vec<String>,
+}
+
+impl Printer<usize> for Emitter<'_> {
+    type Output = String;
+
+    fn print(&self, id: &usize) -> (String, u8) {
+        self.emit(*id)
+    }
+
+    fn parens(&self, code: String) -> String {
+        format!("({})", code)
+    }
+}
+
+impl<'b> Emitter<'b> {
+    /// Method receiver in Rust where a bare float literal needs its type
+    fn receiver(&self, id: usize) -> String {
+        match &self.graph.nodes[id] {
+            Node::Num(x) if self.names[id].is_none() && *x < 0.0 => format!("({:?}_f64)", x),
+            Node::Num(x) if self.names[id].is_none() => format!("{:?}_f64", x),
+            _ => self.wrap(&id, ATOM),
+        }
+    }
+
+    fn call(&self, name: &str, id: usize) -> String {
+        match self.language {
+            Language::Rust => format!("{}.{}()", self.receiver(id), name),
+            Language::C => format!("{}({})", name, self.emit(id).0),
+            Language::JavaScript => format!("Math.{}({})", name, self.emit(id).0),
+        }
+    }
+
+    /// Code of the node and its precedence where 0 is a conditional and negation binds as POW
+    fn emit(&self, id: usize) -> (String, u8) {
+        if let Some(name) = &self.names[id] {
+            return (name.clone(), ATOM);
+        }
+        let language = self.language;
+        match &self.graph.nodes[id] {
+            Node::Num(x) if *x < 0.0 => (format!("{:?}", x), POW),
+            Node::Num(x) => (format!("{:?}", x), ATOM),
+            Node::Arg(i) => (self.args[*i].clone(), ATOM),
+            Node::Const(c) => {
+                let code = match (language, c) {
+                    (Language::Rust, Const::Pi) => "std::f64::consts::PI",
+                    (Language::Rust, Const::E) => "std::f64::consts::E",
+                    (Language::Rust, Const::Infinity) => "f64::INFINITY",
+                    (Language::C, Const::Pi) => "M_PI",
+                    (Language::C, Const::E) => "M_E",
+                    (Language::C, Const::Infinity) => "INFINITY",
+                    (Language::JavaScript, Const::Pi) => "Math.PI",
+                    (Language::JavaScript, Const::E) => "Math.E",
+                    (Language::JavaScript, Const::Infinity) => "Infinity",
+                };
+                (code.to_string(), ATOM)
+            }
+            Node::Add(terms) => {
+                let mut code = self.wrap(&terms[0], ADD);
+                terms[1..]
+                    .iter()
+                    .for_each(|&term| match &self.graph.nodes[term] {
+                        Node::Neg(x) if self.names[term].is_none() => {
+                            code.push_str(&format!(" - {}", self.wrap(x, MUL)))
+                        }
+                        Node::Num(x) if *x < 0.0 && self.names[term].is_none() => {
+                            code.push_str(&format!(" - {:?}", -x))
+                        }
+                        _ => code.push_str(&format!(" + {}", self.wrap(&term, ADD))),
+                    });
+                (code, ADD)
+            }
+            Node::Mul(num, den) => {
+                let product = |factors: &[usize]| {
+                    factors
+                        .iter()
+                        .map(|&f| self.wrap(&f, MUL))
+                        .collect::<Vec<_>>()
+                        .join(" * ")
+                };
+                let code = match &den[..] {
+                    [] => product(num),
+                    [x] => format!("{} / {}", product(num), self.wrap(x, ATOM)),
+                    den => format!("{} / ({})", product(num), product(den)),
+                };
+                (code, MUL)
+            }
+            Node::Neg(x) => match self.emit(*x) {
+                (code, ATOM) => (format!("-{}", code), POW),
+                _ => (format!("-{}", self.wrap(x, MUL)), MUL),
+            },
+            Node::PowI(x, n) => {
+                let factors = vec![self.wrap(x, MUL); *n as usize];
+                (factors.join(" * "), MUL)
+            }
+            Node::Sqrt(x) => (self.call("sqrt", *x), ATOM),
+            Node::Root(x, 3) => (self.call("cbrt", *x), ATOM),
+            Node::Root(x, q) => {
+                let exponent = format!("1.0 / {:?}", *q as f64);
+                match language {
+                    Language::Rust => {
+                        let x = self.receiver(*x);
+                        let code = format!("{}.abs().powf({}).copysign({})", x, exponent, x);
+                        (code, ATOM)
+                    }
+                    Language::C => {
+                        let x = self.emit(*x).0;
+                        (
+                            format!("copysign(pow(fabs({}), {}), {})", x, exponent, x),
+                            ATOM,
+                        )
+                    }
+                    Language::JavaScript => {
+                        let x = self.emit(*x).0;
+                        let code =
+                            format!("Math.sign({}) * Math.pow(Math.abs({}), {})", x, x, exponent);
+                        (code, MUL)
+                    }
+                }
+            }
+            Node::Pow(x, y) => {
+                let code = match (language, &self.graph.nodes[*y]) {
+                    (Language::Rust, Node::Num(n))
+                        if n.fract() == 0.0
+                            && n.abs() <= i32::MAX as f64
+                            && self.names[*y].is_none() =>
+                    {
+                        format!("{}.powi({})", self.receiver(*x), n)
+                    }
+                    (Language::Rust, _) => {
+                        format!("{}.powf({})", self.receiver(*x), self.emit(*y).0)
+                    }
+                    (Language::C, _) => format!("pow({}, {})", self.emit(*x).0, self.emit(*y).0),
+                    (Language::JavaScript, _) => {
+                        format!("Math.pow({}, {})", self.emit(*x).0, self.emit(*y).0)
+                    }
+                };
+                (code, ATOM)
+            }
+            Node::Func(name, x) => self.func(*name, *x),
+            Node::Piecewise(pieces) => {
+                let mut branches = vec![];
+                let mut otherwise = None;
+                for (value, cond) in pieces {
+                    match cond {
+                        Some((lhs, cmp, rhs)) => {
+                            let cmp = match (cmp, language) {
+                                (Cmp::Lt, _) => "<",
+                                (Cmp::Le, _) => "<=",
+                                (Cmp::Gt, _) => ">",
+                                (Cmp::Ge, _) => ">=",
+                                (Cmp::Eq, Language::JavaScript) => "===",
+                                (Cmp::Eq, _) => "==",
+                                (Cmp::Ne, Language::JavaScript) => "!==",
+                                (Cmp::Ne, _) => "!=",
+                            };
+                            let cond =
+                                format!("{} {} {}", self.wrap(lhs, ADD), cmp, self.wrap(rhs, ADD));
+                            branches.push((cond, self.wrap(value, ADD)));
+                        }
+                        None => {
+                            otherwise = Some(self.wrap(value, ADD));
+                            break;
+                        }
+                    }
+                }
+                let otherwise = otherwise.unwrap_or(String::from(match language {
+                    Language::Rust => "f64::NAN",
+                    Language::C => "NAN",
+                    Language::JavaScript => "NaN",
+                }));
+                let code = match language {
+                    Language::Rust => {
+                        let branches: Vec<String> = branches
+                            .iter()
+                            .map(|(cond, value)| format!("if {} {{ {} }}", cond, value))
+                            .collect();
+                        format!("{} else {{ {} }}", branches.join(" else "), otherwise)
+                    }
+                    _ => {
+                        let mut code = String::new();
+                        branches.iter().for_each(|(cond, value)| {
+                            code.push_str(&format!("{} ? {} : ", cond, value))
+                        });
+                        code.push_str(&otherwise);
+                        code
+                    }
+                };
+                (code, 0)
+            }
+        }
+    }
+
+    fn func(&self, name: FuncName, x: usize) -> (String, u8) {
+        let language = self.language;
+        let recip = |f: &str| match language {
+            Language::Rust => (format!("{}.{}().recip()", self.receiver(x), f), ATOM),
+            _ => (format!("1.0 / {}", self.call(f, x)), MUL),
+        };
+        let name = match (name, language) {
+            (FuncName::Cot, _) => return recip("tan"),
+            (FuncName::Sec, _) => return recip("cos"),
+            (FuncName::Csc, _) => return recip("sin"),
+            (FuncName::Re | FuncName::Conj, _) => return self.emit(x),
+            (FuncName::Im, _) => return (String::from("0.0"), ATOM),
+            (FuncName::Sign, Language::Rust) => {
+                let x = self.receiver(x);
+                return (
+                    format!("if {} == 0.0 {{ 0.0 }} else {{ {}.signum() }}", x, x),
+                    0,
+                );
+            }
+            (FuncName::Sign, Language::C) => {
+                let x = self.wrap(&x, ADD + 1);
+                return (format!("(double)(({} > 0) - ({} < 0))", x, x), ATOM);
+            }
+            (FuncName::Sign, Language::JavaScript) => "sign",
+            (FuncName::Sin, _) => "sin",
+            (FuncName::Cos, _) => "cos",
+            (FuncName::Tan, _) => "tan",
+            (FuncName::Arcsin, _) => "asin",
+            (FuncName::Arccos, _) => "acos",
+            (FuncName::Arctan, _) => "atan",
+            (FuncName::Sinh, _) => "sinh",
+            (FuncName::Cosh, _) => "cosh",
+            (FuncName::Tanh, _) => "tanh",
+            (FuncName::Exp, _) => "exp",
+            (FuncName::Ln, Language::Rust) => "ln",
+            (FuncName::Ln, _) => "log",
+            (FuncName::Abs, Language::C) => "fabs",
+            (FuncName::Abs, _) => "abs",
+        };
+        (self.call(name, x), ATOM)
+    }
+}
+
+/// Function of f64 arguments in the given order computing expr where integer powers are
+/// multiplications and common subexpressions are computed once into temporaries
+pub fn codegen(
+    expr: &Expr,
+    name: &str,
+    args: &[Sym],
+    language: Language,
+) -> Result<String, &'static str> {
+    let graph = Graph::new(expr, args)?;
+    let args: Vec<String> = args.iter().map(identifier).collect();
+    let mut emitter = Emitter {
+        graph: &graph,
+        language,
+        names: vec![None; graph.nodes.len()],
+        args: args.clone(),
+    };
+    let mut lines = vec![];
+    for (i, id) in graph.common().into_iter().enumerate() {
+        let (code, _) = emitter.emit(id);
+        lines.push(match language {
+            Language::Rust => format!("    let t{} = {};", i, code),
+            Language::C => format!("    const double t{} = {};", i, code),
+            Language::JavaScript => format!("    const t{} = {};", i, code),
+        });
+        emitter.names[id] = Some(format!("t{}", i));
+    }
+    let (body, _) = emitter.emit(graph.root);
+    let code = match language {
+        Language::Rust => {
+            let params: Vec<String> = args.iter().map(|x| format!("{}: f64", x)).collect();
+            lines.push(format!("    {}", body));
+            format!(
+                "pub fn {}({}) -> f64 {{\n{}\n}}",
+                name,
+                params.join(", "),
+                lines.join("\n")
+            )
+        }
+        Language::C => {
+            let params: Vec<String> = args.iter().map(|x| format!("double {}", x)).collect();
+            lines.push(format!("    return {};", body));
+            format!(
+                "double {}({}) {{\n{}\n}}",
+                name,
+                params.join(", "),
+                lines.join("\n")
+            )
+        }
+        Language::JavaScript if lines.is_empty() => {
+            format!("const {} = ({}) => {};", name, args.join(", "), body)
+        }
+        Language::JavaScript => {
+            lines.push(format!("    return {};", body));
+            format!(
+                "const {} = ({}) => {{\n{}\n}};",
+                name,
+                args.join(", "),
+                lines.join("\n")
+            )
+        }
+    };
+    Ok(code)
+}
+
+#[test]
+fn test_codegen() {
+    use crate::parser::latex_to_expr;
+    let (x, y) = (Sym::new("x"), Sym::new("y").set_sub("1"));
+    let expr = latex_to_expr("\\sin(x+y_{1})^{2}-\\frac{3x^{3}}{2}").collect();
+    assert_eq!(
+        codegen(&expr, "f", &[x, y], Language::Rust).unwrap(),
+        "pub fn f(x: f64, y_1: f64) -> f64 {\n    let t0 = (x + y_1).sin();\n    t0 * t0 - 3.0 * x * x * x / 2.0\n}"
+    );
+    assert_eq!(
+        codegen(&expr, "f", &[x, y], Language::C).unwrap(),
+        "double f(double x, double y_1) {\n    const double t0 = sin(x + y_1);\n    return t0 * t0 - 3.0 * x * x * x / 2.0;\n}"
+    );
+    assert_eq!(
+        codegen(&expr, "f", &[x, y], Language::JavaScript).unwrap(),
+        "const f = (x, y_1) => {\n    const t0 = Math.sin(x + y_1);\n    return t0 * t0 - 3.0 * x * x * x / 2.0;\n};"
+    );
+    let alpha = Sym::new("\\alpha");
+    let expr = latex_to_expr("\\frac{\\pi}{\\sqrt{\\alpha}}+\\cot(\\alpha)^{-1}");
+    assert_eq!(
+        codegen(&expr, "g", &[alpha], Language::JavaScript).unwrap(),
+        "const g = (alpha) => Math.PI / Math.sqrt(alpha) + 1.0 / (1.0 / Math.tan(alpha));"
+    );
+    let expr = latex_to_expr("\\begin{cases}x^{2}&x\\geq 0\\\\-x&\\text{otherwise}\\end{cases}");
+    assert_eq!(
+        codegen(&expr, "h", &[x], Language::Rust).unwrap(),
+        "pub fn h(x: f64) -> f64 {\n    if x >= 0.0 { x * x } else { -x }\n}"
+    );
+    assert_eq!(
+        codegen(&expr, "h", &[x], Language::C).unwrap(),
+        "double h(double x) {\n    return x >= 0.0 ? x * x : -x;\n}"
+    );
+    let expr = latex_to_expr("2^{x}+\\sqrt{2}x^{100000000000}");
+    assert_eq!(
+        codegen(&expr, "p", &[x], Language::Rust).unwrap(),
+        "pub fn p(x: f64) -> f64 {\n    2.0_f64.powf(x) + 2.0_f64.sqrt() * x.powf(100000000000.0)\n}"
+    );
+    let expr = latex_to_expr("\\sqrt[3]{x}+x^{\\frac{2}{5}}");
+    assert_eq!(
+        codegen(&expr, "r", &[x], Language::Rust).unwrap(),
+        "pub fn r(x: f64) -> f64 {\n    let t0 = x.abs().powf(1.0 / 5.0).copysign(x);\n    x.cbrt() + t0 * t0\n}"
+    );
+    assert_eq!(
+        codegen(&expr, "r", &[x], Language::C).unwrap(),
+        "double r(double x) {\n    const double t0 = copysign(pow(fabs(x), 1.0 / 5.0), x);\n    return cbrt(x) + t0 * t0;\n}"
+    );
+    assert_eq!(
+        codegen(&expr, "r", &[x], Language::JavaScript).unwrap(),
+        "const r = (x) => {\n    const t0 = Math.sign(x) * Math.pow(Math.abs(x), 1.0 / 5.0);\n    return Math.cbrt(x) + t0 * t0;\n};"
+    );
+    assert_eq!(
+        codegen(&expr, "h", &[], Language::C),
+        Err("Symbol is not an argument")
+    );
+}
+
+#[test]
+fn test_rust_receivers() {
+    use crate::parser::latex_to_expr;
+    let x = Sym::new("x");
+    // Bare float literals such as 2.0.sin() do not compile and powi takes an i32
+    let expr = latex_to_expr("\\sin 2+(-2)^{x}+\\sec(3)+x^{20}+x^{3000000000}");
+    assert_eq!(
+        codegen(&expr, "f", &[x], Language::Rust).unwrap(),
+        "pub fn f(x: f64) -> f64 {\n    2.0_f64.sin() + (-2.0_f64).powf(x) + 3.0_f64.cos().recip() + x.powi(20) + x.powf(3000000000.0)\n}"
+    );
+}
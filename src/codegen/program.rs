@@ -0,0 +1,279 @@
+use super::graph::{Graph, Node};
+use crate::{Cmp, Expr, FuncName, Sym};
+
+/// Instruction writing the register of its own index from registers of earlier ones
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Instruction {
+    Load(f64),
+    Arg(usize),
+    Add(usize, usize),
+    Mul(usize, usize),
+    Div(usize, usize),
+    Neg(usize),
+    PowI(usize, i32),
+    Sqrt(usize),
+    /// Real q-th root for odd q
+    Root(usize, u32),
+    Pow(usize, usize),
+    Func(FuncName, usize),
+    /// 1 if the comparison holds otherwise 0 where NaN never holds
+    Cmp(Cmp, usize, usize),
+    /// Second register if the first is nonzero otherwise the third
+    Select(usize, usize, usize),
+}
+
+/// Register program of an expr over f64 arguments where out of domain values are NaN
+#[derive(Clone, PartialEq, Debug)]
+pub struct Program {
+    pub instructions: Vec<Instruction>,
+    pub args: usize,
+    /// Register holding the value
+    pub result: usize,
+}
+
+impl Program {
+    fn push(&mut self, instruction: Instruction) -> usize {
+        self.instructions.push(instruction);
+        self.instructions.len() - 1
+    }
+
+    /// Fold registers by a binary instruction such as a+b+c->(a+b)+c
+    fn fold(&mut self, registers: Vec<usize>, op: fn(usize, usize) -> Instruction) -> usize {
+        registers
+            .into_iter()
+            .reduce(|acc, r| self.push(op(acc, r)))
+            .unwrap()
+    }
+
+    /// Value at args in the order of compile
+    pub fn eval(&self, args: &[f64]) -> f64 {
+        let mut registers = vec![0.0; self.instructions.len()];
+        self.run(args, &mut registers)
+    }
+
+    /// Values at points where args[i][k] is the i-th argument at the k-th point
+    /// and the values are written to out[k]
+    pub fn eval_slices(&self, args: &[&[f64]], out: &mut [f64]) {
+        if args.len() != self.args || args.iter().any(|a| a.len() != out.len()) {
+            panic!("Slices must match the arguments and the output");
+        }
+        let mut registers = vec![0.0; self.instructions.len()];
+        let mut point = vec![0.0; self.args];
+        out.iter_mut().enumerate().for_each(|(k, y)| {
+            point.iter_mut().zip(args).for_each(|(x, arg)| *x = arg[k]);
+            *y = self.run(&point, &mut registers);
+        });
+    }
+
+    /// Crash when args has fewer values than the program
+    fn run(&self, args: &[f64], registers: &mut [f64]) -> f64 {
+        for (i, instruction) in self.instructions.iter().enumerate() {
+            registers[i] = match *instruction {
+                Instruction::Load(x) => x,
+                Instruction::Arg(j) => args[j],
+                Instruction::Add(a, b) => registers[a] + registers[b],
+                Instruction::Mul(a, b) => registers[a] * registers[b],
+                Instruction::Div(a, b) => registers[a] / registers[b],
+                Instruction::Neg(a) => -registers[a],
+                Instruction::PowI(a, n) => registers[a].powi(n),
+                Instruction::Sqrt(a) => registers[a].sqrt(),
+                Instruction::Root(a, 3) => registers[a].cbrt(),
+                Instruction::Root(a, q) => {
+                    let x = registers[a];
+                    x.abs().powf(1.0 / q as f64).copysign(x)
+                }
+                Instruction::Pow(a, b) => registers[a].powf(registers[b]),
+                Instruction::Func(name, a) => {
+                    let x = registers[a];
+                    match name {
+                        FuncName::Sin => x.sin(),
+                        FuncName::Cos => x.cos(),
+                        FuncName::Tan => x.tan(),
+                        FuncName::Cot => x.tan().recip(),
+                        FuncName::Sec => x.cos().recip(),
+                        FuncName::Csc => x.sin().recip(),
+                        FuncName::Arcsin => x.asin(),
+                        FuncName::Arccos => x.acos(),
+                        FuncName::Arctan => x.atan(),
+                        FuncName::Sinh => x.sinh(),
+                        FuncName::Cosh => x.cosh(),
+                        FuncName::Tanh => x.tanh(),
+                        FuncName::Exp => x.exp(),
+                        FuncName::Ln => x.ln(),
+                        FuncName::Abs => x.abs(),
+                        FuncName::Sign if x == 0.0 => 0.0,
+                        FuncName::Sign => x.signum(),
+                        FuncName::Re | FuncName::Conj => x,
+                        FuncName::Im => 0.0,
+                    }
+                }
+                Instruction::Cmp(cmp, a, b) => match registers[a].partial_cmp(&registers[b]) {
+                    Some(sign) if cmp.accepts(sign) => 1.0,
+                    _ => 0.0,
+                },
+                Instruction::Select(c, a, b) => match registers[c] != 0.0 {
+                    true => registers[a],
+                    false => registers[b],
+                },
+            };
+        }
+        registers[self.result]
+    }
+}
+
+/// Register program of expr whose arguments are args in order,
+/// computing common subexpressions once
+pub fn compile(expr: &Expr, args: &[Sym]) -> Result<Program, &'static str> {
+    let graph = Graph::new(expr, args)?;
+    let uses = graph.uses();
+    let mut program = Program {
+        instructions: vec![],
+        args: args.len(),
+        result: 0,
+    };
+    // Nodes come after their children so each child already has a register
+    let mut registers = vec![0; graph.nodes.len()];
+    for (id, node) in graph.nodes.iter().enumerate() {
+        if uses[id] == 0 {
+            continue;
+        }
+        let instruction = match node {
+            Node::Num(x) => Instruction::Load(*x),
+            Node::Arg(i) => Instruction::Arg(*i),
+            Node::Const(c) => Instruction::Load(c.value()),
+            Node::Add(terms) => {
+                let terms = terms.iter().map(|t| registers[*t]).collect();
+                registers[id] = program.fold(terms, Instruction::Add);
+                continue;
+            }
+            Node::Mul(num, den) => {
+                let num = program.fold(
+                    num.iter().map(|f| registers[*f]).collect(),
+                    Instruction::Mul,
+                );
+                registers[id] = match den.is_empty() {
+                    true => num,
+                    false => {
+                        let den = den.iter().map(|f| registers[*f]).collect();
+                        let den = program.fold(den, Instruction::Mul);
+                        program.push(Instruction::Div(num, den))
+                    }
+                };
+                continue;
+            }
+            Node::Neg(x) => Instruction::Neg(registers[*x]),
+            Node::PowI(x, n) => Instruction::PowI(registers[*x], *n as i32),
+            Node::Sqrt(x) => Instruction::Sqrt(registers[*x]),
+            Node::Root(x, q) => Instruction::Root(registers[*x], *q),
+            Node::Pow(x, y) => Instruction::Pow(registers[*x], registers[*y]),
+            Node::Func(name, x) => Instruction::Func(*name, registers[*x]),
+            Node::Piecewise(pieces) => {
+                // Select from the last piece backwards starting with NaN when nothing holds
+                let mut value = program.push(Instruction::Load(f64::NAN));
+                for (piece, cond) in pieces.iter().rev() {
+                    value = match cond {
+                        Some((lhs, cmp, rhs)) => {
+                            let holds = program.push(Instruction::Cmp(
+                                *cmp,
+                                registers[*lhs],
+                                registers[*rhs],
+                            ));
+                            program.push(Instruction::Select(holds, registers[*piece], value))
+                        }
+                        None => registers[*piece],
+                    };
+                }
+                registers[id] = value;
+                continue;
+            }
+        };
+        registers[id] = program.push(instruction);
+    }
+    program.result = registers[graph.root];
+    Ok(program)
+}
+
+#[test]
+fn test_program() {
+    use crate::parser::latex_to_expr;
+    use crate::Num;
+    let (x, y) = (Sym::new("x"), Sym::new("y"));
+    let tests = [
+        "\\sin(x+y)^{2}+\\frac{\\sqrt{x+y}}{3}",
+        "\\frac{x^{3}-2xy}{y^{2}+1}-e^{-x}\\ln(y)",
+        "|x-y|^{\\frac{3}{2}}\\cot(y)-\\pi",
+        "\\begin{cases}x^{2}&x<y\\\\y&\\text{otherwise}\\end{cases}",
+    ];
+    let points = [(0.5, 2.0), (3.0, 1.25), (-0.5, 0.75)];
+    tests.iter().for_each(|test| {
+        let expr = latex_to_expr(test);
+        let program = compile(&expr, &[x, y]).unwrap();
+        points.iter().for_each(|&(a, b)| {
+            let value = expr
+                .subs(x, &Expr::Num(Num::frac((a * 4.0) as i64, 4)))
+                .subs(y, &Expr::Num(Num::frac((b * 4.0) as i64, 4)))
+                .eval()
+                .unwrap();
+            assert!(
+                (program.eval(&[a, b]) - value).abs() < 1e-9,
+                "{} at {}",
+                test,
+                a
+            );
+        });
+    });
+    let program = compile(&latex_to_expr("\\frac{1}{x}+y"), &[x, y]).unwrap();
+    let mut out = [0.0; 3];
+    program.eval_slices(&[&[1.0, 2.0, 0.0], &[1.0, 1.0, 1.0]], &mut out);
+    assert_eq!(out, [2.0, 1.5, f64::INFINITY]);
+    assert!(compile(&latex_to_expr("\\ln(x)"), &[x])
+        .unwrap()
+        .eval(&[-1.0])
+        .is_nan());
+    assert_eq!(compile(&latex_to_expr("3"), &[]).unwrap().eval(&[]), 3.0);
+    let roots = [
+        ["\\sqrt[3]{x}", "-8"],
+        ["x^{\\frac{3}{5}}", "-32"],
+        ["x^{-\\frac{2}{3}}", "-8"],
+    ];
+    let values = roots.map(|[root, x]| {
+        compile(&latex_to_expr(root), &[Sym::new("x")])
+            .unwrap()
+            .eval(&[x.parse().unwrap()])
+    });
+    assert_eq!(values, [-2.0, -8.0, 0.25]);
+}
+
+#[test]
+fn test_compile_errors() {
+    use crate::parser::latex_to_expr;
+    let x = Sym::new("x");
+    let errors = [
+        ["x+y", "Symbol is not an argument"],
+        ["x+2i", "Cannot generate code for complex numbers"],
+        [
+            "\\begin{pmatrix}x&1\\end{pmatrix}",
+            "Cannot generate code for matrices",
+        ],
+        [
+            "\\int_{0}^{1}e^{x^{2}}dx",
+            "Cannot generate code for unevaluated expression",
+        ],
+        [
+            "\\sum_{k=1}^{x}\\frac{1}{k^{2}}",
+            "Cannot generate code for unevaluated expression",
+        ],
+    ];
+    errors.iter().for_each(|test| {
+        let expr = latex_to_expr(test[0]).collect();
+        assert_eq!(compile(&expr, &[x]), Err(test[1]), "{}", test[0]);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Slices must match the arguments and the output")]
+fn test_eval_slices_mismatch() {
+    use crate::parser::latex_to_expr;
+    let program = compile(&latex_to_expr("x+1"), &[Sym::new("x")]).unwrap();
+    program.eval_slices(&[&[1.0, 2.0]], &mut [0.0; 3]);
+}
@@ -0,0 +1,148 @@
+use super::program::Program;
+
+/// Depth of bisection of each interval of the initial grid
+const MAX_DEPTH: u32 = 8;
+
+/// Curve of points in the order of x split where the function is undefined or jumps
+struct Segments {
+    segments: Vec<Vec<(f64, f64)>>,
+}
+
+impl Segments {
+    fn push(&mut self, x: f64, y: f64) {
+        match y.is_finite() {
+            true => self.segments.last_mut().unwrap().push((x, y)),
+            false => self.split(),
+        }
+    }
+
+    fn split(&mut self) {
+        if !self.segments.last().unwrap().is_empty() {
+            self.segments.push(vec![]);
+        }
+    }
+}
+
+struct Sampler<'b> {
+    program: &'b Program,
+    /// Deviation from a straight line below which an interval is flat
+    tolerance: f64,
+    /// Difference between neighbours above which an interval has a discontinuity
+    jump: f64,
+}
+
+impl<'b> Sampler<'b> {
+    /// Points of (a,b] given fa=f(a) and fb=f(b) bisecting curved intervals
+    /// and intervals reaching undefined values, poles or jumps
+    fn refine(&self, (a, fa): (f64, f64), (b, fb): (f64, f64), depth: u32, out: &mut Segments) {
+        let m = (a + b) / 2.0;
+        let fm = self.program.eval(&[m]);
+        let defined = fa.is_finite() && fb.is_finite() && fm.is_finite();
+        let flat = (fm - (fa + fb) / 2.0).abs() <= self.tolerance;
+        let continuous = (fb - fa).abs() <= self.jump;
+        if depth == 0 {
+            // Jumps not shrinking by bisection are discontinuities such as steps and poles
+            let half = (fm - fa).abs().max((fb - fm).abs());
+            let step = (fb - fa).abs();
+            if defined && step > self.tolerance && half > 0.9 * step {
+                out.split();
+            }
+            out.push(b, fb);
+        } else if defined && flat && continuous {
+            out.push(m, fm);
+            out.push(b, fb);
+        } else if !fa.is_finite() && !fb.is_finite() && !fm.is_finite() {
+            out.push(b, fb);
+        } else {
+            self.refine((a, fa), (m, fm), depth - 1, out);
+            self.refine((m, fm), (b, fb), depth - 1, out);
+        }
+    }
+}
+
+/// Segments of points (x,f(x)) for x from from to to starting with n evenly spaced
+/// points, adding points where the curve bends and splitting where f is undefined,
+/// has a pole or jumps
+///
+/// Crash when the program does not have exactly one argument
+pub fn sample(program: &Program, from: f64, to: f64, n: usize) -> Vec<Vec<(f64, f64)>> {
+    if program.args != 1 {
+        panic!("Sampling needs a program of one argument");
+    }
+    let n = n.max(2);
+    let xs: Vec<f64> = (0..n)
+        .map(|i| from + (to - from) * i as f64 / (n - 1) as f64)
+        .collect();
+    let mut ys = vec![0.0; n];
+    program.eval_slices(&[&xs], &mut ys);
+    // The scale of the curve ignores the extreme values near poles
+    let mut finite: Vec<f64> = ys.iter().copied().filter(|y| y.is_finite()).collect();
+    finite.sort_by(|a, b| a.total_cmp(b));
+    let scale = match finite.len() {
+        0 => 0.0,
+        len => finite[(len - 1) * 19 / 20] - finite[(len - 1) / 20],
+    };
+    let sampler = Sampler {
+        program,
+        tolerance: scale * 1e-3,
+        jump: scale.max(f64::MIN_POSITIVE) * 0.5,
+    };
+    let mut out = Segments {
+        segments: vec![vec![]],
+    };
+    out.push(xs[0], ys[0]);
+    (1..n)
+        .for_each(|i| sampler.refine((xs[i - 1], ys[i - 1]), (xs[i], ys[i]), MAX_DEPTH, &mut out));
+    out.segments.retain(|segment| !segment.is_empty());
+    out.segments
+}
+
+#[test]
+fn test_sample() {
+    use super::program::compile;
+    use crate::parser::latex_to_expr;
+    use crate::Sym;
+    let x = Sym::new("x");
+    let sample_of = |latex: &str, from: f64, to: f64| {
+        let program = compile(&latex_to_expr(latex).collect(), &[x]).unwrap();
+        sample(&program, from, to, 21)
+    };
+    let line = sample_of("2x+1", 0.0, 1.0);
+    assert_eq!(line.len(), 1);
+    assert_eq!(line[0].len(), 41);
+    assert_eq!(line[0][0], (0.0, 1.0));
+    assert_eq!(line[0][40], (1.0, 3.0));
+    let parabola = sample_of("x^{2}", -1.0, 1.0);
+    assert_eq!(parabola.len(), 1);
+    assert!(parabola[0].len() > 41);
+    assert!(parabola[0].windows(2).all(|w| w[0].0 < w[1].0));
+    // 1/x is undefined at 0 on the grid and tan has a pole between grid points
+    assert_eq!(sample_of("\\frac{1}{x}", -1.0, 1.0).len(), 2);
+    assert_eq!(sample_of("\\tan(x)", 0.0, 3.0).len(), 2);
+    assert_eq!(
+        sample_of("\\operatorname{sgn}(x-\\frac{1}{3})", -1.0, 1.0).len(),
+        2
+    );
+    // Steps smaller than the scale of the curve still split it
+    let sign = sample_of("\\operatorname{sgn}(x)", -2.0, 2.0);
+    assert_eq!(sign.len(), 3);
+    assert_eq!(sign[1], vec![(0.0, 0.0)]);
+    let step = sample_of(
+        "\\begin{cases}0&x<0\\\\1&\\text{otherwise}\\end{cases}",
+        -1.0,
+        1.0,
+    );
+    assert_eq!(step.len(), 2);
+    let stairs = sample_of(
+        "\\begin{cases}0&x<0\\\\1&x<1\\\\2&\\text{otherwise}\\end{cases}",
+        -1.0,
+        2.0,
+    );
+    assert_eq!(stairs.len(), 3);
+    assert!(stairs
+        .iter()
+        .all(|s| s.windows(2).all(|w| w[0].1 == w[1].1)));
+    let root = sample_of("\\sqrt{x}", -1.0, 1.0);
+    assert_eq!(root.len(), 1);
+    assert!(root[0][0].0 < 0.01);
+}
@@ -1,8 +1,9 @@
-use super::Expr;
+use super::limit::infinity;
+use super::{Expr, Matrix, Mul, Num};
 use std::fmt::{Display, Formatter, Result};
 use std::ops;
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Add<'a> {
     pub exprs: Vec<Expr<'a>>,
 }
@@ -14,7 +15,7 @@ impl<'a> Add<'a> {
                 .into_iter()
                 .filter(|x| {
                     if let Expr::Num(n) = x {
-                        n.num != 0
+                        !n.is_zero()
                     } else {
                         true
                     }
@@ -23,32 +24,96 @@ impl<'a> Add<'a> {
         }
     }
 
-    /// Add(x1,x1,num1,x3)->Add(Multi(2,x1),num1,x3) otherwise crash
-    /// multi expr will be collected beforehand
-    /// depend on col_multi
-    pub fn collect(&self) -> Self {
-        let mut result = vec![self.exprs[0].collect().clone()];
-        (1..self.exprs.len()).for_each(|i| {
-            for j in 0..result.len() {
-                let (co1, body1) = self.exprs[i].collect().detach_coeff();
-                let (co2, body2) = result[j].collect().detach_coeff();
-                if body1 == body2 {
-                    match co1 + co2 {
-                        x if x.num == 0 => {
-                            result.remove(j);
-                        }
-                        x => result[j] = Expr::Mul(x * body1.clone()),
-                    };
-                    break;
-                } else if j == result.len() - 1 {
-                    result.push(Expr::Mul(co1 * body1.clone()));
-                }
-            }
+    /// Add(x1,x1,num1,x3)->Add(Mul(2,x1),num1,x3), nested Add is flattened
+    /// terms are compared regardless of the order of their factors
+    /// and stay apart when adding their coefficients overflows or gives \infty-\infty
+    /// depend on Mul::collect
+    pub fn collect(&self) -> Expr<'a> {
+        let mut exprs = vec![];
+        self.exprs.iter().for_each(|expr| match expr.collect() {
+            Expr::Add(add) => exprs.extend(add.exprs),
+            x => exprs.push(x),
         });
-        Add::new(result)
+        if let Some(sum) = Add::sum_matrices(&exprs) {
+            return sum;
+        }
+        let mut terms: Vec<(Num, Mul<'a>)> = vec![];
+        for expr in exprs {
+            let (co1, body1) = expr.detach_coeff();
+            let opposite =
+                |co2: &Num| is_infinite(&body1) && co1.is_negative() != co2.is_negative();
+            match terms.iter_mut().find(|(co2, body2)| {
+                body1.eq_unordered(body2) && co2.checked_add(co1).is_some() && !opposite(co2)
+            }) {
+                Some((co2, _)) => *co2 = *co2 + co1,
+                None => terms.push((co1, body1)),
+            }
+        }
+        Add::new(
+            Add::absorb_infinity(terms)
+                .into_iter()
+                .filter(|(co, _)| !co.is_zero())
+                .map(|(co, body)| (co * body).to_expr())
+                .collect(),
+        )
+        .to_expr()
+    }
+
+    /// c*\infty->sign(c)*\infty which absorbs real numbers unless \infty-\infty is left
+    fn absorb_infinity(terms: Vec<(Num, Mul<'a>)>) -> Vec<(Num, Mul<'a>)> {
+        let infinite = |(co, body): &(Num, Mul)| co.is_real() && is_infinite(body);
+        let signs: Vec<i64> = terms
+            .iter()
+            .filter(|t| infinite(t))
+            .map(|(co, _)| co.num.signum())
+            .collect();
+        terms
+            .into_iter()
+            .filter(|(co, body)| signs.len() != 1 || !body.exprs.is_empty() || !co.is_real())
+            .map(|(co, body)| match infinite(&(co, body.clone())) {
+                true => (Num::new(co.num.signum()), body),
+                false => (co, body),
+            })
+            .collect()
+    }
+
+    /// Sum of collected terms if they are matrices, left unevaluated
+    /// when shapes differ or matrices and scalars are mixed
+    fn sum_matrices(exprs: &[Expr<'a>]) -> Option<Expr<'a>> {
+        let is_matrix = |e: &Expr| matches!(e, Expr::Matrix(m) if !m.is_det());
+        if !exprs.iter().any(is_matrix) {
+            return None;
+        }
+        let sum = exprs
+            .iter()
+            .try_fold(None, |acc: Option<Matrix>, expr| match expr {
+                Expr::Matrix(m) if !m.is_det() => match acc {
+                    Some(acc) => acc.add(m).map(Some),
+                    None => Some(Some(m.clone())),
+                },
+                Expr::Num(n) if n.is_zero() => Some(acc),
+                _ => None,
+            });
+        Some(match sum {
+            Some(Some(matrix)) => Expr::Matrix(matrix),
+            _ => Expr::Add(Add::new(exprs.to_vec())),
+        })
+    }
+
+    /// Add()->0, Add(x)->x otherwise Add(x1,...,xn)
+    pub fn to_expr(self) -> Expr<'a> {
+        match self.exprs.len() {
+            0 => Expr::Num(Num::new(0)),
+            1 => self.exprs[0].clone(),
+            _ => Expr::Add(self),
+        }
     }
 }
 
+fn is_infinite(body: &Mul) -> bool {
+    body.exprs == [infinity()]
+}
+
 impl<'a> ops::Add<Add<'a>> for Add<'a> {
     type Output = Add<'a>;
     fn add(self, mut _rhs: Add<'a>) -> Add<'a> {
@@ -58,6 +123,9 @@ impl<'a> ops::Add<Add<'a>> for Add<'a> {
 
 impl<'a> Display for Add<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.exprs.is_empty() {
+            return write!(f, "0");
+        }
         let mut result = self.exprs[0].to_string();
         for i in 1..self.exprs.len() {
             result = format!("{}+{}", result, self.exprs[i]);
@@ -95,5 +163,9 @@ mod test_add {
             "x+3*y^{2}"
         );
         assert_eq!((n2 * x + x + y).collect().to_string(), "3*x+y");
+        assert_eq!((x * y + n2 * (y * x)).collect().to_string(), "3*x*y");
+        assert_eq!((x + n2 + (y + n2)).collect().to_string(), "x+4+y");
+        assert_eq!((x + y + Num::new(-1) * x).collect().to_string(), "y");
+        assert_eq!((x + Num::new(-1) * x).collect().to_string(), "0");
     }
 }
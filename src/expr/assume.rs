@@ -0,0 +1,319 @@
+use super::func::call;
+use super::{Const, Expr, FuncName, Mul, Num, Pow, Sym};
+use std::cmp::Ordering;
+
+/// Property assumed for a symbol
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Property {
+    Positive,
+    Negative,
+    Nonzero,
+    Real,
+    Integer,
+}
+
+impl Property {
+    /// Whether self implies other
+    fn implies(&self, other: Property) -> bool {
+        use Property::*;
+        *self == other
+            || matches!(
+                (self, other),
+                (Positive | Negative, Nonzero | Real) | (Integer, Real)
+            )
+    }
+}
+
+/// Properties attached to symbols, where queries answer None when the property is unknown
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Assumptions<'a> {
+    facts: Vec<(Sym<'a>, Property)>,
+}
+
+impl<'a> Assumptions<'a> {
+    pub fn new() -> Self {
+        Assumptions { facts: vec![] }
+    }
+
+    pub fn assume(&mut self, sym: Sym<'a>, property: Property) {
+        self.facts.push((sym, property));
+    }
+
+    fn has(&self, sym: &Sym, property: Property) -> bool {
+        self.facts
+            .iter()
+            .any(|(x, p)| x == sym && p.implies(property))
+    }
+
+    /// Sign of a real expr compared to zero
+    pub fn sign(&self, expr: &Expr) -> Option<Ordering> {
+        match expr {
+            Expr::Num(n) if n.is_real() => Some(n.num.cmp(&0)),
+            Expr::Const(Const::Pi | Const::E) => Some(Ordering::Greater),
+            Expr::Sym(x) if self.has(x, Property::Positive) => Some(Ordering::Greater),
+            Expr::Sym(x) if self.has(x, Property::Negative) => Some(Ordering::Less),
+            // Zero terms do not change the sign of the others
+            Expr::Add(add) => {
+                add.exprs
+                    .iter()
+                    .try_fold(Ordering::Equal, |acc, e| match (acc, self.sign(e)?) {
+                        (acc, Ordering::Equal) => Some(acc),
+                        (Ordering::Equal, s) => Some(s),
+                        (acc, s) if acc == s => Some(acc),
+                        _ => None,
+                    })
+            }
+            Expr::Mul(mul) => {
+                mul.exprs
+                    .iter()
+                    .try_fold(Ordering::Greater, |acc, e| match (acc, self.sign(e)?) {
+                        (Ordering::Equal, _) | (_, Ordering::Equal) => Some(Ordering::Equal),
+                        (acc, s) if acc == s => Some(Ordering::Greater),
+                        _ => Some(Ordering::Less),
+                    })
+            }
+            Expr::Pow(pow) => match (self.sign(&pow.body), &*pow.pow) {
+                (Some(Ordering::Greater), exp) if self.is_real(exp) == Some(true) => {
+                    Some(Ordering::Greater)
+                }
+                (Some(Ordering::Less), Expr::Num(n)) if n.is_integer() => match n.num % 2 {
+                    0 => Some(Ordering::Greater),
+                    _ => Some(Ordering::Less),
+                },
+                (None, Expr::Num(n))
+                    if n.is_integer()
+                        && n.num % 2 == 0
+                        && self.is_real(&pow.body) == Some(true)
+                        && self.is_nonzero(&pow.body) == Some(true) =>
+                {
+                    Some(Ordering::Greater)
+                }
+                _ => None,
+            },
+            Expr::Func(func) => match func.name {
+                FuncName::Exp | FuncName::Cosh if self.is_real(&func.arg) == Some(true) => {
+                    Some(Ordering::Greater)
+                }
+                FuncName::Abs if self.is_nonzero(&func.arg) == Some(true) => {
+                    Some(Ordering::Greater)
+                }
+                FuncName::Sinh | FuncName::Tanh | FuncName::Arctan => self.sign(&func.arg),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn is_positive(&self, expr: &Expr) -> Option<bool> {
+        self.sign(expr).map(|s| s == Ordering::Greater)
+    }
+
+    pub fn is_negative(&self, expr: &Expr) -> Option<bool> {
+        self.sign(expr).map(|s| s == Ordering::Less)
+    }
+
+    pub fn is_nonzero(&self, expr: &Expr) -> Option<bool> {
+        match expr {
+            Expr::Num(n) => Some(!n.is_zero()),
+            Expr::Sym(x) if self.has(x, Property::Nonzero) => Some(true),
+            Expr::Mul(mul) => all(mul.exprs.iter().map(|e| self.is_nonzero(e))),
+            Expr::Pow(pow) => self.is_nonzero(&pow.body),
+            expr => self.sign(expr).map(|s| s != Ordering::Equal),
+        }
+    }
+
+    pub fn is_real(&self, expr: &Expr) -> Option<bool> {
+        match expr {
+            Expr::Num(n) => Some(n.is_real()),
+            Expr::Const(Const::Pi | Const::E) => Some(true),
+            Expr::Sym(x) if self.has(x, Property::Real) => Some(true),
+            Expr::Add(add) => all(add.exprs.iter().map(|e| self.is_real(e))),
+            Expr::Mul(mul) => all(mul.exprs.iter().map(|e| self.is_real(e))),
+            Expr::Pow(pow) => match &*pow.pow {
+                Expr::Num(n)
+                    if n.is_integer()
+                        && self.is_real(&pow.body) == Some(true)
+                        && (!n.is_negative() || self.is_nonzero(&pow.body) == Some(true)) =>
+                {
+                    Some(true)
+                }
+                exp if self.is_positive(&pow.body) == Some(true) => self.is_real(exp),
+                _ => None,
+            },
+            Expr::Func(func) => match func.name {
+                FuncName::Abs | FuncName::Re | FuncName::Im => Some(true),
+                FuncName::Ln if self.is_positive(&func.arg) == Some(true) => Some(true),
+                FuncName::Ln | FuncName::Arcsin | FuncName::Arccos => None,
+                FuncName::Tan | FuncName::Cot | FuncName::Sec | FuncName::Csc => None,
+                _ => self.is_real(&func.arg).filter(|real| *real),
+            },
+            _ => None,
+        }
+    }
+
+    pub fn is_integer(&self, expr: &Expr) -> Option<bool> {
+        match expr {
+            Expr::Num(n) => Some(n.is_integer()),
+            Expr::Const(Const::Pi | Const::E) => Some(false),
+            Expr::Sym(x) if self.has(x, Property::Integer) => Some(true),
+            Expr::Add(add) => all(add.exprs.iter().map(|e| self.is_integer(e))),
+            Expr::Mul(mul) => all(mul.exprs.iter().map(|e| self.is_integer(e))),
+            Expr::Pow(pow) => match &*pow.pow {
+                Expr::Num(n) if n.is_integer() && !n.is_negative() => {
+                    self.is_integer(&pow.body).filter(|integer| *integer)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Some(true) if every item is Some(true) otherwise None
+fn all(mut items: impl Iterator<Item = Option<bool>>) -> Option<bool> {
+    items.all(|x| x == Some(true)).then_some(true)
+}
+
+impl<'a> Expr<'a> {
+    /// Collect with rewrites that hold under assumptions such as \sqrt{x^{2}}->|x| for real x,
+    /// (x^{a})^{b}->x^{ab} and (xy)^{b}->x^{b}y^{b} for positive x, |x|->x for nonnegative x,
+    /// sign of expr of known sign and pieces of piecewise whose conditions are decided
+    pub fn refine(&self, assumptions: &Assumptions<'a>) -> Self {
+        // Collecting may expose new rewrites such as sgn(-x)->-sgn(x)
+        match self.refine_step(assumptions) {
+            refined if refined == *self => refined,
+            refined => refined.refine(assumptions),
+        }
+    }
+
+    fn refine_step(&self, assumptions: &Assumptions<'a>) -> Self {
+        let expr = self.map(|e| e.refine(assumptions)).collect();
+        let is = |answer: Option<bool>| answer == Some(true);
+        match &expr {
+            Expr::Pow(pow) => match (&*pow.body, &*pow.pow) {
+                (Expr::Pow(inner), b) if is(assumptions.is_real(b)) => {
+                    let (x, a) = (&*inner.body, &*inner.pow);
+                    let even = matches!(a, Expr::Num(n) if n.is_integer() && n.num % 2 == 0);
+                    if is(assumptions.is_positive(x)) && is(assumptions.is_real(a)) {
+                        Pow::new(x.clone(), a.clone() * b.clone()).collect()
+                    } else if even && is(assumptions.is_real(x)) {
+                        let abs = call(FuncName::Abs, x.clone());
+                        Expr::Pow(Pow::new(abs, a.clone() * b.clone())).refine(assumptions)
+                    } else {
+                        expr
+                    }
+                }
+                // (-1)^{k} for integer k of known parity
+                (Expr::Num(m), k) if *m == Num::new(-1) => {
+                    let half = |k: Expr<'a>| (k * Expr::Num(Num::frac(1, 2))).expand();
+                    if is(assumptions.is_integer(&half(k.clone()))) {
+                        Expr::Num(Num::new(1))
+                    } else if is(assumptions.is_integer(&half(k.clone() - Expr::Num(Num::new(1)))))
+                    {
+                        Expr::Num(Num::new(-1))
+                    } else {
+                        expr
+                    }
+                }
+                (Expr::Mul(mul), b) if is(assumptions.is_real(b)) => {
+                    let (positive, rest): (Vec<_>, Vec<_>) = mul
+                        .exprs
+                        .iter()
+                        .cloned()
+                        .partition(|e| is(assumptions.is_positive(e)));
+                    if positive.is_empty() {
+                        return expr;
+                    }
+                    let power = |e: Expr<'a>| Expr::Pow(Pow::new(e, b.clone()));
+                    let rest = power(Mul::new(rest).to_expr());
+                    Mul::new(positive.into_iter().map(power).chain([rest]).collect()).collect()
+                }
+                _ => expr,
+            },
+            Expr::Func(func) => {
+                let arg = &*func.arg;
+                match func.name {
+                    FuncName::Abs => match assumptions.sign(arg) {
+                        Some(Ordering::Less) => (-arg.clone()).collect(),
+                        Some(_) => arg.clone(),
+                        None => expr,
+                    },
+                    FuncName::Sign => match assumptions.sign(arg) {
+                        Some(sign) => Expr::Num(Num::new(sign as i64)),
+                        None => expr,
+                    },
+                    // ln(e^{x})=x for real x
+                    FuncName::Ln => match arg {
+                        Expr::Pow(pow)
+                            if *pow.body == Expr::Const(Const::E)
+                                && is(assumptions.is_real(&pow.pow)) =>
+                        {
+                            *pow.pow.clone()
+                        }
+                        Expr::Func(exp)
+                            if exp.name == FuncName::Exp && is(assumptions.is_real(&exp.arg)) =>
+                        {
+                            *exp.arg.clone()
+                        }
+                        _ => expr,
+                    },
+                    FuncName::Re | FuncName::Conj if is(assumptions.is_real(arg)) => arg.clone(),
+                    FuncName::Im if is(assumptions.is_real(arg)) => Expr::Num(Num::new(0)),
+                    // sin(k\pi)=0 and cos(k\pi)=(-1)^{k} for integer k
+                    FuncName::Sin | FuncName::Cos => {
+                        let k = (arg.clone() / Expr::Const(Const::Pi)).collect();
+                        match (func.name, is(assumptions.is_integer(&k))) {
+                            (FuncName::Sin, true) => Expr::Num(Num::new(0)),
+                            (_, true) => {
+                                Expr::Pow(Pow::new(Expr::Num(Num::new(-1)), k)).refine(assumptions)
+                            }
+                            _ => expr,
+                        }
+                    }
+                    _ => expr,
+                }
+            }
+            Expr::Piecewise(piecewise) => piecewise.simplify(assumptions),
+            _ => expr,
+        }
+    }
+}
+
+#[test]
+fn test_assume() {
+    use crate::parser::latex_to_expr;
+    let (x, n) = (Sym::new("x"), Sym::new("n"));
+    let mut assumptions = Assumptions::new();
+    assumptions.assume(x, Property::Positive);
+    assumptions.assume(n, Property::Integer);
+    assumptions.assume(Sym::new("a"), Property::Real);
+    assumptions.assume(Sym::new("b"), Property::Real);
+    let query = |latex: &str| {
+        let expr = latex_to_expr(latex);
+        (
+            assumptions.is_positive(&expr),
+            assumptions.is_real(&expr),
+            assumptions.is_integer(&expr),
+        )
+    };
+    assert_eq!(query("x^{2}+1"), (Some(true), Some(true), None));
+    assert_eq!(query("-2x"), (Some(false), Some(true), None));
+    assert_eq!(query("2n+1"), (None, Some(true), Some(true)));
+    assert_eq!(query("y"), (None, None, None));
+    assert_eq!(query("\\sqrt{x}\\pi"), (Some(true), Some(true), None));
+
+    let refine = |latex: &str| latex_to_expr(latex).refine(&assumptions).to_string();
+    assert_eq!(refine("\\sqrt{x^{2}}"), "x");
+    assert_eq!(refine("\\sqrt{n^{2}}"), "|n|");
+    assert_eq!(refine("\\sqrt{y^{2}}"), "(y^{2})^{1/2}");
+    assert_eq!(refine("(x^{a})^{b}"), "x^{a*b}");
+    assert_eq!(refine("(y^{a})^{b}"), "(y^{a})^{b}");
+    assert_eq!(refine("\\sqrt{4xy}"), "2*x^{1/2}*y^{1/2}");
+    assert_eq!(refine("\\sin(n\\pi)+\\cos(2n\\pi)"), "1");
+    assert_eq!(refine("\\Re(x)"), "x");
+    assert_eq!(refine("\\ln e^{a}+\\ln(\\exp{2b})"), "a+2*b");
+    assert_eq!(refine("\\ln e^{y}"), "ln(e^{y})");
+    assert_eq!(refine("\\cos((2n+1)\\pi)"), "-1");
+    assert_eq!(refine("|-x|"), "x");
+    assert_eq!(refine("\\operatorname{sgn}(-\\pi x)"), "-1");
+}
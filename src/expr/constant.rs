@@ -0,0 +1,149 @@
+use std::fmt::{Display, Formatter, Result};
+
+/// Mathematical constant where E is Euler's number
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Const {
+    Pi,
+    E,
+    Infinity,
+}
+
+impl Const {
+    pub fn from_command(command: &str) -> Option<Self> {
+        match command {
+            "\\pi" => Some(Const::Pi),
+            "e" => Some(Const::E),
+            "\\infty" => Some(Const::Infinity),
+            _ => None,
+        }
+    }
+
+    pub fn command(&self) -> &'static str {
+        match self {
+            Const::Pi => "\\pi",
+            Const::E => "e",
+            Const::Infinity => "\\infty",
+        }
+    }
+
+    pub fn value(&self) -> f64 {
+        match self {
+            Const::Pi => std::f64::consts::PI,
+            Const::E => std::f64::consts::E,
+            Const::Infinity => f64::INFINITY,
+        }
+    }
+
+    /// Decimal expansion truncated to n digits after the point by a spigot algorithm
+    pub fn digits(&self, n: usize) -> std::result::Result<String, &'static str> {
+        // Guard digits settle the carries of the last requested digits
+        let digits = match self {
+            Const::Pi => pi_digits(n + 3),
+            Const::E => e_digits(n + 3),
+            Const::Infinity => return Err("Infinity has no decimal expansion"),
+        };
+        let digits: String = digits[..=n].iter().map(|d| d.to_string()).collect();
+        match n {
+            0 => Ok(digits),
+            _ => Ok(format!("{}.{}", &digits[..1], &digits[1..])),
+        }
+    }
+}
+
+/// First n decimal digits of pi by the Rabinowitz-Wagon spigot
+fn pi_digits(n: usize) -> Vec<u64> {
+    let len = 10 * n / 3 + 1;
+    let mut a = vec![2u64; len];
+    let mut digits = vec![];
+    let (mut predigit, mut nines) = (0, 0);
+    for j in 0..n {
+        let mut q = 0;
+        for i in (1..=len).rev() {
+            let x = 10 * a[i - 1] + q * i as u64;
+            a[i - 1] = x % (2 * i as u64 - 1);
+            q = x / (2 * i as u64 - 1);
+        }
+        a[0] = q % 10;
+        q /= 10;
+        match q {
+            9 => nines += 1,
+            10 => {
+                digits.push(predigit + 1);
+                digits.extend(std::iter::repeat_n(0, nines));
+                (predigit, nines) = (0, 0);
+            }
+            q => {
+                if j > 0 {
+                    digits.push(predigit);
+                }
+                digits.extend(std::iter::repeat_n(9, nines));
+                (predigit, nines) = (q, 0);
+            }
+        }
+    }
+    digits.push(predigit);
+    digits.extend(std::iter::repeat_n(9, nines));
+    digits
+}
+
+/// First n decimal digits of e=2+1/2!+1/3!+... by its mixed radix spigot
+fn e_digits(n: usize) -> Vec<u64> {
+    // len! must exceed 10^n
+    let (mut len, mut log) = (2, 0.0);
+    while log < n as f64 + 1.0 {
+        len += 1;
+        log += (len as f64).log10();
+    }
+    let mut a = vec![1u64; len];
+    let mut digits = vec![2];
+    for _ in 1..n {
+        let mut carry = 0;
+        for i in (0..len).rev() {
+            let x = 10 * a[i] + carry;
+            a[i] = x % (i as u64 + 2);
+            carry = x / (i as u64 + 2);
+        }
+        digits.push(carry);
+    }
+    digits
+}
+
+impl Display for Const {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.command())
+    }
+}
+
+#[test]
+fn test_digits() {
+    let pi = "3.14159265358979323846264338327950288419716939937510582097494459";
+    assert_eq!(Const::Pi.digits(62).unwrap(), pi);
+    let e = "2.71828182845904523536028747135266249775724709369995957496696762";
+    assert_eq!(Const::E.digits(62).unwrap(), e);
+    assert_eq!(Const::Pi.digits(0).unwrap(), "3");
+    assert_eq!(Const::E.digits(3).unwrap(), "2.718");
+    assert!(Const::Infinity.digits(3).is_err());
+}
+
+#[test]
+fn test_infinity() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["\\infty-\\infty", "\\infty+-1*\\infty"],
+        ["\\infty+\\infty+1", "\\infty"],
+        ["2\\infty-3", "\\infty"],
+        ["-\\infty+\\pi", "-1*\\infty+\\pi"],
+        ["0\\infty", "0*\\infty"],
+        ["\\infty\\infty", "\\infty"],
+        ["\\infty^{0}", "\\infty^{0}"],
+        ["1^{\\infty}", "1^{\\infty}"],
+        ["\\frac{1}{\\infty}", "0"],
+        ["\\infty^{\\frac{1}{2}}", "\\infty"],
+        ["\\left(\\frac{1}{2}\\right)^{\\infty}", "0"],
+        ["2^{\\infty}", "\\infty"],
+        ["|e|+|\\pi|+|-\\infty|", "e+\\pi+\\infty"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+}
@@ -0,0 +1,107 @@
+use super::{Add, Expr, FuncName, Mul, Num, Piecewise, Pow, Sum, SumOp, Sym};
+
+impl<'a> Expr<'a> {
+    /// Derivative with respect to var, collected
+    pub fn diff(&self, var: Sym<'a>) -> Self {
+        self.derivative(var).collect()
+    }
+
+    fn derivative(&self, var: Sym<'a>) -> Self {
+        match self {
+            Expr::Matrix(matrix) => Expr::Matrix(matrix.map(|e| e.diff(var))),
+            x if !x.contains(&var) => Expr::Num(Num::new(0)),
+            Expr::Sym(_) => Expr::Num(Num::new(1)),
+            Expr::Add(add) => Expr::Add(Add::new(
+                add.exprs.iter().map(|e| e.derivative(var)).collect(),
+            )),
+            Expr::Mul(mul) => {
+                let terms = (0..mul.exprs.len())
+                    .filter(|i| mul.exprs[*i].contains(&var))
+                    .map(|i| {
+                        let mut factors = mul.exprs.clone();
+                        factors[i] = factors[i].derivative(var);
+                        Expr::Mul(Mul::new(factors))
+                    })
+                    .collect();
+                Expr::Add(Add::new(terms))
+            }
+            // (b^e)'=e*b^{e-1}*b' when e is constant otherwise b^e*(e'*ln(b)+e*b'/b)
+            Expr::Pow(pow) if !pow.pow.contains(&var) => {
+                let (body, exp) = (*pow.body.clone(), *pow.pow.clone());
+                let lowered = Pow::new(body.clone(), exp.clone() - Expr::Num(Num::new(1)));
+                exp * Expr::Pow(lowered) * body.derivative(var)
+            }
+            Expr::Pow(pow) => {
+                let (body, exp) = (*pow.body.clone(), *pow.pow.clone());
+                let ln = super::func::call(FuncName::Ln, body.clone());
+                let inner = exp.derivative(var) * ln + exp * body.derivative(var) / body;
+                self.clone() * inner
+            }
+            // ln|u|'=u'/u
+            Expr::Func(func) if func.name == FuncName::Ln => match &*func.arg {
+                Expr::Func(abs) if abs.name == FuncName::Abs => {
+                    abs.arg.derivative(var) / *abs.arg.clone()
+                }
+                arg => func.derivative() * arg.derivative(var),
+            },
+            Expr::Func(func) if func.name.is_real_linear() => {
+                super::func::call(func.name, func.arg.derivative(var))
+            }
+            Expr::Func(func) => func.derivative() * func.arg.derivative(var),
+            Expr::Integral(integral) => integral.derivative(var),
+            // Bounds are free of var since var is not bound by the sum
+            Expr::Sum(sum) if !sum.lower.contains(&var) && !sum.upper.contains(&var) => {
+                match sum.op {
+                    SumOp::Sum => Expr::Sum(Sum::new(
+                        sum.op,
+                        sum.body.derivative(var),
+                        sum.var,
+                        *sum.lower.clone(),
+                        *sum.upper.clone(),
+                    )),
+                    SumOp::Product => match sum.collect() {
+                        Expr::Sum(_) => panic!("Cannot differentiate unevaluated product"),
+                        x => x.derivative(var),
+                    },
+                }
+            }
+            Expr::Sum(sum) => match sum.collect() {
+                Expr::Sum(_) => panic!("Cannot differentiate unevaluated sum"),
+                x => x.derivative(var),
+            },
+            // Derivative of each piece away from the boundaries
+            Expr::Piecewise(piecewise) => Expr::Piecewise(Piecewise::new(
+                piecewise
+                    .pieces
+                    .iter()
+                    .map(|(value, cond)| (value.derivative(var), cond.clone()))
+                    .collect(),
+            )),
+            Expr::Limit(limit) => match limit.collect() {
+                Expr::Limit(_) => panic!("Cannot differentiate unevaluated limit"),
+                x => x.derivative(var),
+            },
+            Expr::Num(_) | Expr::Const(_) => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn test_diff() {
+    use crate::parser::latex_to_expr;
+    let x = Sym::new("x");
+    let tests = [
+        ["x^{3}+2x+1", "3*x^{2}+2"],
+        ["ax^{2}", "2*a*x"],
+        ["\\frac{1}{x}", "-1*x^{-2}"],
+        ["x\\sin x", "sin(x)+x*cos(x)"],
+        ["\\cos 2x", "-2*sin(2*x)"],
+        ["\\ln x", "x^{-1}"],
+        ["\\exp{x^{2}}", "2*exp(x^{2})*x"],
+        ["2^{x}", "2^{x}*ln(2)"],
+        ["\\int_{0}^{x}t^{2}dt", "x^{2}"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).diff(x).to_string(), test[1]);
+    });
+}
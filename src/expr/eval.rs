@@ -0,0 +1,97 @@
+use super::{Expr, FuncName};
+
+impl<'a> Expr<'a> {
+    /// Numeric value of a real expr without free symbols
+    pub fn eval(&self) -> Result<f64, &'static str> {
+        match self {
+            Expr::Num(n) if n.is_real() => Ok(n.num as f64 / n.den as f64),
+            Expr::Num(_) => Err("Cannot evaluate complex number"),
+            Expr::Const(c) => Ok(c.value()),
+            Expr::Sym(_) => Err("Cannot evaluate symbol"),
+            Expr::Add(add) => add.exprs.iter().try_fold(0.0, |acc, e| Ok(acc + e.eval()?)),
+            Expr::Mul(mul) => mul.exprs.iter().try_fold(1.0, |acc, e| Ok(acc * e.eval()?)),
+            Expr::Pow(pow) => {
+                let (body, exp) = (pow.body.eval()?, pow.pow.eval()?);
+                match body.powf(exp) {
+                    x if x.is_nan() => Err("Cannot evaluate complex power"),
+                    x => Ok(x),
+                }
+            }
+            Expr::Func(func) => {
+                let x = func.arg.eval()?;
+                let value = match func.name {
+                    FuncName::Sin => x.sin(),
+                    FuncName::Cos => x.cos(),
+                    FuncName::Tan => x.tan(),
+                    FuncName::Cot => x.tan().recip(),
+                    FuncName::Sec => x.cos().recip(),
+                    FuncName::Csc => x.sin().recip(),
+                    FuncName::Arcsin => x.asin(),
+                    FuncName::Arccos => x.acos(),
+                    FuncName::Arctan => x.atan(),
+                    FuncName::Sinh => x.sinh(),
+                    FuncName::Cosh => x.cosh(),
+                    FuncName::Tanh => x.tanh(),
+                    FuncName::Exp => x.exp(),
+                    FuncName::Ln => x.ln(),
+                    FuncName::Abs => x.abs(),
+                    FuncName::Sign if x == 0.0 => 0.0,
+                    FuncName::Sign => x.signum(),
+                    FuncName::Re | FuncName::Conj => x,
+                    FuncName::Im => 0.0,
+                };
+                match value {
+                    x if x.is_nan() => Err("Argument is out of domain"),
+                    x => Ok(x),
+                }
+            }
+            Expr::Matrix(_) => Err("Cannot evaluate matrix"),
+            Expr::Piecewise(piecewise) => {
+                for (value, cond) in &piecewise.pieces {
+                    let holds = match cond {
+                        Some(c) => match (c.lhs.eval()? - c.rhs.eval()?).partial_cmp(&0.0) {
+                            Some(sign) => c.cmp.accepts(sign),
+                            None => return Err("Cannot compare NaN"),
+                        },
+                        None => true,
+                    };
+                    if holds {
+                        return value.eval();
+                    }
+                }
+                Err("No piece of piecewise holds")
+            }
+            // Integrals, limits and sums are evaluated if they have closed forms
+            x => match x.collect() {
+                Expr::Integral(_) | Expr::Limit(_) | Expr::Sum(_) => {
+                    Err("Cannot evaluate unevaluated expression")
+                }
+                x => x.eval(),
+            },
+        }
+    }
+}
+
+#[test]
+fn test_eval() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["\\frac{1}{4}", "0.25"],
+        ["2\\pi", "6.283185307179586"],
+        ["e^{2}", "7.3890560989306495"],
+        ["\\sin\\left(\\frac{\\pi}{2}\\right)+\\ln e", "2"],
+        ["\\arctan 1", "0.7853981633974483"],
+        ["2^{\\frac{1}{2}}", "1.4142135623730951"],
+        ["\\left|1-\\pi\\right|", "2.141592653589793"],
+        [
+            "\\begin{cases}1&\\pi>3\\\\0&\\text{otherwise}\\end{cases}",
+            "1",
+        ],
+        ["\\int_{0}^{1}x^{2}dx", "0.3333333333333333"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).eval().unwrap().to_string(), test[1]);
+    });
+    assert!(latex_to_expr("x+1").eval().is_err());
+    assert!(latex_to_expr("\\ln(-1)").eval().is_err());
+}
@@ -0,0 +1,436 @@
+use super::{Add, Assumptions, Const, Expr, Mul, Num, Pow};
+use std::fmt::{Display, Formatter, Result};
+
+/// Elementary function where Ln is the natural logarithm,
+/// or real part, imaginary part and complex conjugate
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum FuncName {
+    Sin,
+    Cos,
+    Tan,
+    Cot,
+    Sec,
+    Csc,
+    Arcsin,
+    Arccos,
+    Arctan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Abs,
+    Sign,
+    Re,
+    Im,
+    Conj,
+}
+
+impl FuncName {
+    /// LaTeX command to FuncName where \log is regarded as \ln
+    pub fn from_command(command: &str) -> Option<Self> {
+        match command {
+            "\\sin" => Some(FuncName::Sin),
+            "\\cos" => Some(FuncName::Cos),
+            "\\tan" => Some(FuncName::Tan),
+            "\\cot" => Some(FuncName::Cot),
+            "\\sec" => Some(FuncName::Sec),
+            "\\csc" => Some(FuncName::Csc),
+            "\\arcsin" => Some(FuncName::Arcsin),
+            "\\arccos" => Some(FuncName::Arccos),
+            "\\arctan" => Some(FuncName::Arctan),
+            "\\sinh" => Some(FuncName::Sinh),
+            "\\cosh" => Some(FuncName::Cosh),
+            "\\tanh" => Some(FuncName::Tanh),
+            "\\exp" => Some(FuncName::Exp),
+            "\\ln" | "\\log" => Some(FuncName::Ln),
+            "\\sgn" => Some(FuncName::Sign),
+            "\\Re" => Some(FuncName::Re),
+            "\\Im" => Some(FuncName::Im),
+            "\\overline" | "\\bar" => Some(FuncName::Conj),
+            _ => None,
+        }
+    }
+
+    /// Abs is written as \left|x\right| and Conj as \overline{x} instead
+    pub fn command(&self) -> &'static str {
+        match self {
+            FuncName::Sin => "\\sin",
+            FuncName::Cos => "\\cos",
+            FuncName::Tan => "\\tan",
+            FuncName::Cot => "\\cot",
+            FuncName::Sec => "\\sec",
+            FuncName::Csc => "\\csc",
+            FuncName::Arcsin => "\\arcsin",
+            FuncName::Arccos => "\\arccos",
+            FuncName::Arctan => "\\arctan",
+            FuncName::Sinh => "\\sinh",
+            FuncName::Cosh => "\\cosh",
+            FuncName::Tanh => "\\tanh",
+            FuncName::Exp => "\\exp",
+            FuncName::Ln => "\\ln",
+            FuncName::Abs => "\\abs",
+            FuncName::Sign => "\\operatorname{sgn}",
+            FuncName::Re => "\\Re",
+            FuncName::Im => "\\Im",
+            FuncName::Conj => "\\overline",
+        }
+    }
+
+    /// Re, Im and conj commute with differentiation and integration in a real variable
+    pub fn is_real_linear(&self) -> bool {
+        matches!(self, FuncName::Re | FuncName::Im | FuncName::Conj)
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Func<'a> {
+    pub name: FuncName,
+    pub arg: Box<Expr<'a>>,
+}
+
+impl<'a> Func<'a> {
+    pub fn new(name: FuncName, arg: Expr<'a>) -> Self {
+        Func {
+            name,
+            arg: Box::new(arg),
+        }
+    }
+
+    /// Collect arg and evaluate at trivial points such as sin(0)->0 and ln(1)->0,
+    /// where Re, Im and conj are also distributed over sums
+    pub fn collect(&self) -> Expr<'a> {
+        let arg = self.arg.collect();
+        let value = match (self.name, &arg) {
+            (FuncName::Sin | FuncName::Tan | FuncName::Sinh | FuncName::Tanh, Expr::Num(n))
+            | (FuncName::Arcsin | FuncName::Arctan, Expr::Num(n))
+                if n.is_zero() =>
+            {
+                Some(Num::new(0))
+            }
+            (FuncName::Cos | FuncName::Sec | FuncName::Cosh | FuncName::Exp, Expr::Num(n))
+                if n.is_zero() =>
+            {
+                Some(Num::new(1))
+            }
+            (FuncName::Ln, Expr::Num(n)) if n.is_one() => Some(Num::new(0)),
+            (FuncName::Abs, Expr::Num(n)) => n.modulus(),
+            (FuncName::Sign, Expr::Num(n)) if n.is_real() => Some(Num::new(n.num.signum())),
+            (FuncName::Re, Expr::Num(n)) => Some(n.re()),
+            (FuncName::Im, Expr::Num(n)) => Some(n.im()),
+            (FuncName::Conj, Expr::Num(n)) => Some(n.conj()),
+            _ => None,
+        };
+        match value {
+            Some(n) => Expr::Num(n),
+            None => match self.exact(&arg) {
+                Some(exact) => exact.collect(),
+                None => match self.parity(&arg) {
+                    Some(reflected) => reflected.collect(),
+                    None => self.collect_complex(arg),
+                },
+            },
+        }
+    }
+
+    /// f(-x)->-f(x) for odd f and f(-x)->f(x) for even f
+    fn parity(&self, arg: &Expr<'a>) -> Option<Expr<'a>> {
+        let odd = match self.name {
+            FuncName::Sin | FuncName::Tan | FuncName::Cot | FuncName::Csc | FuncName::Sign => true,
+            FuncName::Arcsin | FuncName::Arctan | FuncName::Sinh | FuncName::Tanh => true,
+            FuncName::Cos | FuncName::Sec | FuncName::Cosh | FuncName::Abs => false,
+            _ => return None,
+        };
+        match arg.detach_coeff().0 {
+            c if c.is_negative() => {
+                let reflected = call(self.name, -arg.clone());
+                Some(if odd { -reflected } else { reflected })
+            }
+            _ => None,
+        }
+    }
+
+    /// Exact values such as ln(e)->1, exp(1)->e, exp(ln(x))->x, sin(\pi/6)->1/2
+    /// and arctan(1)->\pi/4
+    fn exact(&self, arg: &Expr<'a>) -> Option<Expr<'a>> {
+        let e = Expr::Const(Const::E);
+        let is_real = |x: &Expr| Assumptions::new().is_real(x) == Some(true);
+        match (self.name, arg) {
+            (FuncName::Ln, x) if *x == e => Some(Expr::Num(Num::new(1))),
+            // ln(e^{x})=x only holds for real x, see refine for assumed real symbols
+            (FuncName::Ln, Expr::Pow(pow)) if *pow.body == e && is_real(&pow.pow) => {
+                Some(*pow.pow.clone())
+            }
+            (FuncName::Ln, Expr::Func(func))
+                if func.name == FuncName::Exp && is_real(&func.arg) =>
+            {
+                Some(*func.arg.clone())
+            }
+            (FuncName::Exp, Expr::Num(n)) if n.is_one() => Some(e),
+            (FuncName::Exp, Expr::Func(func)) if func.name == FuncName::Ln => {
+                Some(*func.arg.clone())
+            }
+            (FuncName::Re | FuncName::Conj | FuncName::Abs, Expr::Const(c)) => {
+                Some(Expr::Const(*c))
+            }
+            (FuncName::Im, Expr::Const(_)) => Some(Expr::Num(Num::new(0))),
+            (name, Expr::Num(n)) => {
+                let q = inverse_trig(name, *n)?;
+                Some(Expr::Num(q) * Expr::Const(Const::Pi))
+            }
+            (name, arg) => {
+                let q = pi_multiple(arg)?;
+                let (sin, cos) = (sin_pi(q)?, sin_pi(q + Num::frac(1, 2))?);
+                let is_zero = |e: &Expr| matches!(e, Expr::Num(n) if n.is_zero());
+                match name {
+                    FuncName::Sin => Some(sin),
+                    FuncName::Cos => Some(cos),
+                    FuncName::Tan if !is_zero(&cos) => Some(sin / cos),
+                    FuncName::Cot if !is_zero(&sin) => Some(cos / sin),
+                    FuncName::Sec if !is_zero(&cos) => Some(Expr::Num(Num::new(1)) / cos),
+                    FuncName::Csc if !is_zero(&sin) => Some(Expr::Num(Num::new(1)) / sin),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// |z|=\sqrt{z\overline{z}} for numeric z, conj of products and integer powers
+    /// and Re, Im and conj of sums and conjugates
+    fn collect_complex(&self, arg: Expr<'a>) -> Expr<'a> {
+        let distribute = |exprs: &[Expr<'a>], name: FuncName| {
+            exprs.iter().map(|e| call(name, e.clone())).collect()
+        };
+        match (self.name, arg) {
+            (FuncName::Abs, Expr::Num(n)) => {
+                Pow::new(Expr::Num(n.norm()), Expr::Num(Num::frac(1, 2))).collect()
+            }
+            (FuncName::Re | FuncName::Im | FuncName::Conj, Expr::Add(add)) => {
+                Expr::Add(Add::new(distribute(&add.exprs, self.name))).collect()
+            }
+            (FuncName::Conj, Expr::Mul(mul)) => {
+                Expr::Mul(Mul::new(distribute(&mul.exprs, self.name))).collect()
+            }
+            (FuncName::Conj, Expr::Pow(pow)) if matches!(*pow.pow, Expr::Num(n) if n.is_integer()) => {
+                Pow::new(call(FuncName::Conj, *pow.body), *pow.pow).collect()
+            }
+            (FuncName::Conj, Expr::Func(func)) if func.name == FuncName::Conj => *func.arg,
+            (FuncName::Re | FuncName::Abs, Expr::Func(func)) if func.name == FuncName::Conj => {
+                Func::new(self.name, *func.arg).collect()
+            }
+            (FuncName::Im, Expr::Func(func)) if func.name == FuncName::Conj => {
+                -Func::new(FuncName::Im, *func.arg).collect()
+            }
+            (name, arg) => Expr::Func(Func::new(name, arg)),
+        }
+    }
+}
+
+impl<'a> Func<'a> {
+    /// f'(u) of f(u) without the factor u'
+    pub fn derivative(&self) -> Expr<'a> {
+        let u = *self.arg.clone();
+        let one = || Expr::Num(Num::new(1));
+        let square = |e: Expr<'a>| e ^ Expr::Num(Num::new(2));
+        let sqrt_recip = |e: Expr<'a>| Expr::Pow(Pow::new(e, Expr::Num(Num::frac(-1, 2))));
+        match self.name {
+            FuncName::Sin => call(FuncName::Cos, u),
+            FuncName::Cos => -call(FuncName::Sin, u),
+            FuncName::Tan => call(FuncName::Cos, u) ^ Expr::Num(Num::new(-2)),
+            FuncName::Cot => -(call(FuncName::Sin, u) ^ Expr::Num(Num::new(-2))),
+            FuncName::Sec => call(FuncName::Sec, u.clone()) * call(FuncName::Tan, u),
+            FuncName::Csc => -(call(FuncName::Csc, u.clone()) * call(FuncName::Cot, u)),
+            FuncName::Arcsin => sqrt_recip(one() - square(u)),
+            FuncName::Arccos => -sqrt_recip(one() - square(u)),
+            FuncName::Arctan => (one() + square(u)) ^ Expr::Num(Num::new(-1)),
+            FuncName::Sinh => call(FuncName::Cosh, u),
+            FuncName::Cosh => call(FuncName::Sinh, u),
+            FuncName::Tanh => call(FuncName::Cosh, u) ^ Expr::Num(Num::new(-2)),
+            FuncName::Exp => call(FuncName::Exp, u),
+            FuncName::Ln => u ^ Expr::Num(Num::new(-1)),
+            FuncName::Abs => u.clone() / call(FuncName::Abs, u),
+            FuncName::Sign => Expr::Num(Num::new(0)),
+            name => panic!(
+                "{} is not differentiable as a function of u",
+                name.command()
+            ),
+        }
+    }
+
+    /// F(u) with F'=f, logarithms take the absolute value of their argument
+    pub fn antiderivative(&self) -> Expr<'a> {
+        let u = *self.arg.clone();
+        let one = || Expr::Num(Num::new(1));
+        let half = || Expr::Num(Num::frac(1, 2));
+        let square = |e: Expr<'a>| e ^ Expr::Num(Num::new(2));
+        let sqrt = |e: Expr<'a>| Expr::Pow(Pow::new(e, half()));
+        let ln_abs = |e: Expr<'a>| call(FuncName::Ln, call(FuncName::Abs, e));
+        let by_parts = |name: FuncName| u.clone() * call(name, u.clone());
+        match self.name {
+            FuncName::Sin => -call(FuncName::Cos, u),
+            FuncName::Cos => call(FuncName::Sin, u),
+            FuncName::Tan => -ln_abs(call(FuncName::Cos, u)),
+            FuncName::Cot => ln_abs(call(FuncName::Sin, u)),
+            FuncName::Sec => ln_abs(call(FuncName::Sec, u.clone()) + call(FuncName::Tan, u)),
+            FuncName::Csc => -ln_abs(call(FuncName::Csc, u.clone()) + call(FuncName::Cot, u)),
+            FuncName::Arcsin => by_parts(FuncName::Arcsin) + sqrt(one() - square(u)),
+            FuncName::Arccos => by_parts(FuncName::Arccos) - sqrt(one() - square(u)),
+            FuncName::Arctan => {
+                by_parts(FuncName::Arctan) - half() * call(FuncName::Ln, one() + square(u))
+            }
+            FuncName::Sinh => call(FuncName::Cosh, u),
+            FuncName::Cosh => call(FuncName::Sinh, u),
+            FuncName::Tanh => call(FuncName::Ln, call(FuncName::Cosh, u)),
+            FuncName::Exp => call(FuncName::Exp, u),
+            FuncName::Ln => by_parts(FuncName::Ln) - u,
+            FuncName::Abs => half() * by_parts(FuncName::Abs),
+            FuncName::Sign => call(FuncName::Abs, u),
+            name => panic!(
+                "{} has no antiderivative as a function of u",
+                name.command()
+            ),
+        }
+    }
+}
+
+/// Some(q) for q*\pi with real rational q
+fn pi_multiple(expr: &Expr) -> Option<Num> {
+    match expr {
+        Expr::Const(Const::Pi) => Some(Num::new(1)),
+        Expr::Mul(mul) => match &mul.exprs[..] {
+            [Expr::Num(q), Expr::Const(Const::Pi)] if q.is_real() => Some(*q),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// sin(q*\pi) when q is a multiple of 1/12
+fn sin_pi<'a>(q: Num) -> Option<Expr<'a>> {
+    let k = q * Num::new(12);
+    if !k.is_integer() {
+        return None;
+    }
+    let sqrt = |n: i64| Expr::Pow(Pow::new(Expr::Num(Num::new(n)), Expr::Num(Num::frac(1, 2))));
+    let quarter_of = |e: Expr<'a>| Expr::Num(Num::frac(1, 4)) * e;
+    // sin(k*\pi/12) for 0<=k<=6
+    let quarter = |k: i64| match k {
+        0 => Expr::Num(Num::new(0)),
+        1 => quarter_of(sqrt(6) - sqrt(2)),
+        2 => Expr::Num(Num::frac(1, 2)),
+        3 => Expr::Num(Num::frac(1, 2)) * sqrt(2),
+        4 => Expr::Num(Num::frac(1, 2)) * sqrt(3),
+        5 => quarter_of(sqrt(6) + sqrt(2)),
+        _ => Expr::Num(Num::new(1)),
+    };
+    Some(match k.num.rem_euclid(24) {
+        k if k <= 6 => quarter(k),
+        k if k <= 12 => quarter(12 - k),
+        k if k <= 18 => -quarter(k - 12),
+        k => -quarter(24 - k),
+    })
+}
+
+/// q of name(x)=q*\pi for special x such as arctan(1)=\pi/4
+fn inverse_trig(name: FuncName, x: Num) -> Option<Num> {
+    let table: &[(Num, Num)] = match name {
+        FuncName::Arcsin => &[
+            (Num::new(1), Num::frac(1, 2)),
+            (Num::frac(1, 2), Num::frac(1, 6)),
+            (Num::frac(-1, 2), Num::frac(-1, 6)),
+            (Num::new(-1), Num::frac(-1, 2)),
+        ],
+        FuncName::Arccos => &[
+            (Num::new(0), Num::frac(1, 2)),
+            (Num::frac(1, 2), Num::frac(1, 3)),
+            (Num::frac(-1, 2), Num::frac(2, 3)),
+            (Num::new(-1), Num::new(1)),
+        ],
+        FuncName::Arctan => &[
+            (Num::new(1), Num::frac(1, 4)),
+            (Num::new(-1), Num::frac(-1, 4)),
+        ],
+        _ => &[],
+    };
+    table.iter().find(|(y, _)| *y == x).map(|(_, q)| *q)
+}
+
+/// name(arg) as Expr
+pub fn call(name: FuncName, arg: Expr) -> Expr {
+    Expr::Func(Func::new(name, arg))
+}
+
+impl<'a> Display for Func<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.name {
+            FuncName::Abs => write!(f, "|{}|", self.arg),
+            FuncName::Conj => write!(f, "conj({})", self.arg),
+            FuncName::Sign => write!(f, "sgn({})", self.arg),
+            name => write!(f, "{}({})", &name.command()[1..], self.arg),
+        }
+    }
+}
+
+#[test]
+fn test_func() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["\\sin x", "sin(x)"],
+        ["\\sin 2x+1", "sin(2*x)+1"],
+        ["\\sin x\\cos x", "sin(x)*cos(x)"],
+        ["\\sin\\left(x+y\\right)", "sin(x+y)"],
+        ["\\cos^{2}x", "cos(x)^{2}"],
+        ["\\log x^{2}", "ln(x^{2})"],
+        ["\\exp{x+1}", "exp(x+1)"],
+        ["\\sin 0+\\ln 1+\\cos 0", "1"],
+        ["\\sin\\pi+\\cos\\pi+e^{0}", "0"],
+        ["\\ln e+\\ln e^{x}", "1+ln(e^{x})"],
+        ["\\ln e^{3}+\\ln(\\exp{\\pi})", "3+\\pi"],
+        ["\\ln(e^{3\\pi i})", "ln(e^{3*i*\\pi})"],
+        ["e^{\\ln x}", "x"],
+        ["\\cos\\left(\\frac{\\pi}{3}\\right)", "1/2"],
+        ["\\sin\\left(-\\frac{3\\pi}{4}\\right)", "-1/2*2^{1/2}"],
+        ["\\tan\\left(\\frac{\\pi}{3}\\right)", "3^{1/2}"],
+        ["\\tan\\left(\\frac{\\pi}{2}\\right)", "tan(1/2*\\pi)"],
+        ["\\arctan 1+\\arccos 0", "3/4*\\pi"],
+        [
+            "\\sin\\left(\\frac{\\pi}{12}\\right)",
+            "1/4*(6^{1/2}+-1*2^{1/2})",
+        ],
+        [
+            "\\cos\\left(\\frac{5\\pi}{12}\\right)",
+            "1/4*(6^{1/2}+-1*2^{1/2})",
+        ],
+        ["\\sin(-x)+\\cos(-x)", "-1*sin(x)+cos(x)"],
+        ["\\tan(-2x)", "-1*tan(2*x)"],
+        ["\\sin\\frac{\\pi}{6}", "1/2"],
+        ["\\ln\\frac{x}{y}", "ln(x*y^{-1})"],
+        ["\\ln|e|+\\sin\\sqrt{x}", "1+sin(x^{1/2})"],
+        ["\\log_{2}8+\\log_{10}x", "3+ln(x)*ln(10)^{-1}"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+}
+
+#[test]
+fn test_complex_parts() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["i^{2}", "-1"],
+        ["(1+2i)(3-i)", "(5+5*i)"],
+        ["\\frac{1}{1+i}", "(1/2+-1/2*i)"],
+        ["\\Re(3+4i)", "3"],
+        ["\\Im(3+4i)", "4"],
+        ["\\overline{3+4i}", "(3+-4*i)"],
+        ["\\Re(z+2i)", "Re(z)"],
+        ["\\overline{xi^{3}}", "i*conj(x)"],
+        ["\\overline{\\overline{z}}", "z"],
+        ["x_{i}+i", "x_{i}+i"],
+        ["|3+4i|", "5"],
+        ["\\left|1+i\\right|", "2^{1/2}"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+}
@@ -0,0 +1,466 @@
+use super::func::{call, FuncName};
+use super::limit::{infinity_sign, Direction};
+use super::{Add, Expr, Mul, Num, Pow, Sym};
+use crate::solver::{coefficients, solve_polynomial};
+use std::fmt::{Display, Formatter, Result};
+
+/// Recursion limit of substitution and integration by parts
+const DEPTH: usize = 6;
+
+/// \int integrand dvar where bounds are (lower,upper) of a definite integral
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Integral<'a> {
+    pub integrand: Box<Expr<'a>>,
+    pub var: Sym<'a>,
+    pub bounds: Option<Box<(Expr<'a>, Expr<'a>)>>,
+}
+
+impl<'a> Integral<'a> {
+    pub fn new(integrand: Expr<'a>, var: Sym<'a>, bounds: Option<(Expr<'a>, Expr<'a>)>) -> Self {
+        Integral {
+            integrand: Box::new(integrand),
+            var,
+            bounds: bounds.map(Box::new),
+        }
+    }
+
+    pub fn map(&self, f: impl Fn(&Expr<'a>) -> Expr<'a>) -> Self {
+        let bounds = self.bounds.as_ref().map(|b| (f(&b.0), f(&b.1)));
+        Integral::new(f(&self.integrand), self.var, bounds)
+    }
+
+    /// Variable of a definite integral is bound
+    pub fn contains(&self, sym: &Sym) -> bool {
+        match &self.bounds {
+            None => self.integrand.contains(sym),
+            Some(b) => {
+                (*sym != self.var && self.integrand.contains(sym))
+                    || b.0.contains(sym)
+                    || b.1.contains(sym)
+            }
+        }
+    }
+
+    /// Antiderivative or F(upper)-F(lower), remains unevaluated when no rule applies
+    /// or the integrand has a pole between the bounds
+    pub fn collect(&self) -> Expr<'a> {
+        let integral = self.map(|e| e.collect());
+        let value = match (integral.integrand.integrate(self.var), &integral.bounds) {
+            (Some(f), None) => Some(f),
+            (Some(_), Some(b)) if has_pole(&integral.integrand, self.var, b) => None,
+            (Some(f), Some(b)) => self
+                .at(&f, &b.1)
+                .zip(self.at(&f, &b.0))
+                .filter(|(upper, lower)| {
+                    infinity_sign(upper).is_none() || infinity_sign(lower).is_none()
+                })
+                .map(|(upper, lower)| (upper - lower).collect())
+                .filter(|x| !x.is_undefined()),
+            (None, _) => None,
+        };
+        value.unwrap_or(Expr::Integral(integral))
+    }
+
+    /// F(bound) where an infinite bound is a limit
+    fn at(&self, f: &Expr<'a>, bound: &Expr<'a>) -> Option<Expr<'a>> {
+        match infinity_sign(bound) {
+            Some(_) => f.limit(self.var, bound, Direction::Both).ok(),
+            None => Some(f.subs(self.var, bound)),
+        }
+    }
+
+    /// Fundamental theorem of calculus for unevaluated integral
+    pub fn derivative(&self, var: Sym<'a>) -> Expr<'a> {
+        let integral = match self.collect() {
+            Expr::Integral(integral) => integral,
+            x => return x.diff(var),
+        };
+        match &integral.bounds {
+            None if var == integral.var => *integral.integrand,
+            Some(b) if !integral.integrand.contains(&var) => {
+                let f = |t: &Expr<'a>| integral.integrand.subs(integral.var, t);
+                f(&b.1) * b.1.diff(var) - f(&b.0) * b.0.diff(var)
+            }
+            _ => panic!("Cannot differentiate unevaluated integral"),
+        }
+    }
+}
+
+impl<'a> Expr<'a> {
+    /// Antiderivative without constant of integration, None when no rule applies
+    pub fn integrate(&self, var: Sym<'a>) -> Option<Self> {
+        integrate(&self.collect(), var, DEPTH).map(|e| e.collect())
+    }
+}
+
+/// Number of subintervals sampled for sign changes of a non-polynomial denominator
+const SAMPLES: i64 = 64;
+
+/// Whether a denominator or logarithm argument of expr vanishes on the closed interval
+/// between numeric bounds, found from the roots of polynomials and by sampling otherwise
+fn has_pole<'a>(expr: &Expr<'a>, var: Sym<'a>, bounds: &(Expr<'a>, Expr<'a>)) -> bool {
+    // Symbolic bound only checks the other one
+    let (a, b) = match (bounds.0.eval(), bounds.1.eval()) {
+        (Ok(a), Ok(b)) => (a.min(b), a.max(b)),
+        (Ok(a), Err(_)) | (Err(_), Ok(a)) => (a, a),
+        _ => return false,
+    };
+    let mut singular = vec![];
+    singular_parts(expr, var, &mut singular);
+    // Zeros at a bound such as cos(\pi/2) are exact while sampling only finds them approximately
+    let vanishes_at = |u: &Expr<'a>, bound: &Expr<'a>| {
+        infinity_sign(bound).is_none() && u.subs(var, bound) == Expr::Num(Num::new(0))
+    };
+    if singular
+        .iter()
+        .any(|u| vanishes_at(u, &bounds.0) || vanishes_at(u, &bounds.1))
+    {
+        return true;
+    }
+    singular.iter().any(|u| match solve_polynomial(u, var) {
+        Ok(roots) => roots
+            .iter()
+            .any(|(r, _)| matches!(r.eval(), Ok(r) if a <= r && r <= b)),
+        Err(_) if !a.is_finite() || !b.is_finite() => true,
+        Err(_) => {
+            let step = (bounds.1.clone() - bounds.0.clone()) * Expr::Num(Num::frac(1, SAMPLES));
+            let samples = (0..=SAMPLES)
+                .map(|k| {
+                    let x = bounds.0.clone() + Expr::Num(Num::new(k)) * step.clone();
+                    u.subs(var, &x).eval().ok()
+                })
+                .collect::<Vec<_>>();
+            samples.iter().any(|y| !matches!(y, Some(y) if *y != 0.0))
+                || samples
+                    .windows(2)
+                    .any(|w| w[0].map(f64::signum) != w[1].map(f64::signum))
+        }
+    })
+}
+
+/// Bases of negative powers, arguments of logarithms and the cos(u) or sin(u) denominators
+/// of tan(u), sec(u), cot(u) and csc(u) which contain var
+fn singular_parts<'a>(expr: &Expr<'a>, var: Sym<'a>, found: &mut Vec<Expr<'a>>) {
+    if !expr.contains(&var) {
+        return;
+    }
+    match expr {
+        Expr::Add(add) => add.exprs.iter().for_each(|e| singular_parts(e, var, found)),
+        Expr::Mul(mul) => mul.exprs.iter().for_each(|e| singular_parts(e, var, found)),
+        Expr::Pow(pow) => {
+            if matches!(*pow.pow, Expr::Num(n) if n.is_negative()) {
+                found.push(*pow.body.clone());
+            }
+            singular_parts(&pow.body, var, found);
+            singular_parts(&pow.pow, var, found);
+        }
+        Expr::Func(func) => {
+            match func.name {
+                FuncName::Ln => found.push(*func.arg.clone()),
+                FuncName::Tan | FuncName::Sec => found.push(call(FuncName::Cos, *func.arg.clone())),
+                FuncName::Cot | FuncName::Csc => found.push(call(FuncName::Sin, *func.arg.clone())),
+                _ => {}
+            }
+            singular_parts(&func.arg, var, found);
+        }
+        _ => {}
+    }
+}
+
+/// Linearity first, then table lookup, u-substitution, expansion and integration by parts
+fn integrate<'a>(expr: &Expr<'a>, var: Sym<'a>, depth: usize) -> Option<Expr<'a>> {
+    if let Expr::Matrix(matrix) = expr {
+        let rows = matrix
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|e| integrate(e, var, depth)).collect())
+            .collect::<Option<Vec<Vec<_>>>>()?;
+        return Some(Expr::Matrix(super::Matrix::new(rows, matrix.delimiter)));
+    }
+    if !expr.contains(&var) {
+        return Some(expr.clone() * Expr::Sym(var));
+    }
+    if depth == 0 {
+        return None;
+    }
+    match expr {
+        Expr::Add(add) => add
+            .exprs
+            .iter()
+            .map(|e| integrate(e, var, depth))
+            .collect::<Option<Vec<_>>>()
+            .map(|terms| Add::new(terms).to_expr()),
+        Expr::Mul(mul) => {
+            let (consts, factors): (Vec<_>, Vec<_>) =
+                mul.exprs.iter().cloned().partition(|e| !e.contains(&var));
+            let rest = Mul::new(factors.clone()).to_expr();
+            let result = match factors.len() {
+                1 => integrate(&rest, var, depth),
+                _ => substitution(&rest, var, depth)
+                    .or_else(|| by_expansion(&rest, var, depth))
+                    .or_else(|| by_parts(&factors, var, depth)),
+            };
+            result.map(|r| Mul::new(consts).to_expr() * r)
+        }
+        x => table(x, var)
+            .or_else(|| by_expansion(x, var, depth))
+            .or_else(|| substitution(x, var, depth)),
+    }
+}
+
+/// a of a*var+b
+fn linear_coeff<'a>(expr: &Expr<'a>, var: Sym<'a>) -> Option<Expr<'a>> {
+    match coefficients(expr, var) {
+        Ok(coeffs) if coeffs.len() == 2 => Some(coeffs[1].clone()),
+        _ => None,
+    }
+}
+
+/// Integrals of var^n, c^u, f(u), cos(u)^{-2}, sin(u)^{-2}, sec(u)^{2} and csc(u)^{2}
+/// for elementary f and u=a*var+b
+fn table<'a>(expr: &Expr<'a>, var: Sym<'a>) -> Option<Expr<'a>> {
+    if let Some(antiderivative) = inverse_trig(expr, var) {
+        return Some(antiderivative);
+    }
+    let (u, antiderivative) = match expr {
+        Expr::Sym(_) => return Some(Expr::Num(Num::frac(1, 2)) * expr.clone() * expr.clone()),
+        Expr::Pow(pow) if is_squared_reciprocal(pow) => match &*pow.body {
+            Expr::Func(func) if matches!(func.name, FuncName::Cos | FuncName::Sec) => {
+                (*func.arg.clone(), call(FuncName::Tan, *func.arg.clone()))
+            }
+            Expr::Func(func) => (*func.arg.clone(), -call(FuncName::Cot, *func.arg.clone())),
+            _ => unreachable!(),
+        },
+        Expr::Pow(pow) if !pow.pow.contains(&var) => {
+            let u = *pow.body.clone();
+            let antiderivative = match *pow.pow.clone() {
+                Expr::Num(n) if n == Num::new(-1) => {
+                    call(FuncName::Ln, call(FuncName::Abs, u.clone()))
+                }
+                n => {
+                    let m = n + Expr::Num(Num::new(1));
+                    Expr::Pow(Pow::new(u.clone(), m.clone())) / m
+                }
+            };
+            (u, antiderivative)
+        }
+        Expr::Pow(pow) if !pow.body.contains(&var) => {
+            let ln = call(FuncName::Ln, *pow.body.clone());
+            (*pow.pow.clone(), expr.clone() / ln)
+        }
+        Expr::Func(func) if func.name.is_real_linear() => {
+            return Some(call(func.name, func.arg.integrate(var)?));
+        }
+        Expr::Func(func) => (*func.arg.clone(), func.antiderivative()),
+        _ => return None,
+    };
+    Some(antiderivative / linear_coeff(&u, var)?)
+}
+
+/// cos(u)^{-2}, sin(u)^{-2}, sec(u)^{2} or csc(u)^{2}
+fn is_squared_reciprocal(pow: &Pow) -> bool {
+    let names = match *pow.pow {
+        Expr::Num(n) if n == Num::new(-2) => [FuncName::Sin, FuncName::Cos],
+        Expr::Num(n) if n == Num::new(2) => [FuncName::Csc, FuncName::Sec],
+        _ => return false,
+    };
+    matches!(&*pow.body, Expr::Func(func) if names.contains(&func.name))
+}
+
+/// (c+a*var^{2})^{-1}->\frac{\arctan(\sqrt{a/c}var)}{\sqrt{ac}} and
+/// (c-a*var^{2})^{-1/2}->\frac{\arcsin(\sqrt{a/c}var)}{\sqrt{a}} for positive numbers a and c
+fn inverse_trig<'a>(expr: &Expr<'a>, var: Sym<'a>) -> Option<Expr<'a>> {
+    let (body, n) = match expr {
+        Expr::Pow(pow) => match *pow.pow {
+            Expr::Num(n) => (&*pow.body, n),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let coeffs = coefficients(body, var).ok()?;
+    let (c, a) = match coeffs.as_slice() {
+        [Expr::Num(c), Expr::Num(b), Expr::Num(a)]
+            if b.is_zero() && c.is_real() && a.is_real() && !c.is_zero() && !c.is_negative() =>
+        {
+            (*c, *a)
+        }
+        _ => return None,
+    };
+    let sqrt = |x: Num| Expr::Num(x) ^ Expr::Num(Num::frac(1, 2));
+    let u = sqrt((a / c).abs()) * Expr::Sym(var);
+    match a.is_negative() {
+        false if n == Num::new(-1) => Some(call(FuncName::Arctan, u) / sqrt(a * c)),
+        true if n == Num::frac(-1, 2) => Some(call(FuncName::Arcsin, u) / sqrt(-a)),
+        _ => None,
+    }
+}
+
+/// Integrate expanded form when expansion turns expr into a sum
+fn by_expansion<'a>(expr: &Expr<'a>, var: Sym<'a>, depth: usize) -> Option<Expr<'a>> {
+    match expr.expand() {
+        Expr::Add(add) => integrate(&Expr::Add(add), var, depth - 1),
+        _ => None,
+    }
+}
+
+/// Sub expressions of expr which may serve as u of u-substitution
+fn candidates<'a>(expr: &Expr<'a>, var: Sym<'a>, found: &mut Vec<Expr<'a>>) {
+    let mut push = |e: &Expr<'a>| {
+        if e.contains(&var) && *e != Expr::Sym(var) && !found.contains(e) {
+            found.push(e.clone())
+        }
+    };
+    match expr {
+        Expr::Func(func) => {
+            push(expr);
+            push(&func.arg);
+        }
+        Expr::Pow(pow) => {
+            push(&pow.body);
+            push(&pow.pow);
+        }
+        _ => {}
+    }
+    match expr {
+        Expr::Add(add) => add.exprs.iter().for_each(|e| candidates(e, var, found)),
+        Expr::Mul(mul) => mul.exprs.iter().for_each(|e| candidates(e, var, found)),
+        Expr::Pow(pow) => {
+            candidates(&pow.body, var, found);
+            candidates(&pow.pow, var, found);
+        }
+        Expr::Func(func) => candidates(&func.arg, var, found),
+        _ => {}
+    }
+}
+
+/// \int f(u(x))u'(x)dx=\int f(u)du when expr/u' is free of var after replacing u
+fn substitution<'a>(expr: &Expr<'a>, var: Sym<'a>, depth: usize) -> Option<Expr<'a>> {
+    let t = Sym::new("#u");
+    let mut found = vec![];
+    candidates(expr, var, &mut found);
+    found.iter().find_map(|u| {
+        let du = u.diff(var);
+        if du == Expr::Num(Num::new(0)) {
+            return None;
+        }
+        let f = (expr.clone() / du).collect().replace(u, &Expr::Sym(t));
+        if f.contains(&var) {
+            return None;
+        }
+        Some(integrate(&f.collect(), t, depth - 1)?.replace(&Expr::Sym(t), u))
+    })
+}
+
+/// Order of LIATE rule where the factor of the smallest one is differentiated
+fn priority(expr: &Expr, var: Sym) -> usize {
+    match expr {
+        Expr::Func(func) => match func.name {
+            FuncName::Ln => 0,
+            FuncName::Arcsin | FuncName::Arccos | FuncName::Arctan => 1,
+            FuncName::Exp => 4,
+            _ => 3,
+        },
+        Expr::Pow(pow) if !pow.body.contains(&var) => 4,
+        _ => 2,
+    }
+}
+
+/// \int u*dv=u*v-\int v*du where u is chosen from factors by LIATE rule
+fn by_parts<'a>(factors: &[Expr<'a>], var: Sym<'a>, depth: usize) -> Option<Expr<'a>> {
+    let i = (0..factors.len()).min_by_key(|i| priority(&factors[*i], var))?;
+    let u = factors[i].clone();
+    let dv = Mul::new([&factors[..i], &factors[i + 1..]].concat()).to_expr();
+    let v = integrate(&dv, var, depth - 1)?.collect();
+    let vdu = (v.clone() * u.diff(var)).collect();
+    Some(u * v - integrate(&vdu, var, depth - 1)?)
+}
+
+impl<'a> Display for Integral<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.bounds {
+            None => write!(f, "int({},{})", self.integrand, self.var),
+            Some(b) => write!(f, "int({},{},{},{})", self.integrand, self.var, b.0, b.1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_integral {
+    use crate::parser::latex_to_expr;
+    use crate::Sym;
+
+    fn integrate(latex: &'static str) -> String {
+        match latex_to_expr(latex).integrate(Sym::new("x")) {
+            Some(f) => f.to_string(),
+            None => String::from("None"),
+        }
+    }
+
+    #[test]
+    fn test_table() {
+        assert_eq!(integrate("3x^{2}+2x+1"), "x^{3}+x^{2}+x");
+        assert_eq!(integrate("\\frac{1}{x}"), "ln(|x|)");
+        assert_eq!(integrate("x^{-3}"), "-1/2*x^{-2}");
+        assert_eq!(integrate("\\frac{2}{3x+1}"), "2/3*ln(|3*x+1|)");
+        assert_eq!(integrate("a\\cos 2x"), "1/2*a*sin(2*x)");
+        assert_eq!(integrate("\\exp{-x}"), "-1*exp(-1*x)");
+        assert_eq!(integrate("\\ln x"), "x*ln(x)+-1*x");
+        assert_eq!(integrate("2^{x}"), "2^{x}*ln(2)^{-1}");
+        assert_eq!(integrate("y"), "y*x");
+        assert_eq!(integrate("\\frac{1}{1+x^{2}}"), "arctan(x)");
+        assert_eq!(integrate("\\frac{1}{4+9x^{2}}"), "1/6*arctan(3/2*x)");
+        assert_eq!(integrate("\\frac{1}{\\sqrt{4-x^{2}}}"), "arcsin(1/2*x)");
+        assert_eq!(integrate("\\sec^{2}x"), "tan(x)");
+        assert_eq!(integrate("\\csc^{2}2x"), "-1/2*cot(2*x)");
+    }
+
+    #[test]
+    fn test_heuristics() {
+        assert_eq!(integrate("x\\cos x^{2}"), "1/2*sin(x^{2})");
+        assert_eq!(integrate("\\frac{x}{x^{2}+1}"), "1/2*ln(|x^{2}+1|)");
+        assert_eq!(integrate("\\sin^{2}x\\cos x"), "1/3*sin(x)^{3}");
+        assert_eq!(integrate("x(x+1)"), "1/3*x^{3}+1/2*x^{2}");
+        assert_eq!(integrate("x\\exp{x}"), "x*exp(x)+-1*exp(x)");
+        assert_eq!(integrate("x^{2}\\ln x"), "1/3*ln(x)*x^{3}+-1/9*x^{3}");
+        assert_eq!(integrate("\\exp{x}\\sin x"), "None");
+    }
+
+    #[test]
+    fn test_parse_integral() {
+        let tests = [
+            ["\\int x^{2}dx", "1/3*x^{3}"],
+            ["\\int dx+1", "x+1"],
+            ["\\int_{0}^{1}x^{2}+1dx", "4/3"],
+            ["\\int_0^2 t\\,dt", "2"],
+            ["2\\int_{1}^{e}\\frac{1}{x}dx", "2"],
+            ["\\int_{1}^{e}\\frac{1}{x}dx", "1"],
+            ["\\int\\frac{1}{1+x^{2}}dx", "arctan(x)"],
+            ["\\int_{0}^{1}\\frac{1}{x^{2}+1}dx", "1/4*\\pi"],
+            ["\\int_{-1}^{1}\\frac{1}{x^{2}}dx", "int(x^{-2},x,-1,1)"],
+            ["\\int_{0}^{1}\\frac{1}{x}dx", "int(x^{-1},x,0,1)"],
+            [
+                "\\int_{0}^{\\pi}\\frac{1}{\\cos^{2}x}dx",
+                "int(cos(x)^{-2},x,0,\\pi)",
+            ],
+            // tan x=sin x/cos x has poles at the bound \pi/2 and inside (0,\pi)
+            [
+                "\\int_{0}^{\\frac{\\pi}{2}}\\tan x\\,dx",
+                "int(tan(x),x,0,1/2*\\pi)",
+            ],
+            ["\\int_{0}^{\\pi}\\tan x dx", "int(tan(x),x,0,\\pi)"],
+            ["\\int_{0}^{\\pi}\\cot x dx", "int(cot(x),x,0,\\pi)"],
+            ["\\int_{0}^{\\pi}\\sin x dx", "2"],
+            ["\\int_{0}^{\\infty}\\exp{-x}dx", "1"],
+            ["\\int_{1}^{\\infty}\\frac{1}{x^{2}}dx", "1"],
+            [
+                "\\int_{-\\infty}^{\\infty}xdx",
+                "int(x,x,-1*\\infty,\\infty)",
+            ],
+            ["\\int \\sin x\\cos x dx", "1/2*sin(x)^{2}"],
+            ["\\int \\exp{x}\\sin x dx", "int(exp(x)*sin(x),x)"],
+        ];
+        tests.iter().for_each(|test| {
+            assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+        });
+    }
+}
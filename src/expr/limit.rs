@@ -0,0 +1,465 @@
+use super::func::{call, FuncName};
+use super::{Const, Expr, Mul, Num, Pow, Sym};
+use std::fmt::{Display, Formatter, Result};
+
+/// Maximum number of times L'Hopital's rule is applied
+const DEPTH: usize = 8;
+
+/// Side from which var approaches the point
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Direction {
+    Both,
+    Plus,
+    Minus,
+}
+
+/// \lim_{var\to point}expr, remains unevaluated when the limit cannot be found
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Limit<'a> {
+    pub expr: Box<Expr<'a>>,
+    pub var: Sym<'a>,
+    pub point: Box<Expr<'a>>,
+    pub direction: Direction,
+}
+
+/// Extended value of a limit
+#[derive(PartialEq, Eq, Clone, Debug)]
+enum Value<'a> {
+    Finite(Expr<'a>),
+    Infinite(i64),
+}
+
+impl<'a> Limit<'a> {
+    pub fn new(expr: Expr<'a>, var: Sym<'a>, point: Expr<'a>, direction: Direction) -> Self {
+        Limit {
+            expr: Box::new(expr),
+            var,
+            point: Box::new(point),
+            direction,
+        }
+    }
+
+    pub fn map(&self, f: impl Fn(&Expr<'a>) -> Expr<'a>) -> Self {
+        Limit::new(f(&self.expr), self.var, f(&self.point), self.direction)
+    }
+
+    pub fn contains(&self, sym: &Sym) -> bool {
+        (*sym != self.var && self.expr.contains(sym)) || self.point.contains(sym)
+    }
+
+    pub fn collect(&self) -> Expr<'a> {
+        let limit = self.map(|e| e.collect());
+        match limit.expr.limit(self.var, &limit.point, self.direction) {
+            Ok(value) => value,
+            Err(_) => Expr::Limit(limit),
+        }
+    }
+}
+
+pub(crate) fn infinity<'a>() -> Expr<'a> {
+    Expr::Const(Const::Infinity)
+}
+
+/// Some(1) for \infty and Some(-1) for -\infty
+pub(crate) fn infinity_sign(expr: &Expr) -> Option<i64> {
+    match expr {
+        x if *x == infinity() => Some(1),
+        Expr::Mul(mul) if mul.exprs.len() == 2 && mul.exprs[1] == infinity() => {
+            match mul.exprs[0] {
+                Expr::Num(n) if n.is_negative() => Some(-1),
+                Expr::Num(n) if n.is_real() => Some(1),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+impl<'a> Expr<'a> {
+    /// Limit of expr as var approaches point which may be \infty or -\infty
+    pub fn limit(
+        &self,
+        var: Sym<'a>,
+        point: &Expr<'a>,
+        direction: Direction,
+    ) -> std::result::Result<Self, &'static str> {
+        let expr = self.collect();
+        let point = point.collect();
+        let value = match infinity_sign(&point) {
+            // x->+-\infty is t->0+ with x=+-1/t
+            Some(sign) => {
+                let t = Sym::new("#t");
+                let x = Expr::Num(Num::new(sign)) / Expr::Sym(t);
+                let approach = Approach {
+                    var: t,
+                    point: Expr::Num(Num::new(0)),
+                    direction: Direction::Plus,
+                };
+                approach.value(&expr.subs(var, &x), DEPTH)?
+            }
+            None => {
+                let approach = Approach {
+                    var,
+                    point,
+                    direction,
+                };
+                approach.value(&expr, DEPTH)?
+            }
+        };
+        Ok(match value {
+            Value::Finite(x) if x.is_undefined() => return Err("Cannot find limit"),
+            Value::Finite(x) => x.collect(),
+            Value::Infinite(sign) => (Expr::Num(Num::new(sign)) * infinity()).collect(),
+        })
+    }
+}
+
+struct Approach<'a> {
+    var: Sym<'a>,
+    point: Expr<'a>,
+    direction: Direction,
+}
+
+impl<'a> Approach<'a> {
+    /// Leading term of Laurent series first, then limits of sub expressions
+    /// where indeterminate forms are resolved by L'Hopital's rule
+    fn value(&self, expr: &Expr<'a>, depth: usize) -> std::result::Result<Value<'a>, &'static str> {
+        if !expr.contains(&self.var) {
+            return Ok(Value::Finite(expr.clone()));
+        }
+        if let Some(series) = expr
+            .series(self.var, &self.point, 1)
+            .ok()
+            .filter(|s| !s.coeffs.iter().any(|c| c.is_undefined()))
+        {
+            return match series.coeffs.first() {
+                None => Ok(Value::Finite(Expr::Num(Num::new(0)))),
+                Some(c) if series.start == 0 => Ok(Value::Finite(c.clone())),
+                Some(c) => self.pole(c, series.start),
+            };
+        }
+        match expr {
+            Expr::Add(add) => {
+                add.exprs
+                    .iter()
+                    .try_fold(Value::Finite(Expr::Num(Num::new(0))), |acc, e| {
+                        match (acc, self.value(e, depth)?) {
+                            (Value::Finite(x), Value::Finite(y)) => Ok(Value::Finite(x + y)),
+                            (Value::Infinite(s), Value::Finite(_))
+                            | (Value::Finite(_), Value::Infinite(s)) => Ok(Value::Infinite(s)),
+                            (Value::Infinite(s), Value::Infinite(t)) if s == t => {
+                                Ok(Value::Infinite(s))
+                            }
+                            _ => Err("Indeterminate form"),
+                        }
+                    })
+            }
+            Expr::Mul(mul) => {
+                let (mut num, mut den) = (vec![], vec![]);
+                // Factors such as x^{-2} and e^{-x} go to the denominator
+                mul.exprs.iter().for_each(|e| match e {
+                    Expr::Pow(pow) if is_negative(&pow.pow) => {
+                        den.push(Pow::new(*pow.body.clone(), -*pow.pow.clone()).collect())
+                    }
+                    e => num.push(e.clone()),
+                });
+                let num = Mul::new(num).to_expr();
+                match den.is_empty() {
+                    true => self.product(&mul.exprs, depth),
+                    false => self.quotient(&num, &Mul::new(den).to_expr(), depth),
+                }
+            }
+            // b^{p}=e^{p*ln(b)} which also resolves 0^0, 1^\infty and \infty^0
+            Expr::Pow(pow) if pow.pow.contains(&self.var) => {
+                let ln = call(FuncName::Ln, *pow.body.clone());
+                let exponent = (*pow.pow.clone() * ln).collect();
+                match self.value(&exponent, depth)? {
+                    Value::Finite(x) => {
+                        Ok(Value::Finite(Pow::new(Expr::Const(Const::E), x).collect()))
+                    }
+                    infinite => self.func(FuncName::Exp, infinite),
+                }
+            }
+            Expr::Pow(pow) if matches!(*pow.pow, Expr::Num(n) if n.is_negative()) => {
+                let den = Pow::new(*pow.body.clone(), -*pow.pow.clone()).collect();
+                self.quotient(&Expr::Num(Num::new(1)), &den, depth)
+            }
+            Expr::Pow(pow) => match (self.value(&pow.body, depth)?, *pow.pow.clone()) {
+                (Value::Finite(x), n) => Ok(Value::Finite(Pow::new(x, n).collect())),
+                (Value::Infinite(_), Expr::Num(n)) if n.is_negative() => {
+                    Ok(Value::Finite(Expr::Num(Num::new(0))))
+                }
+                (Value::Infinite(1), Expr::Num(_)) => Ok(Value::Infinite(1)),
+                (Value::Infinite(_), Expr::Num(n)) if n.is_integer() => {
+                    Ok(Value::Infinite(if n.num % 2 == 0 { 1 } else { -1 }))
+                }
+                _ => Err("Cannot find limit of power"),
+            },
+            Expr::Func(func) => {
+                let arg = self.value(&func.arg, depth)?;
+                self.func(func.name, arg)
+            }
+            _ => Err("Cannot find limit"),
+        }
+    }
+
+    /// Value of f which must agree from both sides when approaching from both sides
+    fn both_sides(
+        &self,
+        f: impl Fn(&Approach<'a>) -> std::result::Result<Value<'a>, &'static str>,
+    ) -> std::result::Result<Value<'a>, &'static str> {
+        if self.direction != Direction::Both {
+            return f(self);
+        }
+        let side = |direction| {
+            f(&Approach {
+                var: self.var,
+                point: self.point.clone(),
+                direction,
+            })
+        };
+        match (side(Direction::Plus)?, side(Direction::Minus)?) {
+            (plus, minus) if plus == minus => Ok(plus),
+            _ => Err("Limit does not exist"),
+        }
+    }
+
+    /// c*h^k for negative k
+    fn pole(&self, c: &Expr<'a>, k: i64) -> std::result::Result<Value<'a>, &'static str> {
+        let sign = sign(c)?;
+        match (k % 2 == 0, self.direction) {
+            (true, _) | (false, Direction::Plus) => Ok(Value::Infinite(sign)),
+            (false, Direction::Minus) => Ok(Value::Infinite(-sign)),
+            (false, Direction::Both) => Err("Limit does not exist"),
+        }
+    }
+
+    fn product(
+        &self,
+        factors: &[Expr<'a>],
+        depth: usize,
+    ) -> std::result::Result<Value<'a>, &'static str> {
+        let values = factors
+            .iter()
+            .map(|e| self.value(e, depth))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let zero = values
+            .iter()
+            .position(|v| *v == Value::Finite(Expr::Num(Num::new(0))));
+        let infinite = values.iter().any(|v| matches!(v, Value::Infinite(_)));
+        if let (Some(i), true) = (zero, infinite) {
+            // 0*\infty->\infty/(1/0) where 1/0 may only have one-sided limits
+            let rest: Vec<_> = (0..factors.len())
+                .filter(|k| *k != i)
+                .map(|k| factors[k].clone())
+                .collect();
+            let den = Pow::new(factors[i].clone(), Expr::Num(Num::new(-1))).collect();
+            let num = Mul::new(rest).to_expr();
+            return self.both_sides(|approach| approach.quotient(&num, &den, depth));
+        }
+        values
+            .into_iter()
+            .try_fold(Value::Finite(Expr::Num(Num::new(1))), |acc, v| {
+                match (acc, v) {
+                    (Value::Finite(x), Value::Finite(y)) => Ok(Value::Finite((x * y).collect())),
+                    (Value::Infinite(s), Value::Finite(c))
+                    | (Value::Finite(c), Value::Infinite(s)) => Ok(Value::Infinite(s * sign(&c)?)),
+                    (Value::Infinite(s), Value::Infinite(t)) => Ok(Value::Infinite(s * t)),
+                }
+            })
+    }
+
+    /// L'Hopital's rule for 0/0 and \infty/\infty
+    fn quotient(
+        &self,
+        num: &Expr<'a>,
+        den: &Expr<'a>,
+        depth: usize,
+    ) -> std::result::Result<Value<'a>, &'static str> {
+        let zero = Value::Finite(Expr::Num(Num::new(0)));
+        match (self.value(num, depth)?, self.value(den, depth)?) {
+            (n, d)
+                if (n == zero && d == zero)
+                    || matches!((&n, &d), (Value::Infinite(_), Value::Infinite(_))) =>
+            {
+                if depth == 0 {
+                    return Err("Too many applications of L'Hopital's rule");
+                }
+                let ratio = (num.diff(self.var) / den.diff(self.var)).collect();
+                self.value(&ratio, depth - 1)
+            }
+            (_, Value::Infinite(_)) => Ok(zero),
+            (Value::Infinite(s), Value::Finite(d)) => Ok(Value::Infinite(s * sign(&d)?)),
+            (Value::Finite(n), d) if d == zero => match self.leading(den) {
+                Ok((lead, k)) => self.pole(&(n / lead).collect(), -k),
+                // Denominator such as |x| approaches 0 from above without a series
+                Err(_) if is_nonnegative(den) => Ok(Value::Infinite(sign(&n)?)),
+                Err(err) => Err(err),
+            },
+            (Value::Finite(n), Value::Finite(d)) => Ok(Value::Finite((n / d).collect())),
+        }
+    }
+
+    /// (c,k) of the leading term c*h^k of Laurent series
+    fn leading(&self, expr: &Expr<'a>) -> std::result::Result<(Expr<'a>, i64), &'static str> {
+        for order in 1..=DEPTH as i64 {
+            let series = expr.series(self.var, &self.point, order)?;
+            if let Some(c) = series.coeffs.first() {
+                return Ok((c.clone(), series.start));
+            }
+        }
+        Err("Cannot determine sign")
+    }
+
+    fn func(&self, name: FuncName, arg: Value<'a>) -> std::result::Result<Value<'a>, &'static str> {
+        match (name, arg) {
+            (FuncName::Ln, Value::Finite(Expr::Num(n))) if n.is_zero() => Ok(Value::Infinite(-1)),
+            (_, Value::Finite(x)) => Ok(Value::Finite(call(name, x).collect())),
+            (FuncName::Exp, Value::Infinite(1)) => Ok(Value::Infinite(1)),
+            (FuncName::Exp, Value::Infinite(_)) => Ok(Value::Finite(Expr::Num(Num::new(0)))),
+            (FuncName::Ln | FuncName::Abs | FuncName::Cosh, Value::Infinite(_)) => {
+                Ok(Value::Infinite(1))
+            }
+            (FuncName::Sinh, Value::Infinite(s)) => Ok(Value::Infinite(s)),
+            (FuncName::Tanh, Value::Infinite(s)) => Ok(Value::Finite(Expr::Num(Num::new(s)))),
+            (FuncName::Arctan, Value::Infinite(s)) => Ok(Value::Finite(
+                Expr::Num(Num::frac(s, 2)) * Expr::Const(Const::Pi),
+            )),
+            _ => Err("Limit does not exist"),
+        }
+    }
+}
+
+/// Negative number or product with negative coefficient
+fn is_negative(expr: &Expr) -> bool {
+    match expr {
+        Expr::Num(n) => n.is_negative(),
+        Expr::Mul(mul) => matches!(mul.exprs.first(), Some(Expr::Num(n)) if n.is_negative()),
+        _ => false,
+    }
+}
+
+/// Absolute values, even powers and their products with positive numbers
+fn is_nonnegative(expr: &Expr) -> bool {
+    match expr {
+        Expr::Num(n) => n.is_real() && !n.is_negative(),
+        Expr::Func(func) => func.name == FuncName::Abs,
+        Expr::Pow(pow) => {
+            matches!(*pow.pow, Expr::Num(n) if n.is_integer() && n.num % 2 == 0)
+                || is_nonnegative(&pow.body)
+        }
+        Expr::Mul(mul) => mul.exprs.iter().all(is_nonnegative),
+        _ => false,
+    }
+}
+
+/// Sign of a nonzero number
+fn sign(expr: &Expr) -> std::result::Result<i64, &'static str> {
+    match expr {
+        Expr::Num(n) if n.is_zero() => Err("Indeterminate form"),
+        Expr::Num(n) if n.is_negative() => Ok(-1),
+        Expr::Num(n) if n.is_real() => Ok(1),
+        _ => match expr.eval() {
+            Ok(x) if x > 0.0 => Ok(1),
+            Ok(x) if x < 0.0 => Ok(-1),
+            _ => Err("Cannot determine sign"),
+        },
+    }
+}
+
+impl<'a> Display for Limit<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let direction = match self.direction {
+            Direction::Both => "",
+            Direction::Plus => "+",
+            Direction::Minus => "-",
+        };
+        write!(
+            f,
+            "lim({},{},{}{})",
+            self.expr, self.var, self.point, direction
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_limit {
+    use super::Direction;
+    use crate::parser::latex_to_expr;
+    use crate::Sym;
+
+    fn limit(latex: &'static str, point: &'static str, direction: Direction) -> String {
+        let point = latex_to_expr(point);
+        match latex_to_expr(latex).limit(Sym::new("x"), &point, direction) {
+            Ok(value) => value.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_limit() {
+        assert_eq!(limit("x^{2}+1", "2", Direction::Both), "5");
+        assert_eq!(limit("\\frac{x^{2}-1}{x-1}", "1", Direction::Both), "2");
+        assert_eq!(
+            limit("\\frac{1-\\cos x}{x^{2}}", "0", Direction::Both),
+            "1/2"
+        );
+        assert_eq!(limit("\\frac{1}{x}", "0", Direction::Plus), "\\infty");
+        assert_eq!(limit("\\frac{1}{x}", "0", Direction::Minus), "-1*\\infty");
+        assert_eq!(
+            limit("\\frac{1}{x}", "0", Direction::Both),
+            "Limit does not exist"
+        );
+        assert_eq!(limit("\\frac{1}{x^{2}}", "0", Direction::Both), "\\infty");
+        assert_eq!(limit("x\\ln x", "0", Direction::Plus), "0");
+        assert_eq!(limit("x^{x}", "0", Direction::Plus), "1");
+        // 0*\infty and 0^0 from both sides
+        assert_eq!(limit("x\\ln x", "0", Direction::Both), "0");
+        assert_eq!(limit("x^{2}\\ln|x|", "0", Direction::Both), "0");
+        assert_eq!(limit("x^{x}", "0", Direction::Both), "1");
+        assert_eq!(limit("(\\sin x)^{x}", "0", Direction::Both), "1");
+        assert_eq!(limit("\\frac{1}{|x|}", "0", Direction::Plus), "\\infty");
+        assert_eq!(limit("\\frac{-2}{|x|}", "0", Direction::Both), "-1*\\infty");
+        assert_eq!(
+            limit("\\frac{\\pi}{x^{2}}", "0", Direction::Both),
+            "\\infty"
+        );
+        assert_eq!(limit("\\frac{-e}{x}", "0", Direction::Plus), "-1*\\infty");
+    }
+
+    #[test]
+    fn test_infinity() {
+        assert_eq!(
+            limit("\\frac{2x^{2}+1}{x^{2}-x}", "\\infty", Direction::Both),
+            "2"
+        );
+        assert_eq!(limit("\\frac{x}{x^{2}+1}", "\\infty", Direction::Both), "0");
+        assert_eq!(limit("x^{3}-x", "-\\infty", Direction::Both), "-1*\\infty");
+        assert_eq!(limit("\\frac{\\ln x}{x}", "\\infty", Direction::Both), "0");
+        assert_eq!(limit("\\exp{-x}", "\\infty", Direction::Both), "0");
+        assert_eq!(limit("\\frac{x}{e^{x}}", "\\infty", Direction::Both), "0");
+        assert_eq!(limit("x^{2}e^{x}", "-\\infty", Direction::Both), "0");
+        assert_eq!(
+            limit("\\sin x", "\\infty", Direction::Both),
+            "Limit does not exist"
+        );
+    }
+
+    #[test]
+    fn test_parse_limit() {
+        let tests = [
+            ["\\lim_{x\\to0}\\frac{\\sin x}{x}", "1"],
+            ["\\lim_{x\\to 0^{+}}\\frac{1}{x}", "\\infty"],
+            ["2\\lim_{t\\to\\infty}\\frac{t}{t+1}", "2"],
+            ["\\lim_{x\\to0}\\frac{1}{x}", "lim(x^{-1},x,0)"],
+            ["\\lim_{x\\to0}|x|", "0"],
+            ["\\lim_{x\\to0^{+}}\\frac{1}{|x|}", "\\infty"],
+            ["\\lim_{x\\to\\infty}(1+\\frac{1}{x})^{x}", "e"],
+            ["\\lim_{x\\to0}x\\ln x", "0"],
+            ["\\lim_{x\\to0}x^{x}", "1"],
+        ];
+        tests.iter().for_each(|test| {
+            assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+        });
+    }
+}
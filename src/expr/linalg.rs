@@ -0,0 +1,291 @@
+use super::{poly::Poly, Add, Expr, Matrix, Num, Sym};
+use crate::solver::solve_polynomial;
+
+/// Rows of a matrix as polynomials sharing the same gens
+struct PolyRows<'a> {
+    gens: Vec<Expr<'a>>,
+    rows: Vec<Vec<Poly<'a>>>,
+}
+
+impl<'a> PolyRows<'a> {
+    fn new(matrix: &Matrix<'a>) -> Self {
+        let rows: Vec<Vec<Expr>> = matrix
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|e| e.expand()).collect())
+            .collect();
+        let mut gens = vec![];
+        rows.iter()
+            .flatten()
+            .for_each(|e| Poly::find_gens(e, &mut gens));
+        let rows = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|e| Poly::from_expr(e, &gens).unwrap())
+                    .collect()
+            })
+            .collect();
+        PolyRows { gens, rows }
+    }
+
+    /// Fraction-free Gauss-Jordan elimination on the first cols columns where every division is exact.
+    /// Rows above the pivot are eliminated only when reduce is true, in which case
+    /// every pivot equals the last pivot. Returns pivot columns, the last pivot and the sign of row swaps.
+    fn eliminate(&mut self, cols: usize, reduce: bool) -> (Vec<usize>, Poly<'a>, i64) {
+        let rows = &mut self.rows;
+        let mut prev = Poly::constant(Num::new(1), &self.gens);
+        let mut pivots = vec![];
+        let mut sign = 1;
+        for c in 0..cols {
+            let r = pivots.len();
+            let Some(i) = (r..rows.len()).find(|i| !is_zero(&rows[*i][c])) else {
+                continue;
+            };
+            if i != r {
+                rows.swap(r, i);
+                sign = -sign;
+            }
+            let width = rows[r].len();
+            for i in (0..rows.len()).filter(|i| *i > r || (reduce && *i != r)) {
+                let factor = rows[i][c].clone();
+                for j in 0..width {
+                    rows[i][j] = rows[r][c]
+                        .mul(&rows[i][j])
+                        .sub(&factor.mul(&rows[r][j]))
+                        .div_exact(&prev)
+                        .expect("Fraction-free elimination must divide exactly");
+                }
+            }
+            prev = rows[r][c].clone();
+            pivots.push(c);
+        }
+        (pivots, prev, sign)
+    }
+}
+
+/// Gens such as x and x^{-1} or 2^{1/2} are not independent, so a nonzero poly may collect to 0
+fn is_zero(poly: &Poly) -> bool {
+    poly.is_zero() || poly.to_expr().collect() == Expr::Num(Num::new(0))
+}
+
+impl<'a> Matrix<'a> {
+    /// Determinant by Bareiss algorithm, crash when matrix is not square
+    pub fn det(&self) -> Expr<'a> {
+        let (m, n) = self.shape();
+        if m != n {
+            panic!("Determinant is defined only for square matrix");
+        }
+        let mut rows = PolyRows::new(self);
+        match rows.eliminate(n, false) {
+            (pivots, _, _) if pivots.len() < n => Expr::Num(Num::new(0)),
+            (_, det, sign) => det.scale(Num::new(sign)).to_expr().collect(),
+        }
+    }
+
+    pub fn rank(&self) -> usize {
+        let mut rows = PolyRows::new(self);
+        rows.eliminate(self.shape().1, false).0.len()
+    }
+
+    /// Reduced row echelon form with pivot columns
+    fn rref_with_pivots(&self) -> (Self, Vec<usize>) {
+        let mut rows = PolyRows::new(self);
+        let (pivots, pivot, _) = rows.eliminate(self.shape().1, true);
+        let rows = rows
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|e| Poly::ratio_to_expr(e, &pivot).collect())
+                    .collect()
+            })
+            .collect();
+        (Matrix::new(rows, self.delimiter), pivots)
+    }
+
+    /// Reduced row echelon form
+    pub fn rref(&self) -> Self {
+        self.rref_with_pivots().0
+    }
+
+    /// None if matrix is singular, crash when matrix is not square
+    pub fn inverse(&self) -> Option<Self> {
+        let (m, n) = self.shape();
+        if m != n {
+            panic!("Inverse is defined only for square matrix");
+        }
+        let identity = Matrix::identity(n, self.delimiter);
+        let augmented = Matrix::new(
+            self.rows
+                .iter()
+                .zip(identity.rows)
+                .map(|(row, id)| [row.clone(), id].concat())
+                .collect(),
+            self.delimiter,
+        );
+        let mut rows = PolyRows::new(&augmented);
+        let (pivots, pivot, _) = rows.eliminate(n, true);
+        if pivots.len() < n {
+            return None;
+        }
+        let rows = rows
+            .rows
+            .iter()
+            .map(|row| {
+                row[n..]
+                    .iter()
+                    .map(|e| Poly::ratio_to_expr(e, &pivot).collect())
+                    .collect()
+            })
+            .collect();
+        Some(Matrix::new(rows, self.delimiter))
+    }
+
+    /// Basis of {v|Av=0} as column vectors
+    pub fn nullspace(&self) -> Vec<Self> {
+        let n = self.shape().1;
+        let (rref, pivots) = self.rref_with_pivots();
+        (0..n)
+            .filter(|j| !pivots.contains(j))
+            .map(|free| {
+                let mut v = vec![vec![Expr::Num(Num::new(0))]; n];
+                v[free][0] = Expr::Num(Num::new(1));
+                pivots.iter().enumerate().for_each(|(r, c)| {
+                    v[*c][0] = (-rref.rows[r][free].clone()).collect();
+                });
+                Matrix::new(v, self.delimiter)
+            })
+            .collect()
+    }
+
+    /// det(var*I-A) expanded in var
+    pub fn charpoly(&self, var: Sym<'a>) -> Expr<'a> {
+        let (m, n) = self.shape();
+        if m != n {
+            panic!("Characteristic polynomial is defined only for square matrix");
+        }
+        let shifted = Matrix::identity(n, self.delimiter)
+            .scale(&Expr::Sym(var))
+            .add(&self.scale(&Expr::Num(Num::new(-1))))
+            .unwrap();
+        shifted.det().expand()
+    }
+
+    /// Roots of the characteristic polynomial with their algebraic multiplicities
+    pub fn eigenvalues(&self) -> Result<Vec<(Expr<'a>, usize)>, &'static str> {
+        let var = Sym::new("\\lambda");
+        solve_polynomial(&self.charpoly(var), var)
+    }
+
+    /// Trace of square matrix
+    pub fn trace(&self) -> Expr<'a> {
+        Add::new(
+            (0..self.rows.len())
+                .map(|i| self.rows[i][i].clone())
+                .collect(),
+        )
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_linalg {
+    use crate::parser::latex_to_expr;
+    use crate::{Expr, Matrix, Sym};
+
+    fn matrix(latex: &'static str) -> Matrix<'static> {
+        match latex_to_expr(latex) {
+            Expr::Matrix(m) => m,
+            _ => panic!("not a matrix"),
+        }
+    }
+
+    #[test]
+    fn test_det() {
+        let m = matrix("\\begin{pmatrix}a&b\\\\c&d\\end{pmatrix}");
+        assert_eq!(m.det().to_string(), "a*d+-1*b*c");
+        let m = matrix("\\begin{pmatrix}0&1&2\\\\1&0&3\\\\4&-3&8\\end{pmatrix}");
+        assert_eq!(m.det().to_string(), "-2");
+        let m = matrix("\\begin{pmatrix}1&2\\\\2&4\\end{pmatrix}");
+        assert_eq!(m.det().to_string(), "0");
+        let vmatrix = latex_to_expr("2\\begin{vmatrix}x&1\\\\1&x\\end{vmatrix}");
+        assert_eq!(vmatrix.collect().to_string(), "2*(x^{2}+-1)");
+    }
+
+    #[test]
+    fn test_inverse() {
+        let m = matrix("\\begin{pmatrix}2&1\\\\1&1\\end{pmatrix}");
+        assert_eq!(m.inverse().unwrap().to_string(), "[[1,-1],[-1,2]]");
+        let m = matrix("\\begin{pmatrix}a&0\\\\0&b\\end{pmatrix}");
+        assert_eq!(m.inverse().unwrap().to_string(), "[[a^{-1},0],[0,b^{-1}]]");
+        let m = matrix("\\begin{pmatrix}1&2\\\\3&4\\end{pmatrix}");
+        assert_eq!(m.inverse().unwrap().to_string(), "[[-2,1],[3/2,-1/2]]");
+        let m = matrix("\\begin{pmatrix}1&2\\\\2&4\\end{pmatrix}");
+        assert_eq!(m.inverse(), None);
+        let inv = latex_to_expr("\\begin{pmatrix}1&1\\\\0&1\\end{pmatrix}^{-1}");
+        assert_eq!(inv.collect().to_string(), "[[1,-1],[0,1]]");
+    }
+
+    #[test]
+    fn test_rref() {
+        let m = matrix("\\begin{pmatrix}1&2&3\\\\4&5&6\\\\7&8&9\\end{pmatrix}");
+        assert_eq!(m.rank(), 2);
+        assert_eq!(m.rref().to_string(), "[[1,0,-1],[0,1,2],[0,0,0]]");
+        let null = m.nullspace();
+        assert_eq!(null.len(), 1);
+        assert_eq!(null[0].to_string(), "[[1],[-2],[1]]");
+        let m = matrix("\\begin{pmatrix}a&b\\\\2a&2b\\end{pmatrix}");
+        assert_eq!(m.rank(), 1);
+        assert_eq!(m.nullspace()[0].to_string(), "[[-1*b*a^{-1}],[1]]");
+        let m = matrix("\\begin{pmatrix}x&1\\\\1&\\frac{1}{x}\\end{pmatrix}");
+        assert_eq!(m.det().to_string(), "0");
+        assert_eq!(m.rank(), 1);
+        assert_eq!(m.inverse(), None);
+        assert_eq!(m.nullspace()[0].to_string(), "[[-1*x^{-1}],[1]]");
+        let m = matrix("\\begin{pmatrix}\\sqrt{2}&1\\\\2&\\sqrt{2}\\end{pmatrix}");
+        assert_eq!(m.det().to_string(), "0");
+        assert_eq!(m.rank(), 1);
+        assert_eq!(m.inverse(), None);
+        assert_eq!(m.rref().to_string(), "[[1,1/2*2^{1/2}],[0,0]]");
+    }
+
+    #[test]
+    fn test_dependent_gens() {
+        let tests = [
+            [
+                "\\begin{pmatrix}\\sqrt{2}&1\\\\1&\\sqrt{2}\\end{pmatrix}^{-1}",
+                "[[2^{1/2},-1],[-1,2^{1/2}]]",
+            ],
+            [
+                "\\begin{vmatrix}\\sqrt{2}&1\\\\1&\\sqrt{2}\\end{vmatrix}",
+                "1",
+            ],
+            [
+                "\\begin{vmatrix}\\sqrt{2}&\\sqrt{3}\\\\\\sqrt{6}&3\\end{vmatrix}",
+                "0",
+            ],
+            ["\\begin{vmatrix}x&1\\\\1&\\frac{1}{x}\\end{vmatrix}", "0"],
+            // Singular since x*1/x-1 cancels
+            [
+                "\\begin{pmatrix}x&1\\\\1&\\frac{1}{x}\\end{pmatrix}^{-1}",
+                "([[x,1],[1,x^{-1}]])^{-1}",
+            ],
+        ];
+        tests.iter().for_each(|test| {
+            assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+        });
+    }
+
+    #[test]
+    fn test_eigenvalues() {
+        let m = matrix("\\begin{pmatrix}2&1\\\\1&2\\end{pmatrix}");
+        assert_eq!(m.charpoly(Sym::new("t")).to_string(), "t^{2}+-4*t+3");
+        let eigen = m.eigenvalues().unwrap();
+        assert_eq!(eigen[0].0.to_string(), "1");
+        assert_eq!(eigen[1].0.to_string(), "3");
+        let m = matrix("\\begin{pmatrix}1&1\\\\0&1\\end{pmatrix}");
+        assert_eq!(m.eigenvalues().unwrap()[0].1, 2);
+        assert_eq!(m.trace().to_string(), "2");
+    }
+}
@@ -0,0 +1,141 @@
+use super::func::call;
+use super::{Add, Assumptions, Expr, FuncName, Mul, Pow};
+
+impl<'a> Expr<'a> {
+    /// ln(ab)->ln(a)+ln(b) and ln(a^{p})->p*ln(a), where a and b must be positive under
+    /// assumptions unless force and ln(a^{p})->p*ln(a) also holds for real p with odd numerator
+    pub fn expand_log(&self, assumptions: &Assumptions<'a>, force: bool) -> Self {
+        let expr = self.map(|e| e.expand_log(assumptions, force)).collect();
+        let is_positive = |e: &Expr<'a>| assumptions.is_positive(e) == Some(true);
+        let arg = match &expr {
+            Expr::Func(func) if func.name == FuncName::Ln => &*func.arg,
+            _ => return expr,
+        };
+        let ln = |e: &Expr<'a>| call(FuncName::Ln, e.clone()).expand_log(assumptions, force);
+        match arg {
+            Expr::Mul(mul) if force || mul.exprs.iter().all(is_positive) => {
+                Add::new(mul.exprs.iter().map(ln).collect()).collect()
+            }
+            Expr::Pow(pow) => {
+                let odd = matches!(*pow.pow, Expr::Num(n) if n.is_real() && n.num % 2 != 0);
+                match force || odd || is_positive(&pow.body) {
+                    true => (*pow.pow.clone() * ln(&pow.body)).collect(),
+                    false => expr,
+                }
+            }
+            _ => expr,
+        }
+    }
+
+    /// p*ln(a)->ln(a^{p}) for real p and ln(a)+ln(b)->ln(ab),
+    /// where a and b must be positive under assumptions unless force or p has odd numerator
+    pub fn logcombine(&self, assumptions: &Assumptions<'a>, force: bool) -> Self {
+        let expr = self.map(|e| e.logcombine(assumptions, force)).collect();
+        let is_positive = |e: &Expr<'a>| assumptions.is_positive(e) == Some(true);
+        match &expr {
+            Expr::Mul(_) => {
+                let (c, body) = expr.detach_coeff();
+                match &body.exprs[..] {
+                    [Expr::Func(func)]
+                        if func.name == FuncName::Ln
+                            && c.is_real()
+                            && (force || c.num % 2 != 0 || is_positive(&func.arg)) =>
+                    {
+                        let pow = Pow::new(*func.arg.clone(), Expr::Num(c)).collect();
+                        call(FuncName::Ln, pow).collect()
+                    }
+                    _ => expr,
+                }
+            }
+            Expr::Add(add) => {
+                let combinable = |e: &Expr<'a>| match e {
+                    Expr::Func(func) if func.name == FuncName::Ln => {
+                        force || is_positive(&func.arg)
+                    }
+                    _ => false,
+                };
+                let (logs, mut rest): (Vec<_>, Vec<_>) =
+                    add.exprs.iter().cloned().partition(combinable);
+                if logs.len() < 2 {
+                    return expr;
+                }
+                let args = logs.into_iter().map(|e| match e {
+                    Expr::Func(func) => *func.arg,
+                    _ => unreachable!(),
+                });
+                rest.push(call(FuncName::Ln, Mul::new(args.collect()).collect()));
+                Add::new(rest).collect()
+            }
+            _ => expr,
+        }
+    }
+}
+
+#[test]
+fn test_log() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["e^{a}e^{b}", "e^{a+b}"],
+        ["\\exp{x}\\exp{-x}", "1"],
+        ["e^{\\ln x}", "x"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+    let mut assumptions = Assumptions::new();
+    assumptions.assume(super::Sym::new("a"), super::Property::Positive);
+    let expand =
+        |s: &str, force: bool| latex_to_expr(s).expand_log(&assumptions, force).to_string();
+    assert_eq!(expand("\\ln(2\\pi)", false), "ln(2)+ln(\\pi)");
+    assert_eq!(expand("\\ln(xy)", false), "ln(x*y)");
+    assert_eq!(expand("\\ln(xy)", true), "ln(x)+ln(y)");
+    assert_eq!(expand("\\ln(ae^{2})", false), "ln(a)+2");
+    assert_eq!(expand("\\ln x^{3}", false), "3*ln(x)");
+    assert_eq!(expand("\\ln x^{2}", false), "ln(x^{2})");
+    assert_eq!(expand("\\ln(x^{-1})", false), "-1*ln(x)");
+    assert_eq!(expand("\\ln(x^{2}y)", true), "2*ln(x)+ln(y)");
+
+    let combine =
+        |s: &str, force: bool| latex_to_expr(s).logcombine(&assumptions, force).to_string();
+    assert_eq!(combine("3\\ln x", false), "ln(x^{3})");
+    assert_eq!(combine("2\\ln x", false), "2*ln(x)");
+    assert_eq!(combine("2\\ln a+\\ln 3", false), "ln(3*a^{2})");
+    assert_eq!(combine("\\ln 2+\\ln 3", false), "ln(6)");
+    assert_eq!(combine("\\ln x+\\ln y", false), "ln(x)+ln(y)");
+    assert_eq!(combine("\\ln x+\\ln y-\\ln z", true), "ln(x*y*z^{-1})");
+}
+
+#[test]
+fn test_log_errors() {
+    use crate::parser::{latex_to_assumptions, latex_to_expr};
+    let assumptions = latex_to_assumptions("a>0,b<0").unwrap();
+    // Arguments which are not known to be positive are left as they are
+    let tests = [
+        ["\\ln(-2a)", "ln(-2*a)"],
+        ["\\ln(ab)", "ln(a*b)"],
+        ["\\ln(b^{2})", "ln(b^{2})"],
+    ];
+    tests.iter().for_each(|test| {
+        let expanded = latex_to_expr(test[0]).expand_log(&assumptions, false);
+        assert_eq!(expanded.to_string(), test[1]);
+    });
+    let tests = [
+        ["2\\ln b", "2*ln(b)"],
+        ["i\\ln a", "i*ln(a)"],
+        ["\\ln a+\\ln b", "ln(a)+ln(b)"],
+        ["\\ln a+\\ln(-b)", "ln(-1*a*b)"],
+    ];
+    tests.iter().for_each(|test| {
+        let combined = latex_to_expr(test[0]).logcombine(&assumptions, false);
+        assert_eq!(combined.to_string(), test[1]);
+    });
+    let errors = [
+        ["x>1", "Unknown assumption"],
+        ["x", "Assumption must be a relation"],
+        ["2>0", "Assumption must be about a symbol"],
+        ["\\sqrt>0", "Assumption must be about a symbol"],
+    ];
+    errors.iter().for_each(|test| {
+        assert_eq!(latex_to_assumptions(test[0]).err(), Some(test[1]));
+    });
+}
@@ -0,0 +1,230 @@
+use super::{Add, Expr, Mul, Num};
+use std::fmt::{Display, Formatter, Result};
+
+/// LaTeX environment of Matrix, where vmatrix stands for the determinant
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Delimiter {
+    Paren,
+    Bracket,
+    Vert,
+}
+
+impl Delimiter {
+    pub fn from_env(env: &str) -> Option<Self> {
+        match env {
+            "pmatrix" => Some(Delimiter::Paren),
+            "bmatrix" => Some(Delimiter::Bracket),
+            "vmatrix" => Some(Delimiter::Vert),
+            _ => None,
+        }
+    }
+
+    pub fn env(&self) -> &'static str {
+        match self {
+            Delimiter::Paren => "pmatrix",
+            Delimiter::Bracket => "bmatrix",
+            Delimiter::Vert => "vmatrix",
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Matrix<'a> {
+    pub rows: Vec<Vec<Expr<'a>>>,
+    pub delimiter: Delimiter,
+}
+
+impl<'a> Matrix<'a> {
+    /// Crash when rows have different length
+    pub fn new(rows: Vec<Vec<Expr<'a>>>, delimiter: Delimiter) -> Self {
+        if rows.iter().any(|row| row.len() != rows[0].len()) {
+            panic!("Every row of matrix must have the same length");
+        }
+        Matrix { rows, delimiter }
+    }
+
+    pub fn identity(n: usize, delimiter: Delimiter) -> Self {
+        let rows = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| Expr::Num(Num::new((i == j) as i64)))
+                    .collect()
+            })
+            .collect();
+        Matrix::new(rows, delimiter)
+    }
+
+    /// (number of rows, number of columns)
+    pub fn shape(&self) -> (usize, usize) {
+        (
+            self.rows.len(),
+            self.rows.first().map_or(0, |row| row.len()),
+        )
+    }
+
+    /// vmatrix is a scalar rather than a matrix
+    pub fn is_det(&self) -> bool {
+        self.delimiter == Delimiter::Vert
+    }
+
+    pub fn map(&self, f: impl Fn(&Expr<'a>) -> Expr<'a>) -> Self {
+        Matrix {
+            rows: self
+                .rows
+                .iter()
+                .map(|row| row.iter().map(&f).collect())
+                .collect(),
+            delimiter: self.delimiter,
+        }
+    }
+
+    /// Collect each element
+    pub fn collect(&self) -> Self {
+        self.map(|e| e.collect())
+    }
+
+    pub fn transpose(&self) -> Self {
+        let (m, n) = self.shape();
+        Matrix {
+            rows: (0..n)
+                .map(|j| (0..m).map(|i| self.rows[i][j].clone()).collect())
+                .collect(),
+            delimiter: self.delimiter,
+        }
+    }
+
+    /// Element-wise sum, None when shapes differ
+    pub fn add(&self, rhs: &Self) -> Option<Self> {
+        if self.shape() != rhs.shape() {
+            return None;
+        }
+        Some(Matrix {
+            rows: self
+                .rows
+                .iter()
+                .zip(&rhs.rows)
+                .map(|(r1, r2)| {
+                    r1.iter()
+                        .zip(r2)
+                        .map(|(x, y)| (x.clone() + y.clone()).collect())
+                        .collect()
+                })
+                .collect(),
+            delimiter: self.delimiter,
+        })
+    }
+
+    /// Matrix product, None when columns of self and rows of rhs differ
+    pub fn mul(&self, rhs: &Self) -> Option<Self> {
+        let (m, k) = self.shape();
+        let (l, n) = rhs.shape();
+        if k != l {
+            return None;
+        }
+        Some(Matrix {
+            rows: (0..m)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| {
+                            let terms = (0..k)
+                                .map(|t| {
+                                    Expr::Mul(Mul::new(vec![
+                                        self.rows[i][t].clone(),
+                                        rhs.rows[t][j].clone(),
+                                    ]))
+                                })
+                                .collect();
+                            Add::new(terms).collect()
+                        })
+                        .collect()
+                })
+                .collect(),
+            delimiter: self.delimiter,
+        })
+    }
+
+    /// Multiply every element by scalar
+    pub fn scale(&self, scalar: &Expr<'a>) -> Self {
+        self.map(|e| (scalar.clone() * e.clone()).collect())
+    }
+}
+
+impl<'a> Display for Matrix<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let row = row.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+                format!("[{}]", row.join(","))
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        match self.delimiter {
+            Delimiter::Vert => write!(f, "det[{}]", rows),
+            _ => write!(f, "[{}]", rows),
+        }
+    }
+}
+
+#[test]
+fn test_matrix() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["\\begin{pmatrix}1&2\\\\3&4\\end{pmatrix}", "[[1,2],[3,4]]"],
+        [
+            "\\begin{pmatrix}1&x\\\\y&1\\end{pmatrix}+\\begin{pmatrix}x&0\\\\0&x\\end{pmatrix}",
+            "[[1+x,x],[y,1+x]]",
+        ],
+        [
+            "\\begin{pmatrix}1&2\\\\3&4\\end{pmatrix}\\begin{pmatrix}x\\\\y\\end{pmatrix}",
+            "[[x+2*y],[3*x+4*y]]",
+        ],
+        ["2a\\begin{bmatrix}1&x\\end{bmatrix}", "[[2*a,2*a*x]]"],
+        ["\\begin{pmatrix}a&b\\\\c&d\\end{pmatrix}^{T}", "[[a,c],[b,d]]"],
+        [
+            "\\begin{pmatrix}1&1\\\\0&1\\end{pmatrix}^{3}",
+            "[[1,3],[0,1]]",
+        ],
+        [
+            "\\begin{pmatrix}0&1\\\\0&0\\end{pmatrix}\\begin{pmatrix}0&0\\\\1&0\\end{pmatrix}-\\begin{pmatrix}0&0\\\\1&0\\end{pmatrix}\\begin{pmatrix}0&1\\\\0&0\\end{pmatrix}",
+            "[[1,0],[0,-1]]",
+        ],
+        // Incompatible shapes, scalars added to matrices and singular inverses are left
+        [
+            "\\begin{pmatrix}1&2\\end{pmatrix}+\\begin{pmatrix}1\\\\2\\end{pmatrix}",
+            "[[1,2]]+[[1],[2]]",
+        ],
+        [
+            "\\begin{pmatrix}1&2\\end{pmatrix}\\begin{pmatrix}1&2\\end{pmatrix}",
+            "[[1,2]]*[[1,2]]",
+        ],
+        ["\\begin{pmatrix}1&2\\end{pmatrix}+1", "[[1,2]]+1"],
+        ["\\begin{pmatrix}1&2\\end{pmatrix}^{2}", "([[1,2]])^{2}"],
+        [
+            "\\begin{pmatrix}1&2\\\\2&4\\end{pmatrix}^{-1}",
+            "([[1,2],[2,4]])^{-1}",
+        ],
+        [
+            "\\begin{pmatrix}1&0\\\\0&1\\end{pmatrix}^{1000000000}",
+            "[[1,0],[0,1]]",
+        ],
+        [
+            "\\begin{pmatrix}1&1\\\\0&1\\end{pmatrix}^{1000000000}",
+            "[[1,1000000000],[0,1]]",
+        ],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+}
+
+#[test]
+fn test_ragged_matrix() {
+    use crate::parser::try_latex_to_expr;
+    assert_eq!(
+        try_latex_to_expr("\\begin{pmatrix}1&2\\\\3\\end{pmatrix}"),
+        Err("Every row of matrix must have the same length")
+    );
+    assert!(try_latex_to_expr("\\begin{pmatrix}1&2\\\\3&\\end{pmatrix}").is_ok());
+}
@@ -1,34 +1,188 @@
 mod add;
+mod assume;
+mod constant;
+mod diff;
+mod eval;
+pub mod func;
+mod integral;
+mod limit;
+mod linalg;
+mod log;
+pub mod matrix;
 mod mul;
 mod num;
 mod ops;
+mod piecewise;
+pub mod poly;
 mod pow;
+mod radical;
+mod relation;
+mod series;
+mod sum;
 pub mod sym;
+mod trig;
 pub use add::Add;
+pub use assume::{Assumptions, Property};
+pub use constant::Const;
+pub use func::{Func, FuncName};
+pub use integral::Integral;
+pub use limit::{Direction, Limit};
+pub use matrix::{Delimiter, Matrix};
 pub use mul::Mul;
+pub(crate) use num::gcd;
 pub use num::Num;
+pub use piecewise::{Cmp, Condition, Piecewise};
 pub use pow::Pow;
+pub use relation::Relation;
+pub use series::Series;
+use std::cell::Cell;
 use std::fmt::{Display, Formatter, Result};
+pub use sum::{Sum, SumOp};
 pub use sym::Sym;
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Expr<'a> {
     Num(Num),
     Sym(Sym<'a>),
+    Const(Const),
     Add(Add<'a>),
     Mul(Mul<'a>),
     Pow(Pow<'a>),
+    Matrix(Matrix<'a>),
+    Func(Func<'a>),
+    Integral(Integral<'a>),
+    Limit(Limit<'a>),
+    Sum(Sum<'a>),
+    Piecewise(Piecewise<'a>),
 }
 
 impl<'a> Expr<'a> {
-    fn collect(&self) -> Self {
+    pub fn collect(&self) -> Self {
         match self {
-            Expr::Add(add) => Expr::Add(add.collect()),
-            Expr::Mul(mul) => Expr::Mul(mul.collect()),
+            Expr::Add(add) => add.collect(),
+            Expr::Mul(mul) => mul.collect(),
+            Expr::Pow(pow) => pow.collect(),
+            Expr::Matrix(matrix) if matrix.is_det() => matrix.det().collect(),
+            Expr::Matrix(matrix) => Expr::Matrix(matrix.collect()),
+            Expr::Func(func) => func.collect(),
+            Expr::Integral(integral) => integral.collect(),
+            Expr::Limit(limit) => limit.collect(),
+            Expr::Sum(sum) => sum.collect(),
+            Expr::Piecewise(piecewise) => piecewise.collect(),
             x => x.clone(),
         }
     }
 
+    /// Distribute Mul over Add and Pow(Add,n) for positive integer n, then collect
+    pub fn expand(&self) -> Self {
+        match self {
+            Expr::Add(add) => Add::new(add.exprs.iter().map(|e| e.expand()).collect()).collect(),
+            Expr::Mul(mul) => {
+                let terms = mul
+                    .exprs
+                    .iter()
+                    .fold(vec![Expr::Num(Num::new(1))], |terms, expr| {
+                        let summands = match expr.expand() {
+                            Expr::Add(add) => add.exprs,
+                            x => vec![x],
+                        };
+                        terms
+                            .iter()
+                            .flat_map(|t| {
+                                summands.iter().map(|s| (t.clone() * s.clone()).collect())
+                            })
+                            .collect()
+                    });
+                Add::new(terms).collect()
+            }
+            Expr::Pow(pow) => match (pow.body.expand(), pow.pow.expand()) {
+                (Expr::Add(add), Expr::Num(n)) if n.is_integer() && n.num > 1 => {
+                    Expr::Mul(Mul::new(vec![Expr::Add(add); n.num as usize])).expand()
+                }
+                (body, pow) => Pow::new(body, pow).collect(),
+            },
+            Expr::Matrix(matrix) => Expr::Matrix(matrix.map(|e| e.expand())),
+            Expr::Func(_)
+            | Expr::Integral(_)
+            | Expr::Limit(_)
+            | Expr::Sum(_)
+            | Expr::Piecewise(_) => self.map(|e| e.expand()).collect(),
+            x => x.clone(),
+        }
+    }
+
+    /// Check whether sym appears in expr
+    pub fn contains(&self, sym: &Sym) -> bool {
+        match self {
+            Expr::Sym(x) => x == sym,
+            Expr::Num(_) | Expr::Const(_) => false,
+            Expr::Add(add) => add.exprs.iter().any(|e| e.contains(sym)),
+            Expr::Mul(mul) => mul.exprs.iter().any(|e| e.contains(sym)),
+            Expr::Pow(pow) => pow.body.contains(sym) || pow.pow.contains(sym),
+            Expr::Matrix(matrix) => matrix.rows.iter().flatten().any(|e| e.contains(sym)),
+            Expr::Func(func) => func.arg.contains(sym),
+            Expr::Integral(integral) => integral.contains(sym),
+            Expr::Limit(limit) => limit.contains(sym),
+            Expr::Sum(sum) => sum.contains(sym),
+            Expr::Piecewise(piecewise) => piecewise.contains(sym),
+        }
+    }
+
+    /// Apply f to every direct sub expression without collecting
+    pub fn map(&self, f: impl Fn(&Expr<'a>) -> Expr<'a>) -> Self {
+        match self {
+            Expr::Add(add) => Expr::Add(Add::new(add.exprs.iter().map(&f).collect())),
+            Expr::Mul(mul) => Expr::Mul(Mul::new(mul.exprs.iter().map(&f).collect())),
+            Expr::Pow(pow) => Expr::Pow(Pow::new(f(&pow.body), f(&pow.pow))),
+            Expr::Matrix(matrix) => Expr::Matrix(matrix.map(f)),
+            Expr::Func(func) => Expr::Func(Func::new(func.name, f(&func.arg))),
+            Expr::Integral(integral) => Expr::Integral(integral.map(f)),
+            Expr::Limit(limit) => Expr::Limit(limit.map(f)),
+            Expr::Sum(sum) => Expr::Sum(sum.map(f)),
+            Expr::Piecewise(piecewise) => Expr::Piecewise(piecewise.map(f)),
+            x => x.clone(),
+        }
+    }
+
+    /// Replace every occurrence of from by to without collecting
+    pub fn replace(&self, from: &Expr<'a>, to: &Expr<'a>) -> Self {
+        match self {
+            x if x == from => to.clone(),
+            x => x.map(|e| e.replace(from, to)),
+        }
+    }
+
+    /// Substitute value for sym and collect
+    pub fn subs(&self, sym: Sym<'a>, value: &Expr<'a>) -> Self {
+        self.replace(&Expr::Sym(sym), value).collect()
+    }
+
+    /// Whether expr has 0^{0}, 0^{-n} or ln(0) which collect leaves unevaluated
+    pub fn is_undefined(&self) -> bool {
+        match self {
+            Expr::Func(func)
+                if func.name == FuncName::Ln
+                    && matches!(*func.arg, Expr::Num(n) if n.is_zero()) =>
+            {
+                true
+            }
+            Expr::Pow(pow) => match (&*pow.body, &*pow.pow) {
+                (Expr::Num(x), Expr::Num(n)) if x.is_zero() && n.is_real() => {
+                    n.is_zero() || n.is_negative()
+                }
+                _ => pow.body.is_undefined() || pow.pow.is_undefined(),
+            },
+            expr => {
+                let found = Cell::new(false);
+                expr.map(|e| {
+                    found.set(found.get() || e.is_undefined());
+                    e.clone()
+                });
+                found.get()
+            }
+        }
+    }
+
     /// Pow(x,y)->(x,y) otherwise expr->(expr,1)
     fn detach_pow(&self) -> (Self, Self) {
         match self {
@@ -40,6 +194,7 @@ impl<'a> Expr<'a> {
     /// Multi(num,x1,...,xn)->(num,Multi(x1,...,xn)) otherwise expr->(1,expr)
     pub fn detach_coeff(&self) -> (Num, Mul<'a>) {
         match self {
+            Expr::Num(x) => (*x, Mul::new(vec![])),
             Expr::Mul(mul) => match mul.exprs[0] {
                 Expr::Num(x) => (x, Mul::new(mul.exprs[1..].to_vec())),
                 _ => (Num::new(1), Mul::new(mul.exprs.clone())),
@@ -49,101 +204,83 @@ impl<'a> Expr<'a> {
     }
 }
 
+/// Num+Num is evaluated unless it overflows and Add is flattened
 impl<'a> std::ops::Add<Expr<'a>> for Expr<'a> {
     type Output = Expr<'a>;
-    fn add(self, _rhs: Expr<'a>) -> Expr {
+    fn add(self, _rhs: Expr<'a>) -> Expr<'a> {
         match (self, _rhs) {
-            (Expr::Num(x), Expr::Num(y)) => Expr::Num(x + y),
-            (Expr::Num(x), Expr::Sym(y)) => Expr::Add(x + y),
-            (Expr::Num(x), Expr::Add(y)) => Expr::Add(x + y),
-            (Expr::Num(x), Expr::Mul(y)) => Expr::Add(x + y),
-            (Expr::Num(x), Expr::Pow(y)) => Expr::Add(x + y),
-            (Expr::Sym(x), Expr::Num(y)) => Expr::Add(x + y),
-            (Expr::Sym(x), Expr::Sym(y)) => Expr::Add(x + y),
-            (Expr::Sym(x), Expr::Add(y)) => Expr::Add(x + y),
-            (Expr::Sym(x), Expr::Mul(y)) => Expr::Add(x + y),
-            (Expr::Sym(x), Expr::Pow(y)) => Expr::Add(x + y),
-            (Expr::Add(x), Expr::Num(y)) => Expr::Add(x + y),
-            (Expr::Add(x), Expr::Sym(y)) => Expr::Add(x + y),
+            (Expr::Num(x), Expr::Num(y)) if x.checked_add(y).is_some() => Expr::Num(x + y),
             (Expr::Add(x), Expr::Add(y)) => Expr::Add(x + y),
-            (Expr::Add(x), Expr::Mul(y)) => Expr::Add(x + y),
-            (Expr::Add(x), Expr::Pow(y)) => Expr::Add(x + y),
-            (Expr::Mul(x), Expr::Num(y)) => Expr::Add(x + y),
-            (Expr::Mul(x), Expr::Sym(y)) => Expr::Add(x + y),
-            (Expr::Mul(x), Expr::Add(y)) => Expr::Add(x + y),
-            (Expr::Mul(x), Expr::Mul(y)) => Expr::Add(x + y),
-            (Expr::Mul(x), Expr::Pow(y)) => Expr::Add(x + y),
-            (Expr::Pow(x), Expr::Num(y)) => Expr::Add(x + y),
-            (Expr::Pow(x), Expr::Sym(y)) => Expr::Add(x + y),
-            (Expr::Pow(x), Expr::Add(y)) => Expr::Add(x + y),
-            (Expr::Pow(x), Expr::Mul(y)) => Expr::Add(x + y),
-            (Expr::Pow(x), Expr::Pow(y)) => Expr::Add(x + y),
+            (Expr::Add(x), y) => Expr::Add(Add::new([x.exprs, vec![y]].concat())),
+            (x, Expr::Add(y)) => Expr::Add(Add::new([vec![x], y.exprs].concat())),
+            (x, y) => Expr::Add(Add::new(vec![x, y])),
         }
     }
 }
 
+/// Num*Num is evaluated unless it overflows and Mul is flattened
+/// where a Num is also multiplied into the leading Num of Mul such as -1*(2*x)->-2*x
 impl<'a> std::ops::Mul<Expr<'a>> for Expr<'a> {
     type Output = Expr<'a>;
     fn mul(self, rhs: Expr<'a>) -> Self::Output {
         match (self, rhs) {
-            (Expr::Num(x), Expr::Num(y)) => Expr::Num(x * y),
-            (Expr::Num(x), Expr::Sym(y)) => Expr::Mul(x * y),
-            (Expr::Num(x), Expr::Add(y)) => Expr::Mul(x * y),
-            (Expr::Num(x), Expr::Mul(y)) => Expr::Mul(x * y),
-            (Expr::Num(x), Expr::Pow(y)) => Expr::Mul(x * y),
-            (Expr::Sym(x), Expr::Num(y)) => Expr::Mul(x * y),
-            (Expr::Sym(x), Expr::Sym(y)) => Expr::Mul(x * y),
-            (Expr::Sym(x), Expr::Add(y)) => Expr::Mul(x * y),
-            (Expr::Sym(x), Expr::Mul(y)) => Expr::Mul(x * y),
-            (Expr::Sym(x), Expr::Pow(y)) => Expr::Mul(x * y),
-            (Expr::Add(x), Expr::Num(y)) => Expr::Mul(x * y),
-            (Expr::Add(x), Expr::Sym(y)) => Expr::Mul(x * y),
-            (Expr::Add(x), Expr::Add(y)) => Expr::Mul(x * y),
-            (Expr::Add(x), Expr::Mul(y)) => Expr::Mul(x * y),
-            (Expr::Add(x), Expr::Pow(y)) => Expr::Mul(x * y),
-            (Expr::Mul(x), Expr::Num(y)) => Expr::Mul(x * y),
-            (Expr::Mul(x), Expr::Sym(y)) => Expr::Mul(x * y),
-            (Expr::Mul(x), Expr::Add(y)) => Expr::Mul(x * y),
+            (Expr::Num(x), Expr::Num(y)) if x.checked_mul(y).is_some() => Expr::Num(x * y),
+            (Expr::Num(x), Expr::Mul(y)) if matches!(y.exprs.first(), Some(Expr::Num(c)) if x.checked_mul(*c).is_some()) =>
+            {
+                let rest = Mul::new(y.exprs[1..].to_vec());
+                Expr::Num(x) * y.exprs[0].clone() * Expr::Mul(rest)
+            }
             (Expr::Mul(x), Expr::Mul(y)) => Expr::Mul(x * y),
-            (Expr::Mul(x), Expr::Pow(y)) => Expr::Mul(x * y),
-            (Expr::Pow(x), Expr::Num(y)) => Expr::Mul(x * y),
-            (Expr::Pow(x), Expr::Sym(y)) => Expr::Mul(x * y),
-            (Expr::Pow(x), Expr::Add(y)) => Expr::Mul(x * y),
-            (Expr::Pow(x), Expr::Mul(y)) => Expr::Mul(x * y),
-            (Expr::Pow(x), Expr::Pow(y)) => Expr::Mul(x * y),
+            (Expr::Mul(x), y) => Expr::Mul(Mul::new([x.exprs, vec![y]].concat())),
+            (x, Expr::Mul(y)) => Expr::Mul(Mul::new([vec![x], y.exprs].concat())),
+            (x, y) => Expr::Mul(Mul::new(vec![x, y])),
         }
     }
 }
 
+impl<'a> std::ops::Neg for Expr<'a> {
+    type Output = Expr<'a>;
+    fn neg(self) -> Self::Output {
+        Expr::Num(Num::new(-1)) * self
+    }
+}
+
+impl<'a> std::ops::Sub<Expr<'a>> for Expr<'a> {
+    type Output = Expr<'a>;
+    fn sub(self, rhs: Expr<'a>) -> Self::Output {
+        self + -rhs
+    }
+}
+
+/// x/y->Mul(x,Pow(y,-1)) while Num/Num is evaluated unless y is zero or it overflows
+impl<'a> std::ops::Div<Expr<'a>> for Expr<'a> {
+    type Output = Expr<'a>;
+    fn div(self, rhs: Expr<'a>) -> Self::Output {
+        match (self, rhs) {
+            (Expr::Num(x), Expr::Num(y)) if x.checked_div(y).is_some() => Expr::Num(x / y),
+            (x, y) => x * Expr::Pow(Pow::new(y, Expr::Num(Num::new(-1)))),
+        }
+    }
+}
+
+/// Num^Num is evaluated for integer exponent unless it is undefined or overflows
+/// and a root raised to an integer is (x^{1/q})^{p}->x^{p/q}
 impl<'a> std::ops::BitXor<Expr<'a>> for Expr<'a> {
     type Output = Expr<'a>;
     fn bitxor(self, rhs: Expr<'a>) -> Self::Output {
         match (self, rhs) {
-            (Expr::Num(x), Expr::Num(y)) => Expr::Num(x ^ y),
-            (Expr::Num(x), Expr::Sym(y)) => Expr::Pow(x ^ y),
-            (Expr::Num(x), Expr::Add(y)) => Expr::Pow(x ^ y),
-            (Expr::Num(x), Expr::Mul(y)) => Expr::Pow(x ^ y),
-            (Expr::Num(x), Expr::Pow(y)) => Expr::Pow(x ^ y),
-            (Expr::Sym(x), Expr::Num(y)) => Expr::Pow(x ^ y),
-            (Expr::Sym(x), Expr::Sym(y)) => Expr::Pow(x ^ y),
-            (Expr::Sym(x), Expr::Add(y)) => Expr::Pow(x ^ y),
-            (Expr::Sym(x), Expr::Mul(y)) => Expr::Pow(x ^ y),
-            (Expr::Sym(x), Expr::Pow(y)) => Expr::Pow(x ^ y),
-            (Expr::Add(x), Expr::Num(y)) => Expr::Pow(x ^ y),
-            (Expr::Add(x), Expr::Sym(y)) => Expr::Pow(x ^ y),
-            (Expr::Add(x), Expr::Add(y)) => Expr::Pow(x ^ y),
-            (Expr::Add(x), Expr::Mul(y)) => Expr::Pow(x ^ y),
-            (Expr::Add(x), Expr::Pow(y)) => Expr::Pow(x ^ y),
-            (Expr::Mul(x), Expr::Num(y)) => Expr::Pow(x ^ y),
-            (Expr::Mul(x), Expr::Sym(y)) => Expr::Pow(x ^ y),
-            (Expr::Mul(x), Expr::Add(y)) => Expr::Pow(x ^ y),
-            (Expr::Mul(x), Expr::Mul(y)) => Expr::Pow(x ^ y),
-            (Expr::Mul(x), Expr::Pow(y)) => Expr::Pow(x ^ y),
-            (Expr::Pow(x), Expr::Num(y)) => Expr::Pow(x ^ y),
-            (Expr::Pow(x), Expr::Sym(y)) => Expr::Pow(x ^ y),
-            (Expr::Pow(x), Expr::Add(y)) => Expr::Pow(x ^ y),
-            (Expr::Pow(x), Expr::Mul(y)) => Expr::Pow(x ^ y),
-            (Expr::Pow(x), Expr::Pow(y)) => Expr::Pow(x ^ y),
+            (Expr::Num(x), Expr::Num(y)) if y.is_integer() && x.powi(y.num).is_ok() => {
+                Expr::Num(x ^ y)
+            }
+            (Expr::Pow(pow), Expr::Num(p)) if p.is_integer() => match *pow.pow {
+                Expr::Num(r)
+                    if r.is_real() && r.num == 1 && r.den > 1 && p.checked_mul(r).is_some() =>
+                {
+                    Expr::Pow(Pow::new(*pow.body, Expr::Num(p * r)))
+                }
+                _ => Expr::Pow(Pow::new(Expr::Pow(pow), Expr::Num(p))),
+            },
+            (x, y) => Expr::Pow(Pow::new(x, y)),
         }
     }
 }
@@ -155,7 +292,14 @@ impl<'a> Display for Expr<'a> {
             Expr::Add(add) => write!(f, "{}", add),
             Expr::Mul(mul) => write!(f, "{}", mul),
             Expr::Num(num) => write!(f, "{}", num),
+            Expr::Const(c) => write!(f, "{}", c),
             Expr::Pow(pow) => write!(f, "{}", pow),
+            Expr::Matrix(matrix) => write!(f, "{}", matrix),
+            Expr::Func(func) => write!(f, "{}", func),
+            Expr::Integral(integral) => write!(f, "{}", integral),
+            Expr::Limit(limit) => write!(f, "{}", limit),
+            Expr::Sum(sum) => write!(f, "{}", sum),
+            Expr::Piecewise(piecewise) => write!(f, "{}", piecewise),
         }
     }
 }
@@ -171,3 +315,40 @@ fn test_expr() {
     assert_eq!(Expr::Sym(x).detach_pow().0.to_string(), "x");
     assert_eq!(Expr::Sym(x).detach_pow().1.to_string(), "1");
 }
+
+#[test]
+fn test_expand() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["\\left(x+y\\right)^{2}", "x^{2}+2*x*y+y^{2}"],
+        ["(a+b)(a-b)", "a^{2}+-1*b^{2}"],
+        ["x(x+1)-x^{2}", "x"],
+        ["(x+1)^{3}-(x+1)^{3}", "0"],
+        ["2ab-3ba", "-1*a*b"],
+        ["x^{2}x^{-2}+\\frac{1}{2}+\\frac{1}{2}", "2"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).expand().to_string(), test[1]);
+    });
+}
+
+#[test]
+fn test_overflow() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["2^{62}+2^{62}", "4611686018427387904+4611686018427387904"],
+        ["2^{40}2^{40}", "1099511627776*1099511627776"],
+        [
+            "\\frac{1}{3037000500}+\\frac{1}{3037000499}",
+            "6074000999/9223372033963249500",
+        ],
+        [
+            "12345678901234567890",
+            "12*1000000000000000000+345678901234567890",
+        ],
+        ["2^{62}+2^{62}-2^{62}", "4611686018427387904"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+}
@@ -1,7 +1,8 @@
-use super::{num::Num, pow::Pow, Expr};
+use super::limit::infinity;
+use super::{num::Num, pow::Pow, radical, Const, Expr, FuncName};
 use std::fmt::{Display, Formatter, Result};
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Mul<'a> {
     pub exprs: Vec<Expr<'a>>,
 }
@@ -14,7 +15,7 @@ impl<'a> Mul<'a> {
                 .into_iter()
                 .filter(|x| {
                     if let Expr::Num(n) = x {
-                        n.num != 1
+                        !n.is_one()
                     } else {
                         true
                     }
@@ -23,15 +24,25 @@ impl<'a> Mul<'a> {
         }
     }
 
-    /// Mul(x1,x1,x3)->Mul(Pow(x1,2),x3) otherwise crash
+    /// Mul(x1,x1,x3)->Mul(Pow(x1,2),x3) otherwise crash,
+    /// where exp(a) is regarded as e^{a} so that e^{a}exp(b)->e^{a+b}
     fn to_pow(&self) -> Self {
+        if self.exprs.is_empty() {
+            return self.clone();
+        }
+        let detach = |expr: &Expr<'a>| match expr {
+            Expr::Func(func) if func.name == FuncName::Exp => {
+                (Expr::Const(Const::E), *func.arg.clone())
+            }
+            expr => expr.detach_pow(),
+        };
         let mut result = vec![self.exprs[0].clone()];
         (1..self.exprs.len()).for_each(|i| {
             for j in 0..result.len() {
-                let (body1, pow1) = self.exprs[i].detach_pow();
-                let (body2, pow2) = result[j].detach_pow();
+                let (body1, pow1) = detach(&self.exprs[i]);
+                let (body2, pow2) = detach(&result[j]);
                 if body1 == body2 {
-                    result[j] = Expr::Pow(Pow::new(body1, pow1 + pow2));
+                    result[j] = Expr::Pow(Pow::new(body1, pow2 + pow1));
                     break;
                 } else if j == result.len() - 1 {
                     result.push(self.exprs[i].clone())
@@ -41,22 +52,100 @@ impl<'a> Mul<'a> {
         Mul::new(result)
     }
 
-    /// Multi(x1,x1,num1,x3,num2)->Multi(num1*num2,Pow(x1,2),x3) otherwise Expr->Expr
-    /// depend on multi_to_pow
-    pub fn collect(&self) -> Self {
+    /// Mul(x1,x1,num1,x3,num2)->Mul(num1*num2,Pow(x1,2),x3), nested Mul is flattened
+    /// while numbers whose product overflows are kept as factors
+    /// depend on to_pow
+    pub fn collect(&self) -> Expr<'a> {
         let mut coef = Num::new(1);
-        let mut body = vec![Expr::Num(Num::new(1))];
-        self.exprs.iter().for_each(|expr| match expr {
-            Expr::Num(n) => coef = coef * *n,
-            expr => body.push(expr.clone()),
+        let mut body = vec![];
+        let mut push = |expr: Expr<'a>, body: &mut Vec<Expr<'a>>| {
+            let exprs = match expr {
+                Expr::Mul(mul) => mul.exprs,
+                expr => vec![expr],
+            };
+            exprs.into_iter().for_each(|expr| match expr {
+                Expr::Num(n) if coef.checked_mul(n).is_some() => coef = coef * n,
+                expr => body.push(expr),
+            })
+        };
+        let mut collected = vec![];
+        self.exprs
+            .iter()
+            .for_each(|expr| push(expr.collect(), &mut collected));
+        if collected
+            .iter()
+            .any(|e| matches!(e, Expr::Matrix(m) if !m.is_det()))
+        {
+            return Mul::mul_matrices(coef, collected);
+        }
+        Mul::new(collected)
+            .to_pow()
+            .exprs
+            .iter()
+            .for_each(|expr| push(expr.collect(), &mut body));
+        // 0*\infty is indeterminate and c*\infty->sign(c)*\infty
+        let infinite = body.contains(&infinity());
+        if coef.is_zero() && !infinite {
+            return Expr::Num(coef);
+        }
+        if infinite && body.len() == 1 && coef.is_real() && !coef.is_zero() {
+            coef = Num::new(coef.num.signum());
+        }
+        if let Some(merged) = radical::merge(&body) {
+            return Mul::new([vec![Expr::Num(coef)], merged].concat()).collect();
+        }
+        Mul::new([vec![Expr::Num(coef)], body].concat()).to_expr()
+    }
+
+    /// Matrices are multiplied in order and then scaled by the other factors,
+    /// left unevaluated when their shapes are incompatible
+    fn mul_matrices(coef: Num, exprs: Vec<Expr<'a>>) -> Expr<'a> {
+        let mut scalars = vec![Expr::Num(coef)];
+        let mut matrices = vec![];
+        exprs.iter().for_each(|expr| match expr {
+            Expr::Matrix(m) if !m.is_det() => matrices.push(m),
+            x => scalars.push(x.clone()),
         });
-        body[0] = Expr::Num(coef);
-        Mul::new(body).to_pow()
+        let product = matrices[1..]
+            .iter()
+            .try_fold(matrices[0].clone(), |acc, m| acc.mul(m));
+        match product {
+            Some(product) => Expr::Matrix(product.scale(&Mul::new(scalars).collect())),
+            None => Expr::Mul(Mul::new([vec![Expr::Num(coef)], exprs].concat())),
+        }
+    }
+
+    /// Mul()->1, Mul(x)->x otherwise Mul(x1,...,xn)
+    pub fn to_expr(self) -> Expr<'a> {
+        match self.exprs.len() {
+            0 => Expr::Num(Num::new(1)),
+            1 => self.exprs[0].clone(),
+            _ => Expr::Mul(self),
+        }
+    }
+
+    /// Equality regardless of the order of factors
+    pub fn eq_unordered(&self, other: &Mul) -> bool {
+        let mut rest = other.exprs.clone();
+        self.exprs.len() == other.exprs.len()
+            && self
+                .exprs
+                .iter()
+                .all(|expr| match rest.iter().position(|x| x == expr) {
+                    Some(i) => {
+                        rest.remove(i);
+                        true
+                    }
+                    None => false,
+                })
     }
 }
 
 impl<'a> Display for Mul<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.exprs.is_empty() {
+            return write!(f, "1");
+        }
         let mut result = String::new();
         for expr in &self.exprs {
             result = if let Expr::Add(_) = expr {
@@ -77,4 +166,8 @@ fn test_mul() {
     let n3 = Num::new(3);
     assert_eq!((x * y * y).to_pow().to_string(), "x*y^{2}");
     assert_eq!((x * y * n3 * y).collect().to_string(), "3*x*y^{2}");
+    assert_eq!((x * n3 * (y * n3)).collect().to_string(), "9*x*y");
+    assert_eq!((x * Num::new(0) * y).collect().to_string(), "0");
+    assert_eq!((x * (x ^ Num::new(-1))).collect().to_string(), "1");
+    assert!((x * y).eq_unordered(&(y * x)));
 }
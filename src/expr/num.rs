@@ -1,18 +1,249 @@
 use std::fmt::{Display, Formatter, Result};
+/// Gaussian rational (num+im*i)/den where i is the imaginary unit
 #[derive(Clone, Copy, PartialEq, Debug, Eq)]
 pub struct Num {
-    pub num: i32,
+    pub num: i64,
+    pub im: i64,
+    pub den: i64,
 }
 
 impl Num {
-    pub fn new(num: i32) -> Self {
-        Num { num }
+    pub fn new(num: i64) -> Self {
+        Num { num, im: 0, den: 1 }
     }
+
+    /// num/den reduced to lowest terms with positive den, crash when den is zero
+    pub fn frac(num: i64, den: i64) -> Self {
+        Num::gaussian(num, 0, den)
+    }
+
+    /// num/den of numbers read from input, Err when den is zero
+    pub fn checked_frac(num: i64, den: i64) -> std::result::Result<Self, &'static str> {
+        match den {
+            0 => Err("Division by zero"),
+            den => Num::reduce(num as i128, 0, den as i128).ok_or("Overflow in Num"),
+        }
+    }
+
+    /// (num+im*i)/den reduced to lowest terms with positive den, crash when den is zero
+    pub fn gaussian(num: i64, im: i64, den: i64) -> Self {
+        if den == 0 {
+            panic!("Division by zero");
+        }
+        Num::reduce(num as i128, im as i128, den as i128).expect("Overflow in Num")
+    }
+
+    /// Reduced (num+im*i)/den, None when den is zero or a part exceeds i64::MAX in magnitude
+    fn reduce(num: i128, im: i128, den: i128) -> Option<Self> {
+        if den == 0 {
+            return None;
+        }
+        let g = [num, im, den]
+            .iter()
+            .fold(0, |g, x| wide_gcd(g, x.unsigned_abs()));
+        let part = |x: i128| {
+            Some(i64::try_from(x.unsigned_abs() / g).ok()? * (x.signum() * den.signum()) as i64)
+        };
+        Some(Num {
+            num: part(num)?,
+            im: part(im)?,
+            den: part(den)?,
+        })
+    }
+
+    /// (num,im,den) widened so that the product of two parts cannot overflow
+    fn wide(&self) -> (i128, i128, i128) {
+        (self.num as i128, self.im as i128, self.den as i128)
+    }
+
+    /// re+im*i
+    pub fn complex(re: Num, im: Num) -> Self {
+        re + Num::i() * im
+    }
+
+    /// The imaginary unit
+    pub fn i() -> Self {
+        Num {
+            num: 0,
+            im: 1,
+            den: 1,
+        }
+    }
+
+    pub fn re(&self) -> Self {
+        Num::frac(self.num, self.den)
+    }
+
+    pub fn im(&self) -> Self {
+        Num::frac(self.im, self.den)
+    }
+
+    pub fn conj(&self) -> Self {
+        Num::gaussian(self.num, -self.im, self.den)
+    }
+
+    /// |x|^2=re^2+im^2
+    pub fn norm(&self) -> Self {
+        *self * self.conj()
+    }
+
+    /// |x| if it is rational
+    pub fn modulus(&self) -> Option<Self> {
+        match self.is_real() {
+            true => Some(self.abs()),
+            false => self.norm().sqrt(),
+        }
+    }
+
+    /// Nonnegative square root if x is the square of a rational
+    pub fn sqrt(&self) -> Option<Self> {
+        match self.is_real() && !self.is_negative() {
+            true => Some(Num::frac(isqrt(self.num)?, isqrt(self.den)?)),
+            false => None,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0 && self.im == 0
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.num == 1 && self.im == 0 && self.den == 1
+    }
+
+    pub fn is_real(&self) -> bool {
+        self.im == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1 && self.im == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.im == 0 && self.num < 0
+    }
+
+    /// |x| of real x, crash when x is not real
+    pub fn abs(&self) -> Self {
+        if !self.is_real() {
+            panic!("Tried to take abs of complex number");
+        }
+        Num::frac(self.num.abs(), self.den)
+    }
+
+    /// 1/x, crash when x is zero
+    pub fn recip(&self) -> Self {
+        Num::new(1) / *self
+    }
+
+    /// x^n for integer n, Err for 0^0, 0^n with n<0 and overflow
+    pub fn powi(&self, n: i64) -> std::result::Result<Self, &'static str> {
+        const OVERFLOW: &str = "Overflow in power";
+        match n {
+            0 if self.is_zero() => Err("0^0 is undefined"),
+            n if n < 0 && self.is_zero() => Err("Division by zero"),
+            n if n < 0 => self.recip().powi(n.checked_neg().ok_or(OVERFLOW)?),
+            // 1, -1, i and -i cycle with period 4
+            n if self.num.abs() + self.im.abs() == 1 && self.den == 1 => {
+                (0..n % 4).try_fold(Num::new(1), |acc, _| acc.checked_mul(*self).ok_or(OVERFLOW))
+            }
+            n if self.is_real() => {
+                let n = u32::try_from(n).map_err(|_| OVERFLOW)?;
+                match (self.num.checked_pow(n), self.den.checked_pow(n)) {
+                    (Some(num), Some(den)) => Ok(Num::frac(num, den)),
+                    _ => Err(OVERFLOW),
+                }
+            }
+            n => {
+                let (mut base, mut n, mut acc) = (*self, n, Num::new(1));
+                while n > 0 {
+                    if n % 2 == 1 {
+                        acc = acc.checked_mul(base).ok_or(OVERFLOW)?;
+                    }
+                    n /= 2;
+                    if n > 0 {
+                        base = base.checked_mul(base).ok_or(OVERFLOW)?;
+                    }
+                }
+                Ok(acc)
+            }
+        }
+    }
+
+    /// x+y, None on overflow
+    pub fn checked_add(&self, rhs: Num) -> Option<Self> {
+        let (x, y) = (self.wide(), rhs.wide());
+        Num::reduce(
+            (x.0 * y.2).checked_add(y.0 * x.2)?,
+            (x.1 * y.2).checked_add(y.1 * x.2)?,
+            x.2 * y.2,
+        )
+    }
+
+    /// x*y, None on overflow
+    pub fn checked_mul(&self, rhs: Num) -> Option<Self> {
+        let (x, y) = (self.wide(), rhs.wide());
+        Num::reduce(
+            (x.0 * y.0).checked_sub(x.1 * y.1)?,
+            (x.0 * y.1).checked_add(x.1 * y.0)?,
+            x.2 * y.2,
+        )
+    }
+
+    /// x/y, None on overflow or when y is zero
+    pub fn checked_div(&self, rhs: Num) -> Option<Self> {
+        let (x, y) = (self.wide(), rhs.wide());
+        Num::reduce(
+            (x.0 * y.0).checked_add(x.1 * y.1)?.checked_mul(y.2)?,
+            (x.1 * y.0).checked_sub(x.0 * y.1)?.checked_mul(y.2)?,
+            (y.0 * y.0).checked_add(y.1 * y.1)?.checked_mul(x.2)?,
+        )
+    }
+}
+
+/// Square root of a perfect square n>=0
+fn isqrt(n: i64) -> Option<i64> {
+    let r = (n as f64).sqrt().round() as i64;
+    (r * r == n).then_some(r)
+}
+
+/// Greatest common divisor of magnitudes where gcd(0,0)=0
+fn wide_gcd(a: u128, b: u128) -> u128 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
 }
 
+/// Greatest common divisor which is always positive (gcd(0,0)=1)
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    if a == 0 {
+        1
+    } else {
+        a
+    }
+}
+
+/// 1/2, 2*i or (1+-1/2*i)
 impl Display for Num {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}", self.num)
+        let im = match self.im() {
+            im if im.is_zero() => String::new(),
+            im if im.is_one() => String::from("i"),
+            im if im == Num::new(-1) => String::from("-i"),
+            im => format!("{}*i", im),
+        };
+        match (self.re(), im) {
+            (re, im) if im.is_empty() && re.den == 1 => write!(f, "{}", re.num),
+            (re, im) if im.is_empty() => write!(f, "{}/{}", re.num, re.den),
+            (re, im) if re.is_zero() => write!(f, "{}", im),
+            (re, im) => write!(f, "({}+{})", re, im),
+        }
     }
 }
 
@@ -23,4 +254,48 @@ fn test_num() {
     let y = Expr::Num(Num::new(2));
     assert_eq!((x + y).to_string(), "3");
     assert_eq!((Num::new(3) ^ Num::new(3)).to_string(), "27");
+    assert_eq!(Num::frac(2, -4).to_string(), "-1/2");
+    assert_eq!((Num::frac(1, 2) + Num::frac(1, 3)).to_string(), "5/6");
+    assert_eq!((Num::frac(2, 3) * Num::frac(3, 4)).to_string(), "1/2");
+    assert_eq!((Num::frac(1, 2) - Num::new(1)).to_string(), "-1/2");
+    assert_eq!((Num::new(1) / Num::new(3)).to_string(), "1/3");
+    assert_eq!(Num::new(2).powi(-2), Ok(Num::frac(1, 4)));
+    assert_eq!(Num::frac(-2, 3).powi(-3), Ok(Num::frac(-27, 8)));
+    assert_eq!(Num::new(-1).powi(1_000_000_000_001), Ok(Num::new(-1)));
+    assert!(Num::new(0).powi(-1).is_err());
+    assert!(Num::new(0).powi(0).is_err());
+    assert!(Num::new(2).powi(64).is_err());
+    assert!(Num::new(2).powi(1 << 40).is_err());
+    let big = Num::new(1 << 62);
+    assert_eq!(big.checked_add(big), None);
+    assert_eq!(big.checked_add(-big), Some(Num::new(0)));
+    assert_eq!(Num::new(1 << 40).checked_mul(Num::new(1 << 40)), None);
+    assert_eq!(
+        Num::frac(1, 3037000500).checked_add(Num::frac(1, 3037000499)),
+        Some(Num::frac(6074000999, 9223372033963249500))
+    );
+    assert_eq!(
+        Num::frac(i64::MAX, 2).checked_mul(Num::frac(2, i64::MAX)),
+        Some(Num::new(1))
+    );
+    assert_eq!(Num::new(1).checked_div(Num::new(0)), None);
+    assert_eq!(Num::checked_frac(3, -6), Ok(Num::frac(-1, 2)));
+    assert_eq!(Num::checked_frac(1, 0), Err("Division by zero"));
+}
+
+#[test]
+fn test_complex() {
+    let i = Num::i();
+    let z = Num::complex(Num::new(3), Num::new(4));
+    assert_eq!((i * i).to_string(), "-1");
+    assert_eq!(i.powi(3), Ok(-i));
+    assert_eq!(i.powi(-1_000_000_000_002), Ok(Num::new(-1)));
+    assert_eq!(i.recip(), -i);
+    assert_eq!(z.to_string(), "(3+4*i)");
+    assert_eq!((z * z.conj()).to_string(), "25");
+    assert_eq!((Num::new(1) / z).to_string(), "(3/25+-4/25*i)");
+    assert_eq!((z + -z.re()).to_string(), "4*i");
+    assert_eq!(z.modulus(), Some(Num::new(5)));
+    assert_eq!((Num::new(1) + i).modulus(), None);
+    assert_eq!((Num::new(1) + i).powi(2), Ok(Num::new(2) * i));
 }
@@ -48,10 +48,26 @@ impl_ops_add!(Mul<'a>; Sym<'a>,Pow<'a>,Mul<'a>,Num);
 impl_ops_add!(Pow<'a>; Sym<'a>,Pow<'a>,Mul<'a>,Num);
 impl_ops_add!(Num; Sym<'a>,Pow<'a>,Mul<'a>);
 
-/// Num + Num
+/// Num + Num, crash on overflow
 impl ops::Add<Num> for Num {
     type Output = Num;
     fn add(self, _rhs: Num) -> Num {
-        Num::new(self.num + _rhs.num)
+        self.checked_add(_rhs).expect("Overflow in addition")
+    }
+}
+
+/// -Num
+impl ops::Neg for Num {
+    type Output = Num;
+    fn neg(self) -> Num {
+        Num::gaussian(-self.num, -self.im, self.den)
+    }
+}
+
+/// Num - Num
+impl ops::Sub<Num> for Num {
+    type Output = Num;
+    fn sub(self, rhs: Num) -> Num {
+        self + -rhs
     }
 }
@@ -47,17 +47,29 @@ impl_ops_mul!(Add<'a>; Sym<'a>,Pow<'a>,Add<'a>,Num);
 impl_ops_mul!(Pow<'a>; Sym<'a>,Pow<'a>,Add<'a>,Num);
 impl_ops_mul!(Num; Sym<'a>,Pow<'a>,Add<'a>);
 
+/// Num * Num, crash on overflow
 impl ops::Mul<Num> for Num {
     type Output = Num;
-    fn mul<'a>(self, rhs: Num) -> Self::Output {
-        Num::new(self.num * rhs.num)
+    fn mul(self, rhs: Num) -> Self::Output {
+        self.checked_mul(rhs).expect("Overflow in multiplication")
+    }
+}
+
+/// Num / Num, crash when rhs is zero or on overflow
+impl ops::Div<Num> for Num {
+    type Output = Num;
+    fn div(self, rhs: Num) -> Self::Output {
+        if rhs.is_zero() {
+            panic!("Division by zero");
+        }
+        self.checked_div(rhs).expect("Overflow in division")
     }
 }
 
 impl<'a> ops::Mul<Mul<'a>> for Mul<'a> {
     type Output = Mul<'a>;
     fn mul(self, rhs: Mul<'a>) -> Self::Output {
-        Mul::new(vec![self.exprs, rhs.exprs].concat())
+        Mul::new([self.exprs, rhs.exprs].concat())
     }
 }
 
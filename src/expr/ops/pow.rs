@@ -23,9 +23,16 @@ impl_ops_pow!(Mul<'a>; Sym<'a>,Pow<'a>,Add<'a>,Mul<'a>,Num);
 impl_ops_pow!(Pow<'a>; Sym<'a>,Pow<'a>,Add<'a>,Mul<'a>,Num);
 impl_ops_pow!(Num; Sym<'a>,Pow<'a>,Add<'a>,Mul<'a>);
 
+/// Num^Num for integer exponent, crash on 0^0, 0^{-n}, overflow and non-integer exponent
 impl BitXor<Num> for Num {
     type Output = Num;
     fn bitxor<'a>(self, rhs: Num) -> Self::Output {
-        Num::new(self.num.pow(rhs.num as u32))
+        if !rhs.is_integer() {
+            panic!("Tried to raise number to non-integer power");
+        }
+        match self.powi(rhs.num) {
+            Ok(x) => x,
+            Err(err) => panic!("{}", err),
+        }
     }
 }
@@ -0,0 +1,217 @@
+use super::{Assumptions, Expr, Sym};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result};
+
+/// Relative difference of the sides below which a numeric comparison is undecided
+const TOLERANCE: f64 = 1e-9;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Cmp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Cmp {
+    /// <, \leq, >, \geq, = and \neq including the short forms \le, \ge, \ne, \lt and \gt
+    pub fn from_command(command: &str) -> Option<Self> {
+        match command {
+            "<" | "\\lt" => Some(Cmp::Lt),
+            "\\leq" | "\\le" => Some(Cmp::Le),
+            ">" | "\\gt" => Some(Cmp::Gt),
+            "\\geq" | "\\ge" => Some(Cmp::Ge),
+            "=" => Some(Cmp::Eq),
+            "\\neq" | "\\ne" => Some(Cmp::Ne),
+            _ => None,
+        }
+    }
+
+    pub fn command(&self) -> &'static str {
+        match self {
+            Cmp::Lt => "<",
+            Cmp::Le => "\\leq",
+            Cmp::Gt => ">",
+            Cmp::Ge => "\\geq",
+            Cmp::Eq => "=",
+            Cmp::Ne => "\\neq",
+        }
+    }
+
+    /// Whether lhs-rhs having the sign satisfies the comparison
+    pub fn accepts(&self, sign: Ordering) -> bool {
+        match self {
+            Cmp::Lt => sign == Ordering::Less,
+            Cmp::Le => sign != Ordering::Greater,
+            Cmp::Gt => sign == Ordering::Greater,
+            Cmp::Ge => sign != Ordering::Less,
+            Cmp::Eq => sign == Ordering::Equal,
+            Cmp::Ne => sign != Ordering::Equal,
+        }
+    }
+}
+
+/// lhs cmp rhs such as x\geq 0
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Condition<'a> {
+    pub lhs: Expr<'a>,
+    pub cmp: Cmp,
+    pub rhs: Expr<'a>,
+}
+
+impl<'a> Condition<'a> {
+    pub fn new(lhs: Expr<'a>, cmp: Cmp, rhs: Expr<'a>) -> Self {
+        Condition { lhs, cmp, rhs }
+    }
+
+    pub fn map(&self, f: impl Fn(&Expr<'a>) -> Expr<'a>) -> Self {
+        Condition::new(f(&self.lhs), self.cmp, f(&self.rhs))
+    }
+
+    /// Some(true) or Some(false) if decided by assumptions or numerically, otherwise None
+    pub fn holds(&self, assumptions: &Assumptions) -> Option<bool> {
+        let diff = (self.lhs.clone() - self.rhs.clone()).collect();
+        let sign = match assumptions.sign(&diff) {
+            Some(sign) => sign,
+            // Numeric differences are compared unless they are too close to zero
+            None => {
+                let x = diff.eval().ok()?;
+                let scale = [&self.lhs, &self.rhs]
+                    .iter()
+                    .map(|e| e.eval().map_or(0.0, f64::abs))
+                    .fold(1.0, f64::max);
+                if x.abs() <= TOLERANCE * scale {
+                    return None;
+                }
+                x.partial_cmp(&0.0)?
+            }
+        };
+        Some(self.cmp.accepts(sign))
+    }
+}
+
+impl<'a> Display for Condition<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let cmp = match self.cmp {
+            Cmp::Le => "<=",
+            Cmp::Ge => ">=",
+            Cmp::Ne => "!=",
+            cmp => cmp.command(),
+        };
+        write!(f, "{}{}{}", self.lhs, cmp, self.rhs)
+    }
+}
+
+/// Value of the first piece whose condition holds where None is otherwise
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Piecewise<'a> {
+    pub pieces: Vec<(Expr<'a>, Option<Condition<'a>>)>,
+}
+
+impl<'a> Piecewise<'a> {
+    pub fn new(pieces: Vec<(Expr<'a>, Option<Condition<'a>>)>) -> Self {
+        Piecewise { pieces }
+    }
+
+    pub fn map(&self, f: impl Fn(&Expr<'a>) -> Expr<'a>) -> Self {
+        Piecewise::new(
+            self.pieces
+                .iter()
+                .map(|(value, cond)| (f(value), cond.as_ref().map(|c| c.map(&f))))
+                .collect(),
+        )
+    }
+
+    pub fn contains(&self, sym: &Sym) -> bool {
+        self.pieces.iter().any(|(value, cond)| {
+            value.contains(sym)
+                || cond
+                    .as_ref()
+                    .is_some_and(|c| c.lhs.contains(sym) || c.rhs.contains(sym))
+        })
+    }
+
+    pub fn collect(&self) -> Expr<'a> {
+        self.map(|e| e.collect()).simplify(&Assumptions::new())
+    }
+
+    /// Drop pieces whose conditions are false and the ones after a condition that holds,
+    /// then the value itself if the first piece holds, while a piecewise without pieces
+    /// or whose conditions are all false is left as it is since it is undefined everywhere
+    pub fn simplify(&self, assumptions: &Assumptions) -> Expr<'a> {
+        let mut pieces = vec![];
+        for (value, cond) in &self.pieces {
+            match cond.as_ref().map(|c| c.holds(assumptions)) {
+                Some(Some(false)) => continue,
+                Some(None) => pieces.push((value.clone(), cond.clone())),
+                _ => {
+                    pieces.push((value.clone(), None));
+                    break;
+                }
+            }
+        }
+        match &pieces[..] {
+            [(value, None)] => value.clone(),
+            [] => Expr::Piecewise(self.clone()),
+            _ => Expr::Piecewise(Piecewise::new(pieces)),
+        }
+    }
+}
+
+impl<'a> Display for Piecewise<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let pieces: Vec<String> = self
+            .pieces
+            .iter()
+            .map(|(value, cond)| match cond {
+                Some(cond) => format!("{} if {}", value, cond),
+                None => format!("{} otherwise", value),
+            })
+            .collect();
+        write!(f, "piecewise({})", pieces.join(", "))
+    }
+}
+
+#[test]
+fn test_piecewise() {
+    use super::Property;
+    use crate::parser::latex_to_expr;
+    let abs = "\\begin{cases}x&x\\geq 0\\\\-x&\\text{otherwise}\\end{cases}";
+    assert_eq!(
+        latex_to_expr(abs).collect().to_string(),
+        "piecewise(x if x>=0, -1*x otherwise)"
+    );
+    let tests = [
+        [
+            "\\begin{cases}1&2>1\\\\0&\\text{otherwise}\\end{cases}",
+            "1",
+        ],
+        [
+            "\\begin{cases}1&\\pi<3\\\\x&x=0\\\\0&\\text{otherwise}\\end{cases}",
+            "piecewise(x if x=0, 0 otherwise)",
+        ],
+        ["\\begin{cases}1&e\\neq 2\\end{cases}", "1"],
+        [
+            "\\begin{cases}1&x<0\\\\2&x<-1\\end{cases}",
+            "piecewise(1 if x<0, 2 if x<-1)",
+        ],
+        [
+            "\\begin{cases}1&1<0\\\\2&2<1\\end{cases}",
+            "piecewise(1 if 1<0, 2 if 2<1)",
+        ],
+        [
+            "\\begin{cases}1&\\tan(\\frac{\\pi}{5})\\tan(\\frac{2\\pi}{5})\\neq\\sqrt{5}\\\\0&\\text{otherwise}\\end{cases}",
+            "piecewise(1 if tan(1/5*\\pi)*tan(2/5*\\pi)!=5^{1/2}, 0 otherwise)",
+        ],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+    let empty = Piecewise::new(vec![]);
+    assert_eq!(empty.collect(), Expr::Piecewise(empty));
+    let mut assumptions = Assumptions::new();
+    assumptions.assume(Sym::new("x"), Property::Negative);
+    assert_eq!(latex_to_expr(abs).refine(&assumptions).to_string(), "-1*x");
+}
@@ -0,0 +1,305 @@
+use super::{num::gcd, Add, Expr, Mul, Num, Pow};
+
+/// Multivariate polynomial with rational coefficients.
+/// Generators are symbols or any sub expression which is not a polynomial (e.g. x^{y}).
+/// Polys are only combined with each other when they share the same gens.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Poly<'a> {
+    pub gens: Vec<Expr<'a>>,
+    /// (exponents of gens, coefficient) in descending lex order without zero coefficient
+    pub terms: Vec<(Vec<u32>, Num)>,
+}
+
+impl<'a> Poly<'a> {
+    pub fn zero(gens: &[Expr<'a>]) -> Self {
+        Poly {
+            gens: gens.to_vec(),
+            terms: vec![],
+        }
+    }
+
+    pub fn monomial(exps: Vec<u32>, coef: Num, gens: &[Expr<'a>]) -> Self {
+        Poly {
+            gens: gens.to_vec(),
+            terms: vec![(exps, coef)],
+        }
+        .normalize()
+    }
+
+    pub fn constant(coef: Num, gens: &[Expr<'a>]) -> Self {
+        Poly::monomial(vec![0; gens.len()], coef, gens)
+    }
+
+    /// i-th generator as a polynomial
+    pub fn gen(i: usize, gens: &[Expr<'a>]) -> Self {
+        let mut exps = vec![0; gens.len()];
+        exps[i] = 1;
+        Poly::monomial(exps, Num::new(1), gens)
+    }
+
+    /// Push generators of collected expr which are not in gens yet
+    pub fn find_gens(expr: &Expr<'a>, gens: &mut Vec<Expr<'a>>) {
+        match expr {
+            Expr::Num(_) => {}
+            Expr::Add(add) => add.exprs.iter().for_each(|e| Poly::find_gens(e, gens)),
+            Expr::Mul(mul) => mul.exprs.iter().for_each(|e| Poly::find_gens(e, gens)),
+            Expr::Pow(pow) if as_degree(&pow.pow).is_some() => Poly::find_gens(&pow.body, gens),
+            x => {
+                if !gens.contains(x) {
+                    gens.push(x.clone())
+                }
+            }
+        }
+    }
+
+    /// None if collected expr has a sub expression which is not in gens or a coefficient overflows
+    pub fn from_expr(expr: &Expr<'a>, gens: &[Expr<'a>]) -> Option<Self> {
+        match expr {
+            Expr::Num(n) => Some(Poly::constant(*n, gens)),
+            Expr::Add(add) => add.exprs.iter().try_fold(Poly::zero(gens), |acc, e| {
+                acc.checked_add(&Poly::from_expr(e, gens)?)
+            }),
+            Expr::Mul(mul) => mul
+                .exprs
+                .iter()
+                .try_fold(Poly::constant(Num::new(1), gens), |acc, e| {
+                    acc.checked_mul(&Poly::from_expr(e, gens)?)
+                }),
+            Expr::Pow(pow) if as_degree(&pow.pow).is_some() => {
+                Poly::from_expr(&pow.body, gens)?.checked_pow(as_degree(&pow.pow)?)
+            }
+            x => gens.iter().position(|g| g == x).map(|i| Poly::gen(i, gens)),
+        }
+    }
+
+    pub fn to_expr(&self) -> Expr<'a> {
+        Add::new(
+            self.terms
+                .iter()
+                .map(|(exps, coef)| {
+                    let mut factors = vec![Expr::Num(*coef)];
+                    exps.iter().enumerate().for_each(|(i, e)| match e {
+                        0 => {}
+                        1 => factors.push(self.gens[i].clone()),
+                        e => factors.push(
+                            Pow::new(self.gens[i].clone(), Expr::Num(Num::new(*e as i64)))
+                                .collect(),
+                        ),
+                    });
+                    Mul::new(factors).collect()
+                })
+                .collect(),
+        )
+        .to_expr()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Some(c) if poly is the constant c
+    pub fn as_constant(&self) -> Option<Num> {
+        match &self.terms[..] {
+            [] => Some(Num::new(0)),
+            [(exps, coef)] if exps.iter().all(|e| *e == 0) => Some(*coef),
+            _ => None,
+        }
+    }
+
+    /// Sort terms in descending lex order, merge equal monomials and drop zeros
+    fn normalize(self) -> Self {
+        self.checked_normalize().expect("Overflow in Num")
+    }
+
+    /// normalize, None when merging coefficients overflows
+    fn checked_normalize(mut self) -> Option<Self> {
+        self.terms.sort_by(|a, b| b.0.cmp(&a.0));
+        let mut terms: Vec<(Vec<u32>, Num)> = vec![];
+        for (exps, coef) in self.terms {
+            match terms.last_mut() {
+                Some(last) if last.0 == exps => last.1 = last.1.checked_add(coef)?,
+                _ => terms.push((exps, coef)),
+            }
+        }
+        self.terms = terms.into_iter().filter(|t| !t.1.is_zero()).collect();
+        Some(self)
+    }
+
+    pub fn add(&self, rhs: &Self) -> Self {
+        self.checked_add(rhs).expect("Overflow in Num")
+    }
+
+    /// self+rhs, None on overflow
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Poly {
+            gens: self.gens.clone(),
+            terms: [self.terms.clone(), rhs.terms.clone()].concat(),
+        }
+        .checked_normalize()
+    }
+
+    pub fn neg(&self) -> Self {
+        self.scale(Num::new(-1))
+    }
+
+    pub fn sub(&self, rhs: &Self) -> Self {
+        self.add(&rhs.neg())
+    }
+
+    /// self-rhs, None on overflow
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        self.checked_add(&rhs.neg())
+    }
+
+    pub fn scale(&self, c: Num) -> Self {
+        Poly {
+            gens: self.gens.clone(),
+            terms: self
+                .terms
+                .iter()
+                .map(|(e, k)| (e.clone(), *k * c))
+                .collect(),
+        }
+        .normalize()
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Self {
+        self.checked_mul(rhs).expect("Overflow in Num")
+    }
+
+    /// self*rhs, None on overflow
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        let mut terms = vec![];
+        for (e1, c1) in &self.terms {
+            for (e2, c2) in &rhs.terms {
+                let exps = e1.iter().zip(e2).map(|(a, b)| a + b).collect();
+                terms.push((exps, c1.checked_mul(*c2)?));
+            }
+        }
+        Poly {
+            gens: self.gens.clone(),
+            terms,
+        }
+        .checked_normalize()
+    }
+
+    /// self^n, None on overflow
+    pub fn checked_pow(&self, n: u32) -> Option<Self> {
+        (0..n).try_fold(Poly::constant(Num::new(1), &self.gens), |acc, _| {
+            acc.checked_mul(self)
+        })
+    }
+
+    /// Some(q) if self=q*rhs, crash when rhs is zero
+    pub fn div_exact(&self, rhs: &Self) -> Option<Self> {
+        self.checked_div_exact(rhs).expect("Overflow in Num")
+    }
+
+    /// Ok(Some(q)) if self=q*rhs, Err on overflow, crash when rhs is zero
+    pub fn checked_div_exact(&self, rhs: &Self) -> Result<Option<Self>, &'static str> {
+        const OVERFLOW: &str = "Overflow";
+        let (lead_exps, lead_coef) = &rhs.terms[0];
+        let mut rest = self.clone();
+        let mut quotient = Poly::zero(&self.gens);
+        while let Some((exps, coef)) = rest.terms.first() {
+            if exps.iter().zip(lead_exps).any(|(a, b)| a < b) {
+                return Ok(None);
+            }
+            let exps = exps.iter().zip(lead_exps).map(|(a, b)| a - b).collect();
+            let coef = coef.checked_div(*lead_coef).ok_or(OVERFLOW)?;
+            let t = Poly::monomial(exps, coef, &self.gens);
+            rest = rest
+                .checked_sub(&t.checked_mul(rhs).ok_or(OVERFLOW)?)
+                .ok_or(OVERFLOW)?;
+            quotient = quotient.checked_add(&t).ok_or(OVERFLOW)?;
+        }
+        Ok(Some(quotient))
+    }
+
+    /// Positive rational c and minimal exponents so that self/(c*x^exps) has Gaussian integer coefficients
+    /// whose real and imaginary parts are coprime
+    fn content(&self) -> (Num, Vec<u32>) {
+        let exps = (0..self.gens.len())
+            .map(|i| self.terms.iter().map(|t| t.0[i]).min().unwrap_or(0))
+            .collect();
+        let num = self
+            .terms
+            .iter()
+            .fold(0, |acc, t| gcd(acc, gcd(t.1.num, t.1.im)));
+        let den = self
+            .terms
+            .iter()
+            .fold(1, |acc, t| acc / gcd(acc, t.1.den) * t.1.den);
+        (Num::frac(num, den), exps)
+    }
+
+    /// Cancel common monomial and numeric factors of num/den
+    /// so that the leading coefficient of den becomes positive
+    pub fn cancel(num: &Self, den: &Self) -> (Self, Self) {
+        if let Some(q) = num.div_exact(den) {
+            return (q, Poly::constant(Num::new(1), &num.gens));
+        }
+        let (c1, e1) = num.content();
+        let (c2, e2) = den.content();
+        let c = Num::frac(gcd(c1.num, c2.num), c1.den / gcd(c1.den, c2.den) * c2.den);
+        let c = if den.terms[0].1.is_negative() { -c } else { c };
+        let exps: Vec<u32> = e1.iter().zip(&e2).map(|(a, b)| *a.min(b)).collect();
+        let common = Poly::monomial(exps, c, &num.gens);
+        (
+            num.div_exact(&common).unwrap_or_else(|| num.clone()),
+            den.div_exact(&common).unwrap_or_else(|| den.clone()),
+        )
+    }
+
+    /// num/den as a collected expr after cancellation
+    pub fn ratio_to_expr(num: &Self, den: &Self) -> Expr<'a> {
+        let (num, den) = Poly::cancel(num, den);
+        match den.as_constant() {
+            Some(d) => num.scale(d.recip()).to_expr(),
+            None => (num.to_expr() / den.to_expr()).collect(),
+        }
+    }
+}
+
+/// Exponent of Pow which is a non-negative integer
+fn as_degree(expr: &Expr) -> Option<u32> {
+    match expr {
+        Expr::Num(n) if n.is_integer() && n.num >= 0 => Some(n.num as u32),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test_poly {
+    use super::Poly;
+    use crate::parser::latex_to_expr;
+
+    /// Polys sharing the gens of all inputs
+    fn polys(latex: &[&'static str]) -> Vec<Poly<'static>> {
+        let exprs: Vec<_> = latex.iter().map(|l| latex_to_expr(l).collect()).collect();
+        let mut gens = vec![];
+        exprs.iter().for_each(|e| Poly::find_gens(e, &mut gens));
+        exprs
+            .iter()
+            .map(|e| Poly::from_expr(e, &gens).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_poly() {
+        let p = polys(&["(x+y)^{2}", "x+y", "x-y"]);
+        assert_eq!(p[0].to_expr().to_string(), "x^{2}+2*x*y+y^{2}");
+        assert_eq!(p[0].div_exact(&p[1]).unwrap().to_expr().to_string(), "x+y");
+        assert_eq!(p[0].div_exact(&p[2]), None);
+        assert_eq!(p[0].sub(&p[1].mul(&p[1])).to_expr().to_string(), "0");
+        assert_eq!(p[1].as_constant(), None);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let p = polys(&["4ax", "-6ay-2a^{2}"]);
+        let (num, den) = Poly::cancel(&p[0], &p[1]);
+        assert_eq!(num.to_expr().to_string(), "-2*x");
+        assert_eq!(den.to_expr().to_string(), "a+3*y");
+    }
+}
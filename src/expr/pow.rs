@@ -1,14 +1,15 @@
-use super::{mul::Mul, Expr};
+use super::limit::infinity;
+use super::{matrix::Matrix, mul::Mul, num::Num, radical, sym::Sym, Const, Expr, FuncName};
 use std::fmt::{Display, Formatter, Result};
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Pow<'a> {
     pub body: Box<Expr<'a>>,
     pub pow: Box<Expr<'a>>,
 }
 
 impl<'a> Pow<'a> {
-    pub fn to_mul(&self) -> Mul {
+    pub fn to_mul(&self) -> Mul<'a> {
         match *self.pow {
             Expr::Num(x) if x.num > 0 => Mul::new(vec![*self.body.clone(); x.num as usize]),
             _ => panic!("Tried to convert pow to mul with non-integer pow"),
@@ -22,11 +23,118 @@ impl<'a> Pow<'a> {
             pow: Box::new(pow),
         }
     }
+
+    /// Pow(x,0)->1, Pow(x,1)->x, Pow(num1,num2)->num for integer num2 while 0^0 and 0^{-n} are left,
+    /// radicals of numbers are simplified, 1/(a+b\sqrt{c}) is rationalized and \sqrt{a+b\sqrt{c}} is denested,
+    /// Pow(Pow(x,y),n)->Pow(x,y*n) and Pow(Mul(x,y),n)->Mul(Pow(x,n),Pow(y,n)) for integer n
+    pub fn collect(&self) -> Expr<'a> {
+        match (self.body.collect(), self.pow.collect()) {
+            (Expr::Matrix(m), pow) if !m.is_det() => Pow::collect_matrix(m, pow),
+            (Expr::Num(x), Expr::Num(n)) if x.is_zero() && n.is_real() => match n {
+                n if n.is_zero() || n.is_negative() => {
+                    Expr::Pow(Pow::new(Expr::Num(x), Expr::Num(n)))
+                }
+                _ => Expr::Num(x),
+            },
+            (body, pow) if body == infinity() || pow == infinity() => {
+                Pow::collect_infinity(body, pow)
+            }
+            (_, Expr::Num(n)) if n.is_zero() => Expr::Num(Num::new(1)),
+            (body, Expr::Num(n)) if n.is_one() => body,
+            (Expr::Num(x), _) if x.is_one() => Expr::Num(x),
+            (Expr::Const(Const::E), Expr::Func(func)) if func.name == FuncName::Ln => *func.arg,
+            // Overflowing powers are left as they are
+            (Expr::Num(x), Expr::Num(n)) if n.is_integer() => match x.powi(n.num) {
+                Ok(x) => Expr::Num(x),
+                Err(_) => Expr::Pow(Pow::new(Expr::Num(x), Expr::Num(n))),
+            },
+            (Expr::Num(x), Expr::Num(n)) if x.is_real() && n.is_real() => {
+                match radical::power(x, n) {
+                    Some(x) => x,
+                    None => Expr::Pow(Pow::new(Expr::Num(x), Expr::Num(n))),
+                }
+            }
+            (Expr::Add(add), Expr::Num(n)) if n == Num::new(-1) || n == Num::frac(1, 2) => {
+                let simplified = match n == Num::new(-1) {
+                    true => radical::rationalize(&add),
+                    false => radical::denest(&add),
+                };
+                simplified.unwrap_or_else(|| Expr::Pow(Pow::new(Expr::Add(add), Expr::Num(n))))
+            }
+            (Expr::Pow(pow), Expr::Num(n)) if n.is_integer() => {
+                Pow::new(*pow.body, *pow.pow * Expr::Num(n)).collect()
+            }
+            (Expr::Mul(mul), Expr::Num(n)) if n.is_integer() => Mul::new(
+                mul.exprs
+                    .into_iter()
+                    .map(|expr| Expr::Pow(Pow::new(expr, Expr::Num(n))))
+                    .collect(),
+            )
+            .collect(),
+            (body, pow) => Expr::Pow(Pow::new(body, pow)),
+        }
+    }
+}
+
+impl<'a> Pow<'a> {
+    /// \infty^{n}->\infty and \infty^{-n}->0 for positive n, x^{\infty}->0 for 0<=x<1
+    /// and x^{\infty}->\infty for x>1 while \infty^{0} and 1^{\infty} are left
+    fn collect_infinity(body: Expr<'a>, pow: Expr<'a>) -> Expr<'a> {
+        let zero = || Expr::Num(Num::new(0));
+        match (&body, &pow) {
+            (_, Expr::Num(n)) if n.is_real() && !n.is_zero() && n.is_negative() => zero(),
+            (_, Expr::Num(n)) if n.is_real() && !n.is_zero() => infinity(),
+            (Expr::Num(x), _) if x.is_real() && !x.is_negative() && x.num < x.den => zero(),
+            (Expr::Num(x), _) if x.is_real() && x.num > x.den => infinity(),
+            (x, _) if *x == infinity() && pow == infinity() => infinity(),
+            _ => Expr::Pow(Pow::new(body, pow)),
+        }
+    }
+
+    /// Matrix^{T} is the transpose and Matrix^{n} is the power of square matrix for integer n
+    /// where negative n is the power of the inverse if it exists
+    fn collect_matrix(matrix: Matrix<'a>, pow: Expr<'a>) -> Expr<'a> {
+        let (m, n) = matrix.shape();
+        match pow {
+            Expr::Sym(sym) if sym == Sym::new("T") || sym == Sym::new("\\top") => {
+                Expr::Matrix(matrix.transpose())
+            }
+            // Exponentiation by squaring
+            Expr::Num(k) if k.is_integer() && !k.is_negative() && m == n => {
+                let product = |a: &Matrix<'a>, b: &Matrix<'a>| {
+                    a.mul(b)
+                        .expect("Square matrices of the same size can be multiplied")
+                };
+                let (mut base, mut k) = (matrix, k.num);
+                let mut power = Matrix::identity(n, base.delimiter);
+                while k > 0 {
+                    if k % 2 == 1 {
+                        power = product(&power, &base);
+                    }
+                    k /= 2;
+                    if k > 0 {
+                        base = product(&base, &base);
+                    }
+                }
+                Expr::Matrix(power)
+            }
+            Expr::Num(k) if k.is_integer() && m == n => match matrix.inverse() {
+                Some(inverse) => Pow::collect_matrix(inverse, Expr::Num(-k)),
+                None => Expr::Pow(Pow::new(Expr::Matrix(matrix), Expr::Num(k))),
+            },
+            pow => Expr::Pow(Pow::new(Expr::Matrix(matrix), pow)),
+        }
+    }
 }
 
 impl<'a> Display for Pow<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        if let Expr::Sym(_) | Expr::Num(_) = *self.body {
+        if let Expr::Num(n) = *self.body {
+            match (n.re().is_zero() && !n.is_real()) || n.is_negative() {
+                true => write!(f, "({})^{{{}}}", n, self.pow),
+                false => write!(f, "{}^{{{}}}", n, self.pow),
+            }
+        } else if let Expr::Sym(_) | Expr::Const(_) | Expr::Func(_) = *self.body {
             write!(f, "{}", format_args!("{}^{{{}}}", self.body, self.pow))
         } else {
             write!(f, "{}", format_args!("({})^{{{}}}", self.body, self.pow))
@@ -53,4 +161,47 @@ fn test_pow() {
         Expr::Num(Num::new(2)),
     );
     assert_eq!(pow.to_mul().to_string(), "(x+y)*(x+y)");
+
+    let n2 = Num::new(2);
+    assert_eq!((x ^ Num::new(0)).collect().to_string(), "1");
+    assert_eq!((x ^ Num::new(1)).collect().to_string(), "x");
+    let pow = Pow::new(Expr::Num(n2), Expr::Num(Num::new(-2)));
+    assert_eq!(pow.collect().to_string(), "1/4");
+    assert_eq!(((x ^ y) ^ n2).collect().to_string(), "x^{2*y}");
+    assert_eq!(((x * y) ^ n2).collect().to_string(), "x^{2}*y^{2}");
+}
+
+#[test]
+fn test_num_pow() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["2^{-1}", "1/2"],
+        ["\\left(-\\frac{2}{3}\\right)^{-3}", "-27/8"],
+        ["2^{\\frac{1}{2}}", "2^{1/2}"],
+        ["4^{\\frac{3}{2}}", "8"],
+        ["8^{-\\frac{2}{3}}", "1/4"],
+        ["0^{\\frac{1}{2}}", "0"],
+        ["(-1)^{1000000000001}", "-1"],
+        ["2^{100}", "2^{100}"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+}
+
+#[test]
+fn test_zero_pow() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["0^{0}", "0^{0}"],
+        ["0^{-1}", "0^{-1}"],
+        ["\\frac{1}{0}", "0^{-1}"],
+        ["\\frac{x}{0}+1", "x*0^{-1}+1"],
+        ["0^{2}", "0"],
+    ];
+    tests.iter().for_each(|test| {
+        let expr = latex_to_expr(test[0]).collect();
+        assert_eq!(expr.to_string(), test[1]);
+        assert_eq!(expr.is_undefined(), test[1] != "0");
+    });
 }
@@ -0,0 +1,202 @@
+use super::{Add, Expr, Mul, Num, Pow};
+
+/// Largest factor searched when extracting perfect powers from a radicand
+const MAX_FACTOR: i64 = 10000;
+
+/// x^{e} for real x and non-integer real e as c*s^{1/q} with c the extracted part,
+/// where negative exponents are rationalized such as 8^{-1/2}->1/4*2^{1/2}.
+/// Odd roots of negative numbers are real and \sqrt{-n} is i\sqrt{n}
+pub fn power<'a>(x: Num, e: Num) -> Option<Expr<'a>> {
+    if x.is_zero() {
+        return match e.is_negative() {
+            true => None,
+            false => Some(Expr::Num(x)),
+        };
+    }
+    // x^{e}=x^{k}*x^{r/q} with 0<r<q
+    let q = e.den;
+    let k = e.num.div_euclid(q);
+    let r = e.num.rem_euclid(q);
+    // (a/b)^{r/q}=(a^{r}*b^{r(q-1)})^{1/q}/b^{r}
+    let radicand = x
+        .num
+        .checked_pow(r as u32)?
+        .checked_mul(x.den.checked_pow((r * (q - 1)) as u32)?)?;
+    let mut coef = x.powi(k).ok()? / Num::new(x.den.checked_pow(r as u32)?);
+    if radicand < 0 {
+        coef = match q {
+            2 => coef * Num::i(),
+            q if q % 2 == 1 => -coef,
+            _ => return None,
+        };
+    }
+    let (m, s) = extract(radicand.abs(), q);
+    coef = coef * Num::new(m);
+    if s == 1 {
+        return Some(Expr::Num(coef));
+    }
+    // s^{1/q}=t^{1/(q/d)} when s=t^{d}
+    let (t, q) = (2..=q)
+        .rev()
+        .filter(|d| q % d == 0)
+        .find_map(|d| root(s, d).map(|t| (t, q / d)))
+        .unwrap_or((s, q));
+    if q == 1 {
+        return Some(Expr::Num(coef * Num::new(t)));
+    }
+    let radical = Expr::Pow(Pow::new(Expr::Num(Num::new(t)), Expr::Num(Num::frac(1, q))));
+    Some(Mul::new(vec![Expr::Num(coef), radical]).to_expr())
+}
+
+/// (m,s) with n=m^{q}*s where s has no q-th power factor up to MAX_FACTOR
+fn extract(mut n: i64, q: i64) -> (i64, i64) {
+    let mut m = 1;
+    let mut p = 2i64;
+    while p <= MAX_FACTOR {
+        let Some(pq) = p.checked_pow(q as u32) else {
+            break;
+        };
+        if pq > n {
+            break;
+        }
+        while n % pq == 0 {
+            n /= pq;
+            m *= p;
+        }
+        p += 1;
+    }
+    (m, n)
+}
+
+/// t with t^{d}=n if it exists
+fn root(n: i64, d: i64) -> Option<i64> {
+    let t = (n as f64).powf(1.0 / d as f64).round() as i64;
+    (t - 1..=t + 1).find(|t| *t > 0 && t.checked_pow(d as u32) == Some(n))
+}
+
+/// Whether t^{2} is a rational number while t is not, such as 3\sqrt{2}
+fn is_surd(expr: &Expr) -> bool {
+    !matches!(expr, Expr::Num(_)) && matches!(square(expr), Expr::Num(n) if n.is_real())
+}
+
+fn square<'a>(expr: &Expr<'a>) -> Expr<'a> {
+    (expr.clone() * expr.clone()).expand()
+}
+
+/// 1/(x+y)=(x-y)/(x^{2}-y^{2}) when x and y are rational or surds
+pub fn rationalize<'a>(add: &Add<'a>) -> Option<Expr<'a>> {
+    let [x, y] = &add.exprs[..] else {
+        return None;
+    };
+    let is_term = |e: &Expr| is_surd(e) || matches!(e, Expr::Num(n) if n.is_real());
+    if !is_term(x) || !is_term(y) || !(is_surd(x) || is_surd(y)) {
+        return None;
+    }
+    match (square(x) - square(y)).collect() {
+        Expr::Num(d) if !d.is_zero() => {
+            Some(((x.clone() - y.clone()) * Expr::Num(d.recip())).expand())
+        }
+        _ => None,
+    }
+}
+
+/// \sqrt{a+b\sqrt{c}}=\sqrt{d}+\sqrt{e} with d+e=a and 4de=b^{2}c
+/// when a^{2}-b^{2}c is the square of a rational
+pub fn denest<'a>(add: &Add<'a>) -> Option<Expr<'a>> {
+    let (a, t) = match &add.exprs[..] {
+        [Expr::Num(a), t] | [t, Expr::Num(a)] if a.is_real() && is_surd(t) => (*a, t),
+        _ => return None,
+    };
+    let t2 = match square(t) {
+        Expr::Num(t2) => t2,
+        _ => return None,
+    };
+    let r = (a * a - t2).sqrt()?;
+    let (d, e) = ((a + r) / Num::new(2), (a - r) / Num::new(2));
+    if d.is_negative() || e.is_negative() {
+        return None;
+    }
+    let sign = if t.detach_coeff().0.is_negative() {
+        -1
+    } else {
+        1
+    };
+    let sqrt = |n: Num| Expr::Pow(Pow::new(Expr::Num(n), Expr::Num(Num::frac(1, 2))));
+    Some((sqrt(d) + Expr::Num(Num::new(sign)) * sqrt(e)).collect())
+}
+
+/// Merge radicals of positive numbers with the same exponent such as \sqrt{2}\sqrt{3}->\sqrt{6},
+/// None if there is nothing to merge
+pub fn merge<'a>(exprs: &[Expr<'a>]) -> Option<Vec<Expr<'a>>> {
+    let radical = |e: &Expr| match e {
+        Expr::Pow(pow) => match (&*pow.body, &*pow.pow) {
+            (Expr::Num(x), Expr::Num(e)) if x.is_real() && !x.is_negative() && !e.is_integer() => {
+                Some((*x, *e))
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    let (i, j) = (0..exprs.len()).find_map(|i| {
+        let (_, e1) = radical(&exprs[i])?;
+        let j = (i + 1..exprs.len())
+            .find(|j| matches!(radical(&exprs[*j]), Some((_, e2)) if e1 == e2))?;
+        Some((i, j))
+    })?;
+    let ((x, e), (y, _)) = (radical(&exprs[i])?, radical(&exprs[j])?);
+    let mut merged = exprs.to_vec();
+    merged[i] = Pow::new(Expr::Num(x * y), Expr::Num(e)).collect();
+    merged.remove(j);
+    Some(merged)
+}
+
+#[test]
+fn test_radical() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["\\sqrt{8}", "2*2^{1/2}"],
+        ["\\sqrt{72}", "6*2^{1/2}"],
+        ["\\sqrt[3]{54}", "3*2^{1/3}"],
+        ["\\sqrt[3]{-8}", "-2"],
+        ["\\sqrt{-4}", "2*i"],
+        ["\\sqrt[4]{4}", "2^{1/2}"],
+        ["\\sqrt{\\frac{1}{2}}", "1/2*2^{1/2}"],
+        ["\\frac{1}{\\sqrt{2}}", "1/2*2^{1/2}"],
+        ["\\sqrt{2}\\sqrt{3}", "6^{1/2}"],
+        ["\\sqrt{2}\\sqrt{8}", "4"],
+        ["\\sqrt{6}\\sqrt{3}", "3*2^{1/2}"],
+        ["\\frac{1}{1+\\sqrt{2}}", "-1+2^{1/2}"],
+        ["\\frac{1}{\\sqrt{3}-1}", "1/2*3^{1/2}+1/2"],
+        ["\\sqrt{3+2\\sqrt{2}}", "2^{1/2}+1"],
+        ["\\sqrt{5-2\\sqrt{6}}", "3^{1/2}+-1*2^{1/2}"],
+        ["\\sqrt{x}\\sqrt{x}", "x"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+}
+
+#[test]
+fn test_radical_errors() {
+    use crate::parser::{latex_to_expr, try_latex_to_expr};
+    // Poles, even roots of negative numbers and overflowing radicands are left
+    let tests = [
+        ["0^{-\\frac{1}{2}}", "0^{-1/2}"],
+        ["\\sqrt[4]{-16}", "(-16)^{1/4}"],
+        ["3^{\\frac{81}{2}}", "3^{81/2}"],
+        ["\\sqrt{10007^{2}}", "10007"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+    let errors = [
+        ["\\sqrt", "Expected argument"],
+        ["\\sqrt[3]", "Expected argument"],
+        ["\\sqrt{}", "Expected argument"],
+        ["\\sqrt[]{2}", "Expected argument"],
+        ["\\sqrt[0]{2}", "Index of root must not be 0"],
+    ];
+    errors.iter().for_each(|test| {
+        assert_eq!(try_latex_to_expr(test[0]), Err(test[1]), "{}", test[0]);
+    });
+}
@@ -0,0 +1,21 @@
+use super::Expr;
+use std::fmt::{Display, Formatter, Result};
+
+/// lhs=rhs
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Relation<'a> {
+    pub lhs: Expr<'a>,
+    pub rhs: Expr<'a>,
+}
+
+impl<'a> Relation<'a> {
+    pub fn new(lhs: Expr<'a>, rhs: Expr<'a>) -> Self {
+        Relation { lhs, rhs }
+    }
+}
+
+impl<'a> Display for Relation<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}={}", self.lhs, self.rhs)
+    }
+}
@@ -0,0 +1,375 @@
+use super::func::{Func, FuncName};
+use super::{Add, Expr, Mul, Num, Pow, Sym};
+use std::fmt::{Display, Formatter, Result};
+
+/// Error of a truncated expansion which may vanish with more terms
+const PRECISION: &str = "Insufficient order";
+/// Maximum number of extra terms to retry with when leading terms cancel
+const EXTRA: i64 = 8;
+/// Error when j! of a Taylor coefficient overflows Num
+const ORDER: &str = "Order too large";
+
+/// Sum of coeffs[k]*(var-point)^{start+k} followed by the order term O((var-point)^{order})
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Series<'a> {
+    pub var: Sym<'a>,
+    pub point: Expr<'a>,
+    pub start: i64,
+    pub coeffs: Vec<Expr<'a>>,
+    pub order: i64,
+}
+
+impl<'a> Expr<'a> {
+    /// Taylor or Laurent series of expr at var=point up to but not including (var-point)^{order}
+    pub fn series(
+        &self,
+        var: Sym<'a>,
+        point: &Expr<'a>,
+        order: i64,
+    ) -> std::result::Result<Series<'a>, &'static str> {
+        let expr = self.collect();
+        let point = point.collect();
+        for extra in 0..=EXTRA {
+            let expansion = Expansion {
+                var,
+                point: &point,
+                order: order + extra,
+            };
+            match expansion.of(&expr) {
+                Ok(series) if series.order >= order => return Ok(series.truncate(order)),
+                Ok(_) => {}
+                Err(err) if err == PRECISION => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Err(PRECISION)
+    }
+}
+
+/// Series arithmetic at var=point where leaves are expanded up to order
+struct Expansion<'a, 'b> {
+    var: Sym<'a>,
+    point: &'b Expr<'a>,
+    order: i64,
+}
+
+impl<'a, 'b> Expansion<'a, 'b> {
+    fn of(&self, expr: &Expr<'a>) -> std::result::Result<Series<'a>, &'static str> {
+        if !expr.contains(&self.var) {
+            return Ok(self.series(0, vec![expr.clone()]));
+        }
+        match expr {
+            Expr::Sym(_) => Ok(self.series(0, vec![self.point.clone(), Expr::Num(Num::new(1))])),
+            Expr::Add(add) => add
+                .exprs
+                .iter()
+                .try_fold(self.series(0, vec![]), |acc, e| Ok(acc.add(&self.of(e)?))),
+            Expr::Mul(mul) => mul
+                .exprs
+                .iter()
+                .try_fold(self.series(0, vec![Expr::Num(Num::new(1))]), |acc, e| {
+                    Ok(acc.mul(&self.of(e)?))
+                }),
+            // b^e=exp(e*ln(b)) when the exponent depends on var
+            Expr::Pow(pow) if pow.pow.contains(&self.var) => {
+                let ln = Expr::Func(Func::new(FuncName::Ln, *pow.body.clone()));
+                let exponent = (*pow.pow.clone() * ln).collect();
+                self.compose(FuncName::Exp, &self.of(&exponent)?)
+            }
+            Expr::Pow(pow) => self.of(&pow.body)?.pow(&pow.pow),
+            Expr::Func(func) => {
+                let arg = self.of(&func.arg)?;
+                self.compose(func.name, &arg)
+            }
+            _ => Err("Cannot expand into series"),
+        }
+    }
+
+    fn series(&self, start: i64, coeffs: Vec<Expr<'a>>) -> Series<'a> {
+        Series {
+            var: self.var,
+            point: self.point.clone(),
+            start,
+            coeffs,
+            order: self.order,
+        }
+        .normalize()
+    }
+
+    /// f(u0+r)=sum of f^{(j)}(u0)/j!*r^j where u0 is the constant term of u
+    fn compose(
+        &self,
+        name: FuncName,
+        u: &Series<'a>,
+    ) -> std::result::Result<Series<'a>, &'static str> {
+        if u.start < 0 {
+            return Err("Cannot expand function at a pole of its argument");
+        }
+        let u0 = u.coeff(0);
+        let is_zero = u0 == Expr::Num(Num::new(0));
+        if is_zero && matches!(name, FuncName::Ln | FuncName::Abs) {
+            return Err("Cannot expand function at its singular point");
+        }
+        let mut r = u.clone();
+        r.coeffs = (r.start..r.order)
+            .map(|e| match e {
+                0 => Expr::Num(Num::new(0)),
+                e => u.coeff(e),
+            })
+            .collect();
+        let r = r.normalize();
+        let t = Sym::new("#t");
+        let mut derivative = Expr::Func(Func::new(name, Expr::Sym(t)));
+        let mut power = Series {
+            coeffs: vec![Expr::Num(Num::new(1))],
+            start: 0,
+            order: u.order,
+            ..u.clone()
+        }
+        .normalize();
+        let mut result = Series {
+            coeffs: vec![],
+            ..power.clone()
+        }
+        .normalize();
+        let mut factorial = Num::new(1);
+        for j in 0.. {
+            if power.start >= u.order {
+                break;
+            }
+            if j > 0 {
+                factorial = factorial.checked_mul(Num::new(j)).ok_or(ORDER)?;
+            }
+            let coeff = derivative.subs(t, &u0) * Expr::Num(Num::new(1) / factorial);
+            result = result.add(&power.scale(&coeff.collect()));
+            derivative = derivative.diff(t);
+            power = power.mul(&r);
+        }
+        Ok(result)
+    }
+}
+
+impl<'a> Series<'a> {
+    /// Coefficient of (var-point)^{exp}
+    fn coeff(&self, exp: i64) -> Expr<'a> {
+        match exp - self.start {
+            k if k >= 0 && (k as usize) < self.coeffs.len() => self.coeffs[k as usize].clone(),
+            _ => Expr::Num(Num::new(0)),
+        }
+    }
+
+    /// Expand coefficients, strip leading zeros and drop terms beyond order
+    fn normalize(mut self) -> Self {
+        let len = (self.order - self.start).max(0) as usize;
+        self.coeffs.truncate(len);
+        self.coeffs = self.coeffs.iter().map(|c| c.expand()).collect();
+        while let Some(Expr::Num(n)) = self.coeffs.first() {
+            if !n.is_zero() {
+                break;
+            }
+            self.coeffs.remove(0);
+            self.start += 1;
+        }
+        if self.coeffs.is_empty() {
+            self.start = self.order;
+        }
+        self
+    }
+
+    fn truncate(mut self, order: i64) -> Self {
+        self.order = self.order.min(order);
+        self.normalize()
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        let start = self.start.min(rhs.start);
+        let order = self.order.min(rhs.order);
+        Series {
+            coeffs: (start..order)
+                .map(|e| self.coeff(e) + rhs.coeff(e))
+                .collect(),
+            start,
+            order,
+            ..self.clone()
+        }
+        .normalize()
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        let start = self.start + rhs.start;
+        let order = (self.order + rhs.start).min(rhs.order + self.start);
+        Series {
+            coeffs: (start..order)
+                .map(|e| {
+                    let terms = (self.start..=e - rhs.start)
+                        .map(|i| self.coeff(i) * rhs.coeff(e - i))
+                        .collect();
+                    Expr::Add(Add::new(terms))
+                })
+                .collect(),
+            start,
+            order,
+            ..self.clone()
+        }
+        .normalize()
+    }
+
+    fn scale(&self, scalar: &Expr<'a>) -> Self {
+        Series {
+            coeffs: self
+                .coeffs
+                .iter()
+                .map(|c| scalar.clone() * c.clone())
+                .collect(),
+            ..self.clone()
+        }
+        .normalize()
+    }
+
+    /// (c*h^k*(1+r))^{alpha}=c^{alpha}*h^{k*alpha}*sum of binom(alpha,j)*r^j
+    /// where k*alpha must be an integer
+    fn pow(&self, alpha: &Expr<'a>) -> std::result::Result<Self, &'static str> {
+        if self.coeffs.is_empty() {
+            return Err(PRECISION);
+        }
+        let k = self.start;
+        let start = match alpha {
+            Expr::Num(n) if (*n * Num::new(k)).is_integer() => (*n * Num::new(k)).num,
+            _ if k == 0 => 0,
+            _ => return Err("Cannot expand into power series"),
+        };
+        let c = self.coeffs[0].clone();
+        let relative = self.order - k;
+        let r = Series {
+            start: 1,
+            coeffs: self.coeffs[1..]
+                .iter()
+                .map(|e| e.clone() / c.clone())
+                .collect(),
+            order: relative,
+            ..self.clone()
+        }
+        .normalize();
+        let mut power = Series {
+            start: 0,
+            coeffs: vec![Expr::Num(Num::new(1))],
+            order: relative,
+            ..self.clone()
+        }
+        .normalize();
+        let mut sum = Series {
+            coeffs: vec![],
+            ..power.clone()
+        }
+        .normalize();
+        let mut binom = Expr::Num(Num::new(1));
+        for j in 0.. {
+            if power.start >= relative {
+                break;
+            }
+            sum = sum.add(&power.scale(&binom));
+            let next = alpha.clone() - Expr::Num(Num::new(j));
+            binom = (binom * next / Expr::Num(Num::new(j + 1))).expand();
+            power = power.mul(&r);
+        }
+        let leading = Pow::new(c, alpha.clone()).collect();
+        Ok(Series {
+            start: sum.start + start,
+            coeffs: sum
+                .coeffs
+                .iter()
+                .map(|e| leading.clone() * e.clone())
+                .collect(),
+            order: sum.order + start,
+            ..sum
+        }
+        .normalize())
+    }
+
+    /// var-point or var when point is 0
+    fn base(&self) -> Expr<'a> {
+        match &self.point {
+            Expr::Num(n) if n.is_zero() => Expr::Sym(self.var),
+            point => Expr::Add(Add::new(vec![Expr::Sym(self.var), -point.clone()])).collect(),
+        }
+    }
+
+    /// Power series part without the order term
+    pub fn to_expr(&self) -> Expr<'a> {
+        let terms = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .map(|(k, c)| {
+                let h = Pow::new(self.base(), Expr::Num(Num::new(self.start + k as i64)));
+                Expr::Mul(Mul::new(vec![c.clone(), Expr::Pow(h)])).collect()
+            })
+            .collect();
+        Expr::Add(Add::new(terms)).collect()
+    }
+
+    /// (var-point)^{order} in the order term
+    pub fn order_term(&self) -> Expr<'a> {
+        Pow::new(self.base(), Expr::Num(Num::new(self.order))).collect()
+    }
+}
+
+impl<'a> Display for Series<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.coeffs.is_empty() {
+            true => write!(f, "O({})", self.order_term()),
+            false => write!(f, "{}+O({})", self.to_expr(), self.order_term()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_series {
+    use crate::parser::latex_to_expr;
+    use crate::{Expr, Num, Sym};
+
+    fn series(latex: &'static str, point: i64, order: i64) -> String {
+        let point = Expr::Num(Num::new(point));
+        match latex_to_expr(latex).series(Sym::new("x"), &point, order) {
+            Ok(series) => series.to_string(),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_taylor() {
+        assert_eq!(series("\\sin x", 0, 6), "x+-1/6*x^{3}+1/120*x^{5}+O(x^{6})");
+        assert_eq!(series("\\exp{x}", 0, 3), "1+x+1/2*x^{2}+O(x^{3})");
+        assert_eq!(series("\\frac{1}{1-x}", 0, 3), "1+x+x^{2}+O(x^{3})");
+        assert_eq!(
+            series("(1+x)^{\\frac{1}{2}}", 0, 3),
+            "1+1/2*x+-1/8*x^{2}+O(x^{3})"
+        );
+        assert_eq!(series("x^{2}+1", 0, 5), "1+x^{2}+O(x^{5})");
+        assert_eq!(series("\\cos x^{2}", 0, 5), "1+-1/2*x^{4}+O(x^{5})");
+        assert_eq!(series("\\ln x", 1, 3), "x+-1+-1/2*(x+-1)^{2}+O((x+-1)^{3})");
+        assert_eq!(series("\\tan x", 0, 4), "x+1/3*x^{3}+O(x^{4})");
+    }
+
+    #[test]
+    fn test_laurent() {
+        assert_eq!(series("\\frac{1}{\\sin x}", 0, 2), "x^{-1}+1/6*x+O(x^{2})");
+        assert_eq!(series("\\frac{\\cos x}{x^{2}}", 0, 1), "x^{-2}+-1/2+O(x)");
+        assert_eq!(series("\\frac{\\sin x-x}{x^{3}}", 0, 2), "-1/6+O(x^{2})");
+        assert_eq!(
+            series("\\ln x", 0, 2),
+            "Cannot expand function at its singular point"
+        );
+        assert_eq!(
+            series("x^{\\frac{1}{2}}", 0, 2),
+            "Cannot expand into power series"
+        );
+    }
+
+    #[test]
+    fn test_order_too_large() {
+        assert!(series("e^{x}", 0, 21).ends_with("1/2432902008176640000*x^{20}+O(x^{21})"));
+        assert_eq!(series("e^{x}", 0, 22), "Order too large");
+        assert_eq!(series("\\tan x", 0, 25), "Order too large");
+    }
+}
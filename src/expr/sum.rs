@@ -0,0 +1,305 @@
+use super::limit::infinity;
+use super::{Add, Expr, Mul, Num, Pow, Sym};
+use crate::solver::coefficients;
+use std::cell::Cell;
+use std::fmt::{Display, Formatter, Result};
+
+/// Maximum number of terms evaluated one by one when bounds are numeric
+const MAX_TERMS: i64 = 1000;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SumOp {
+    Sum,
+    Product,
+}
+
+impl SumOp {
+    pub fn command(&self) -> &'static str {
+        match self {
+            SumOp::Sum => "\\sum",
+            SumOp::Product => "\\prod",
+        }
+    }
+}
+
+/// \sum_{var=lower}^{upper}body or \prod_{var=lower}^{upper}body where var is bound
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Sum<'a> {
+    pub op: SumOp,
+    pub body: Box<Expr<'a>>,
+    pub var: Sym<'a>,
+    pub lower: Box<Expr<'a>>,
+    pub upper: Box<Expr<'a>>,
+}
+
+impl<'a> Sum<'a> {
+    pub fn new(op: SumOp, body: Expr<'a>, var: Sym<'a>, lower: Expr<'a>, upper: Expr<'a>) -> Self {
+        Sum {
+            op,
+            body: Box::new(body),
+            var,
+            lower: Box::new(lower),
+            upper: Box::new(upper),
+        }
+    }
+
+    pub fn map(&self, f: impl Fn(&Expr<'a>) -> Expr<'a>) -> Self {
+        Sum::new(
+            self.op,
+            f(&self.body),
+            self.var,
+            f(&self.lower),
+            f(&self.upper),
+        )
+    }
+
+    pub fn contains(&self, sym: &Sym) -> bool {
+        (*sym != self.var && self.body.contains(sym))
+            || self.lower.contains(sym)
+            || self.upper.contains(sym)
+    }
+
+    /// Term by term for numeric bounds unless a term is undefined, otherwise closed form if found
+    pub fn collect(&self) -> Expr<'a> {
+        let sum = self.map(|e| e.collect());
+        if is_indexed(&sum.body, self.var) {
+            return Expr::Sum(sum);
+        }
+        if let (Expr::Num(a), Expr::Num(b)) = (&*sum.lower, &*sum.upper) {
+            if a.is_integer() && b.is_integer() && b.num - a.num < MAX_TERMS {
+                let terms: Vec<_> = (a.num..=b.num)
+                    .map(|k| sum.body.subs(self.var, &Expr::Num(Num::new(k))))
+                    .collect();
+                if terms.iter().any(|t| t.is_undefined()) {
+                    return Expr::Sum(sum);
+                }
+                return match self.op {
+                    SumOp::Sum => Add::new(terms).collect(),
+                    SumOp::Product => Mul::new(terms).collect(),
+                };
+            }
+        }
+        let closed = match self.op {
+            SumOp::Sum => sum.closed_sum(&sum.body),
+            SumOp::Product => sum.closed_product(&sum.body),
+        };
+        match closed {
+            Some(closed) => closed.collect(),
+            None => Expr::Sum(sum),
+        }
+    }
+
+    /// Number of terms upper-lower+1
+    fn count(&self) -> Expr<'a> {
+        (*self.upper.clone() - *self.lower.clone() + Expr::Num(Num::new(1))).collect()
+    }
+
+    /// Linearity, Faulhaber's formula for polynomials and geometric series
+    fn closed_sum(&self, body: &Expr<'a>) -> Option<Expr<'a>> {
+        let infinite = *self.upper == infinity();
+        if !body.contains(&self.var) {
+            return match infinite {
+                true => None,
+                false => Some(body.clone() * self.count()),
+            };
+        }
+        if let Expr::Add(add) = body {
+            let terms = add.exprs.iter().map(|e| self.closed_sum(e));
+            return Some(Add::new(terms.collect::<Option<_>>()?).to_expr());
+        }
+        if let Some((c, q)) = geometric(body, self.var) {
+            if q == Expr::Num(Num::new(1)) {
+                return match infinite {
+                    true => None,
+                    false => Some(c * self.count()),
+                };
+            }
+            let a = *self.lower.clone();
+            let q_a = Pow::new(q.clone(), a).collect();
+            return match infinite {
+                true if converges(&q) => Some(c * q_a / (Expr::Num(Num::new(1)) - q)),
+                true => None,
+                false => {
+                    let b = *self.upper.clone() + Expr::Num(Num::new(1));
+                    let q_b = Pow::new(q.clone(), b).collect();
+                    Some(c * (q_b - q_a) / (q - Expr::Num(Num::new(1))))
+                }
+            };
+        }
+        let coeffs = coefficients(body, self.var).ok().filter(|_| !infinite)?;
+        let a = *self.lower.clone() - Expr::Num(Num::new(1));
+        let terms = coeffs
+            .into_iter()
+            .enumerate()
+            .map(|(p, c)| c * (faulhaber(p, &self.upper) - faulhaber(p, &a)))
+            .collect();
+        Some(Add::new(terms).to_expr().expand())
+    }
+
+    /// Product of factors, constant powers and r^{f(k)}=r^{\sum f(k)}
+    fn closed_product(&self, body: &Expr<'a>) -> Option<Expr<'a>> {
+        if *self.upper == infinity() {
+            return None;
+        }
+        match body {
+            x if !x.contains(&self.var) => Some(Pow::new(x.clone(), self.count()).collect()),
+            Expr::Mul(mul) => {
+                let factors = mul.exprs.iter().map(|e| self.closed_product(e));
+                Some(Mul::new(factors.collect::<Option<_>>()?).to_expr())
+            }
+            Expr::Pow(pow) if !pow.pow.contains(&self.var) => {
+                Some(Pow::new(self.closed_product(&pow.body)?, *pow.pow.clone()).collect())
+            }
+            Expr::Pow(pow) if !pow.body.contains(&self.var) => {
+                let exponent = self.closed_sum(&pow.pow)?;
+                Some(Pow::new(*pow.body.clone(), exponent).collect())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether expr has a symbol subscripted by var such as x_{k}, which cannot be substituted
+fn is_indexed(expr: &Expr, var: Sym) -> bool {
+    match expr {
+        Expr::Sym(sym) => sym.is_indexed_by(&var),
+        expr => {
+            let found = Cell::new(false);
+            expr.map(|e| {
+                found.set(found.get() || is_indexed(e, var));
+                e.clone()
+            });
+            found.get()
+        }
+    }
+}
+
+/// (c,q) of c*q^{var}, where c*r^{a*var+b} is c*r^b*(r^a)^{var}
+fn geometric<'a>(expr: &Expr<'a>, var: Sym<'a>) -> Option<(Expr<'a>, Expr<'a>)> {
+    let factors = match expr {
+        Expr::Mul(mul) => mul.exprs.clone(),
+        x => vec![x.clone()],
+    };
+    let (consts, rest): (Vec<_>, Vec<_>) = factors.into_iter().partition(|e| !e.contains(&var));
+    match &rest[..] {
+        [Expr::Pow(pow)] if !pow.body.contains(&var) => {
+            let coeffs = coefficients(&pow.pow, var).ok().filter(|c| c.len() == 2)?;
+            let r = *pow.body.clone();
+            let c = Mul::new(consts).to_expr() * Pow::new(r.clone(), coeffs[0].clone()).collect();
+            Some((c.collect(), Pow::new(r, coeffs[1].clone()).collect()))
+        }
+        _ => None,
+    }
+}
+
+/// |q|<1 for numeric q
+fn converges(q: &Expr) -> bool {
+    matches!(q, Expr::Num(n) if n.norm().num < n.norm().den)
+}
+
+/// 1^p+2^p+...+n^p=1/(p+1)*\sum_{j=0}^{p}binom(p+1,j)*B_j*n^{p+1-j} with B_1=1/2
+fn faulhaber<'a>(p: usize, n: &Expr<'a>) -> Expr<'a> {
+    let bernoulli = bernoulli(p);
+    let terms = (0..=p)
+        .map(|j| {
+            let b = if j == 1 {
+                Num::frac(1, 2)
+            } else {
+                bernoulli[j]
+            };
+            let c = Num::new(binomial(p + 1, j)) * b / Num::new(p as i64 + 1);
+            Expr::Num(c) * Pow::new(n.clone(), Expr::Num(Num::new((p + 1 - j) as i64))).collect()
+        })
+        .collect();
+    Add::new(terms).to_expr()
+}
+
+/// Bernoulli numbers B_0,...,B_n with B_1=-1/2
+fn bernoulli(n: usize) -> Vec<Num> {
+    let mut b = vec![Num::new(1)];
+    for m in 1..=n {
+        let sum = (0..m).fold(Num::new(0), |acc, j| {
+            acc + Num::new(binomial(m + 1, j)) * b[j]
+        });
+        b.push(Num::new(-1) * sum / Num::new(m as i64 + 1));
+    }
+    b
+}
+
+fn binomial(n: usize, k: usize) -> i64 {
+    (0..k).fold(1, |acc, i| acc * (n - i) as i64 / (i + 1) as i64)
+}
+
+impl<'a> Display for Sum<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let name = match self.op {
+            SumOp::Sum => "sum",
+            SumOp::Product => "prod",
+        };
+        write!(
+            f,
+            "{}({},{},{},{})",
+            name, self.body, self.var, self.lower, self.upper
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_sum {
+    use crate::parser::latex_to_expr;
+
+    #[test]
+    fn test_sum() {
+        let tests = [
+            ["\\sum_{k=1}^{4}k^{2}", "30"],
+            ["\\sum_{k=1}^{n}k", "1/2*n^{2}+1/2*n"],
+            ["\\sum_{k=1}^{n}k^{3}", "1/4*n^{4}+1/2*n^{3}+1/4*n^{2}"],
+            ["\\sum_{i=0}^{n-1}(2i+1)", "n^{2}"],
+            ["\\sum_{k=1}^{n}a", "a*n"],
+            ["\\sum_{k=0}^{n}2^{k}", "2^{n+1}+-1"],
+            ["\\sum_{k=0}^{\\infty}\\frac{1}{2^{k}}", "2"],
+            [
+                "\\sum_{k=1}^{\\infty}\\frac{1}{k^{2}}",
+                "sum(k^{-2},k,1,\\infty)",
+            ],
+            ["\\sum^{3}_{j=1}x_{j}", "sum(x_{j},j,1,3)"],
+            ["\\sum_{k=0}^{n}(-1)^{2k}", "n+1"],
+            ["\\sum_{k=1}^{n}3\\cdot 1^{k}", "3*n"],
+            ["\\sum_{k=0}^{\\infty}1^{k}", "sum(1,k,0,\\infty)"],
+            ["\\sum_{k=0}^{3}\\frac{1}{k}", "sum(k^{-1},k,0,3)"],
+        ];
+        tests.iter().for_each(|test| {
+            assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+        });
+    }
+
+    #[test]
+    fn test_constant_and_undefined_terms() {
+        // Ratio 1 used to divide by q-1=0 and undefined terms used to be added up
+        let tests = [
+            ["\\sum_{k=2}^{n}5", "5*(n+-1)"],
+            ["\\sum_{k=1}^{n}(-1)^{2k}x", "x*n"],
+            ["\\prod_{k=1}^{n}1^{k}", "1"],
+            ["\\sum_{k=-1}^{1}\\frac{1}{k}", "sum(k^{-1},k,-1,1)"],
+            ["\\sum_{k=1}^{3}\\frac{1}{k}", "11/6"],
+        ];
+        tests.iter().for_each(|test| {
+            assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+        });
+    }
+
+    #[test]
+    fn test_product() {
+        let tests = [
+            ["\\prod_{k=1}^{5}k", "120"],
+            ["\\prod_{k=1}^{n}2", "2^{n}"],
+            ["\\prod_{k=1}^{n}x^{k}", "x^{1/2*n^{2}+1/2*n}"],
+            ["\\prod_{k=1}^{n}k", "prod(k,k,1,n)"],
+            ["\\prod_{k=1}^{n}k+1", "prod(k,k,1,n)+1"],
+            ["\\prod_{k=0}^{3}\\frac{1}{k}", "prod(k^{-1},k,0,3)"],
+        ];
+        tests.iter().for_each(|test| {
+            assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+        });
+    }
+}
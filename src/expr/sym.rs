@@ -1,5 +1,5 @@
 use std::fmt::{Display, Formatter, Result};
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Sym<'a> {
     symbol: &'a str,
     sub: &'a str,
@@ -14,6 +14,19 @@ impl<'a> Sym<'a> {
         self.sub = sub;
         *self
     }
+
+    pub fn symbol(&self) -> &'a str {
+        self.symbol
+    }
+
+    pub fn sub(&self) -> &'a str {
+        self.sub
+    }
+
+    /// x_{k} is indexed by k
+    pub fn is_indexed_by(&self, index: &Sym) -> bool {
+        index.sub.is_empty() && self.sub == index.symbol
+    }
 }
 
 impl<'a> Display for Sym<'a> {
@@ -0,0 +1,494 @@
+use super::func::call;
+use super::{Add, Expr, FuncName, Mul, Num, Pow};
+use std::cell::Cell;
+
+/// Largest n for which sin(nx) and cos(nx) are expanded, beyond which binomial
+/// coefficients of the expansion may overflow
+const MAX_MULTIPLE: i64 = 32;
+
+impl<'a> Expr<'a> {
+    /// Angle addition, multiple angle and half angle formulas such as sin(2x)->2sin(x)cos(x)
+    /// and tan(x/2)->sin(x)/(1+cos(x))
+    pub fn expand_trig(&self) -> Self {
+        match self.map(|e| e.expand_trig()) {
+            Expr::Func(func) => expand_func(func.name, &func.arg.collect()),
+            Expr::Pow(pow) => half_angle(&pow).unwrap_or_else(|| Expr::Pow(pow).collect()),
+            x => x.collect(),
+        }
+    }
+
+    /// Shortest form found by Pythagorean identities, where tan, cot, sec and csc
+    /// are written by sin and cos before and sin/cos is folded into tan after
+    pub fn trigsimp(&self) -> Self {
+        let expr = self.map(|e| e.trigsimp()).collect();
+        if !has_trig(&expr) {
+            return expr;
+        }
+        let rewritten = to_sin_cos(&expr).collect();
+        let mut candidates = vec![];
+        for base in [rewritten.clone(), rewritten.expand_trig()] {
+            candidates.extend(identities(&base));
+            // Identities on the numerator such as 1-cos^{2}(x) over cos^{2}(x)
+            if let Some((num, den)) = together(&base) {
+                candidates.extend(
+                    identities(&num)
+                        .into_iter()
+                        .map(|num| (num / den.clone()).collect()),
+                );
+            }
+        }
+        candidates.iter().map(fold_tan).fold(
+            expr,
+            |best, e| if size(&e) < size(&best) { e } else { best },
+        )
+    }
+
+    /// sin(a)sin(b), cos(a)cos(b) and sin(a)cos(b) into sums, including powers such as
+    /// sin^{2}(x)->1/2-1/2cos(2x)
+    pub fn product_to_sum(&self) -> Self {
+        let expr = self.map(|e| e.product_to_sum()).expand();
+        let factors = match &expr {
+            Expr::Add(add) => {
+                let terms = add.exprs.iter().map(|e| e.product_to_sum()).collect();
+                return Add::new(terms).collect();
+            }
+            Expr::Mul(mul) => mul.exprs.clone(),
+            Expr::Pow(_) => vec![expr.clone()],
+            _ => return expr,
+        };
+        // Split powers of sin and cos into repeated factors
+        let mut trig = vec![];
+        let mut rest = vec![];
+        for factor in factors {
+            match &factor {
+                Expr::Func(func) if is_sin_cos(func.name) => trig.push(factor.clone()),
+                Expr::Pow(pow) => match (&*pow.body, &*pow.pow) {
+                    (Expr::Func(func), Expr::Num(n))
+                        if is_sin_cos(func.name) && n.is_integer() && n.num > 0 =>
+                    {
+                        trig.extend(vec![*pow.body.clone(); n.num as usize])
+                    }
+                    _ => rest.push(factor),
+                },
+                _ => rest.push(factor),
+            }
+        }
+        if trig.len() < 2 {
+            return expr;
+        }
+        let combined = product_pair(&trig[0], &trig[1]);
+        let expr = Mul::new([vec![combined], trig[2..].to_vec(), rest].concat()).to_expr();
+        expr.expand().product_to_sum()
+    }
+
+    /// sin(a)+-sin(b) and cos(a)+-cos(b) into products with the same coefficient
+    pub fn sum_to_product(&self) -> Self {
+        let expr = self.map(|e| e.sum_to_product()).collect();
+        let terms = match &expr {
+            Expr::Add(add) => add.exprs.clone(),
+            _ => return expr,
+        };
+        for i in 0..terms.len() {
+            for j in i + 1..terms.len() {
+                if let Some(product) = sum_pair(&terms[i], &terms[j]) {
+                    let mut rest = terms.clone();
+                    rest.remove(j);
+                    rest[i] = product;
+                    return Add::new(rest).to_expr().collect().sum_to_product();
+                }
+            }
+        }
+        expr
+    }
+}
+
+fn is_sin_cos(name: FuncName) -> bool {
+    matches!(name, FuncName::Sin | FuncName::Cos)
+}
+
+/// f(a+b) and f(nx) by addition formulas for sin, cos, tan, sinh and cosh,
+/// where f(-nx) is expanded by parity and p/q*x is a multiple of x/q
+fn expand_func<'a>(name: FuncName, arg: &Expr<'a>) -> Expr<'a> {
+    use FuncName::*;
+    let (a, b) = match arg {
+        Expr::Add(add) => (
+            add.exprs[0].clone(),
+            Add::new(add.exprs[1..].to_vec()).to_expr(),
+        ),
+        arg => match arg.detach_coeff() {
+            (n, _) if n.is_negative() && matches!(name, Sin | Tan | Sinh) => {
+                return (-expand_func(name, &(-arg.clone()).collect())).expand();
+            }
+            (n, _) if n.is_negative() && matches!(name, Cos | Cosh) => {
+                return expand_func(name, &(-arg.clone()).collect());
+            }
+            (n, _) if name == Tan && n.is_real() && n.den == 2 => {
+                let double = (Expr::Num(Num::new(2)) * arg.clone()).collect();
+                let cos = expand_func(Cos, &double);
+                let sin = expand_func(Sin, &double);
+                return (sin / (Expr::Num(Num::new(1)) + cos)).collect();
+            }
+            (n, x) if n.is_real() && 1 < n.num && n.num <= MAX_MULTIPLE => {
+                let x = (Expr::Num(Num::frac(1, n.den)) * x.to_expr()).collect();
+                if matches!(name, Sin | Cos | Sinh | Cosh) {
+                    return multiple(name, n.num, &x);
+                }
+                (x.clone(), Expr::Num(Num::new(n.num - 1)) * x)
+            }
+            _ => return call(name, arg.clone()).collect(),
+        },
+    };
+    let f = |name: FuncName, x: &Expr<'a>| expand_func(name, &x.collect());
+    let (sin, cos, tan) = (FuncName::Sin, FuncName::Cos, FuncName::Tan);
+    let (sinh, cosh) = (FuncName::Sinh, FuncName::Cosh);
+    let expanded = match name {
+        FuncName::Sin => f(sin, &a) * f(cos, &b) + f(cos, &a) * f(sin, &b),
+        FuncName::Cos => f(cos, &a) * f(cos, &b) - f(sin, &a) * f(sin, &b),
+        FuncName::Tan => {
+            let (ta, tb) = (f(tan, &a), f(tan, &b));
+            return ((ta.clone() + tb.clone()) / (Expr::Num(Num::new(1)) - ta * tb)).collect();
+        }
+        FuncName::Sinh => f(sinh, &a) * f(cosh, &b) + f(cosh, &a) * f(sinh, &b),
+        FuncName::Cosh => f(cosh, &a) * f(cosh, &b) + f(sinh, &a) * f(sinh, &b),
+        name => return call(name, arg.clone()).collect(),
+    };
+    expanded.expand()
+}
+
+/// f(nx) for f=sin, cos, sinh or cosh by applying the addition formula n-1 times,
+/// since recursing on both f((n-1)x) and its pair takes 2^{n} steps
+fn multiple<'a>(name: FuncName, n: i64, x: &Expr<'a>) -> Expr<'a> {
+    let (f, g, sign) = match name {
+        FuncName::Sin | FuncName::Cos => (FuncName::Sin, FuncName::Cos, -1),
+        _ => (FuncName::Sinh, FuncName::Cosh, 1),
+    };
+    let (s, c) = (call(f, x.clone()), call(g, x.clone()));
+    let (mut sn, mut cn) = (s.clone(), c.clone());
+    for _ in 1..n {
+        (sn, cn) = (
+            (s.clone() * cn.clone() + c.clone() * sn.clone()).expand(),
+            (c.clone() * cn + Expr::Num(Num::new(sign)) * s.clone() * sn).expand(),
+        );
+    }
+    match name {
+        FuncName::Sin | FuncName::Sinh => sn,
+        _ => cn,
+    }
+}
+
+/// sin^{2k}(x/2)->((1-cos(x))/2)^{k} and cos^{2k}(x/2)->((1+cos(x))/2)^{k}, while odd
+/// powers stay since the sign of sin(x/2) and cos(x/2) depends on x
+fn half_angle<'a>(pow: &Pow<'a>) -> Option<Expr<'a>> {
+    let (Expr::Func(func), Expr::Num(n)) = (&*pow.body, &*pow.pow) else {
+        return None;
+    };
+    let sign = match func.name {
+        FuncName::Sin => -1,
+        FuncName::Cos => 1,
+        _ => return None,
+    };
+    let half = matches!(func.arg.detach_coeff(), (c, _) if c.is_real() && c.den == 2);
+    if !half || !n.is_integer() || n.num % 2 != 0 {
+        return None;
+    }
+    let double = (Expr::Num(Num::new(2)) * *func.arg.clone()).collect();
+    let cos = Expr::Num(Num::new(sign)) * expand_func(FuncName::Cos, &double);
+    let base = Expr::Num(Num::frac(1, 2)) * (Expr::Num(Num::new(1)) + cos);
+    Some(Expr::Pow(Pow::new(base, Expr::Num(Num::new(n.num / 2)))).expand())
+}
+
+/// Product of two sin or cos factors as a sum
+fn product_pair<'a>(x: &Expr<'a>, y: &Expr<'a>) -> Expr<'a> {
+    let (f, a, g, b) = match (x, y) {
+        (Expr::Func(f), Expr::Func(g)) => (f.name, *f.arg.clone(), g.name, *g.arg.clone()),
+        _ => unreachable!(),
+    };
+    let half = || Expr::Num(Num::frac(1, 2));
+    let sum = || call(FuncName::Cos, a.clone() + b.clone());
+    let diff = || call(FuncName::Cos, a.clone() - b.clone());
+    match (f, g) {
+        // sin(a)sin(b)=(cos(a-b)-cos(a+b))/2
+        (FuncName::Sin, FuncName::Sin) => half() * (diff() - sum()),
+        // cos(a)cos(b)=(cos(a-b)+cos(a+b))/2
+        (FuncName::Cos, FuncName::Cos) => half() * (diff() + sum()),
+        // sin(a)cos(b)=(sin(a+b)+sin(a-b))/2
+        (FuncName::Sin, FuncName::Cos) => {
+            half() * (call(FuncName::Sin, a.clone() + b.clone()) + call(FuncName::Sin, a - b))
+        }
+        _ => product_pair(y, x),
+    }
+}
+
+/// c*f(a)+-c*f(b) as a product for f=sin or cos
+fn sum_pair<'a>(x: &Expr<'a>, y: &Expr<'a>) -> Option<Expr<'a>> {
+    let (c1, f) = detach_trig(x)?;
+    let (c2, g) = detach_trig(y)?;
+    if f.0 != g.0 || !(c1 == c2 || c1 == -c2) {
+        return None;
+    }
+    let (a, b) = (f.1, g.1);
+    let half = Expr::Num(Num::frac(1, 2));
+    let mean = (half.clone() * (a.clone() + b.clone())).expand();
+    let gap = (half * (a - b)).expand();
+    let two = Expr::Num(c1 * Num::new(2));
+    let product = match (f.0, c1 == c2) {
+        // sin(a)+sin(b)=2sin((a+b)/2)cos((a-b)/2)
+        (FuncName::Sin, true) => two * call(FuncName::Sin, mean) * call(FuncName::Cos, gap),
+        // sin(a)-sin(b)=2cos((a+b)/2)sin((a-b)/2)
+        (FuncName::Sin, false) => two * call(FuncName::Cos, mean) * call(FuncName::Sin, gap),
+        // cos(a)+cos(b)=2cos((a+b)/2)cos((a-b)/2)
+        (FuncName::Cos, true) => two * call(FuncName::Cos, mean) * call(FuncName::Cos, gap),
+        // cos(a)-cos(b)=-2sin((a+b)/2)sin((a-b)/2)
+        (_, false) => -two * call(FuncName::Sin, mean) * call(FuncName::Sin, gap),
+        _ => unreachable!(),
+    };
+    Some(product.collect())
+}
+
+/// c*f(a)->(c,(f,a)) for numeric c and f=sin or cos
+fn detach_trig<'a>(expr: &Expr<'a>) -> Option<(Num, (FuncName, Expr<'a>))> {
+    let (c, body) = expr.detach_coeff();
+    match &body.exprs[..] {
+        [Expr::Func(func)] if is_sin_cos(func.name) => Some((c, (func.name, *func.arg.clone()))),
+        _ => None,
+    }
+}
+
+/// Pythagorean identities applied to expr followed by expansion, expr itself and its expansion
+fn identities<'a>(expr: &Expr<'a>) -> Vec<Expr<'a>> {
+    // from^{2}=c+s*to^{2} as (from,to,c,s)
+    [
+        (FuncName::Cos, FuncName::Sin, 1, -1),
+        (FuncName::Sin, FuncName::Cos, 1, -1),
+        (FuncName::Cosh, FuncName::Sinh, 1, 1),
+        (FuncName::Sinh, FuncName::Cosh, -1, 1),
+    ]
+    .iter()
+    .map(|&(from, to, c, s)| pythagorean(expr, from, to, (c, s)).expand())
+    .chain([expr.clone(), expr.expand()])
+    .collect()
+}
+
+/// Sum as (numerator,denominator) over the product of the largest negative integer
+/// powers of each base among its terms, None without such powers
+fn together<'a>(expr: &Expr<'a>) -> Option<(Expr<'a>, Expr<'a>)> {
+    let Expr::Add(add) = expr else {
+        return None;
+    };
+    let mut den: Vec<(Expr<'a>, i64)> = vec![];
+    for term in &add.exprs {
+        let factors = match term {
+            Expr::Mul(mul) => mul.exprs.clone(),
+            term => vec![term.clone()],
+        };
+        for factor in factors {
+            let (base, Expr::Num(n)) = factor.detach_pow() else {
+                continue;
+            };
+            if !n.is_integer() || !n.is_negative() {
+                continue;
+            }
+            match den.iter_mut().find(|(b, _)| *b == base) {
+                Some((_, m)) => *m = (*m).max(-n.num),
+                None => den.push((base, -n.num)),
+            }
+        }
+    }
+    if den.is_empty() {
+        return None;
+    }
+    let den = Mul::new(
+        den.into_iter()
+            .map(|(base, n)| base ^ Expr::Num(Num::new(n)))
+            .collect(),
+    )
+    .collect();
+    let num = Add::new(
+        add.exprs
+            .iter()
+            .map(|term| (term.clone() * den.clone()).collect())
+            .collect(),
+    )
+    .collect();
+    Some((num, den))
+}
+
+/// Replace from(u)^{n} by (c+s*to(u)^{2})^{n/2}*from(u)^{n mod 2} for n>=2
+fn pythagorean<'a>(expr: &Expr<'a>, from: FuncName, to: FuncName, (c, s): (i64, i64)) -> Expr<'a> {
+    match expr {
+        Expr::Pow(pow) => match (&*pow.body, &*pow.pow) {
+            (Expr::Func(func), Expr::Num(n))
+                if func.name == from && n.is_integer() && n.num >= 2 =>
+            {
+                let square = Pow::new(call(to, *func.arg.clone()), Expr::Num(Num::new(2)));
+                let identity = Expr::Num(Num::new(c)) + Expr::Num(Num::new(s)) * Expr::Pow(square);
+                let half = Pow::new(identity, Expr::Num(Num::new(n.num / 2)));
+                let odd = Pow::new(*pow.body.clone(), Expr::Num(Num::new(n.num % 2)));
+                Expr::Pow(half) * Expr::Pow(odd)
+            }
+            _ => expr.map(|e| pythagorean(e, from, to, (c, s))),
+        },
+        expr => expr.map(|e| pythagorean(e, from, to, (c, s))),
+    }
+}
+
+/// tan->sin/cos, cot->cos/sin, sec->1/cos and csc->1/sin
+fn to_sin_cos<'a>(expr: &Expr<'a>) -> Expr<'a> {
+    let expr = expr.map(to_sin_cos);
+    let sin = |u: &Expr<'a>| call(FuncName::Sin, u.clone());
+    let cos = |u: &Expr<'a>| call(FuncName::Cos, u.clone());
+    match &expr {
+        Expr::Func(func) => {
+            let u = &func.arg;
+            match func.name {
+                FuncName::Tan => sin(u) / cos(u),
+                FuncName::Cot => cos(u) / sin(u),
+                FuncName::Sec => Expr::Num(Num::new(1)) / cos(u),
+                FuncName::Csc => Expr::Num(Num::new(1)) / sin(u),
+                _ => expr,
+            }
+        }
+        _ => expr,
+    }
+}
+
+/// sin(u)^{n}*cos(u)^{-n}->tan(u)^{n} and cos(u)^{n}*sin(u)^{-n}->cot(u)^{n}
+fn fold_tan<'a>(expr: &Expr<'a>) -> Expr<'a> {
+    let expr = expr.map(fold_tan);
+    let Expr::Mul(mul) = &expr else {
+        return expr;
+    };
+    let power = |e: &Expr<'a>| match e.detach_pow() {
+        (Expr::Func(func), Expr::Num(n)) if is_sin_cos(func.name) => Some((func, n)),
+        _ => None,
+    };
+    let exprs = &mul.exprs;
+    for i in 0..exprs.len() {
+        for j in 0..exprs.len() {
+            let (Some((f, n)), Some((g, m))) = (power(&exprs[i]), power(&exprs[j])) else {
+                continue;
+            };
+            if f.name == g.name || f.arg != g.arg || n != -m {
+                continue;
+            }
+            let name = match f.name {
+                FuncName::Sin => FuncName::Tan,
+                _ => FuncName::Cot,
+            };
+            let mut rest = exprs.clone();
+            rest[i] = Expr::Pow(Pow::new(call(name, *f.arg), Expr::Num(n)));
+            rest.remove(j);
+            return fold_tan(&Mul::new(rest).collect());
+        }
+    }
+    expr
+}
+
+fn is_trig(name: FuncName) -> bool {
+    use FuncName::*;
+    matches!(name, Sin | Cos | Tan | Cot | Sec | Csc | Sinh | Cosh | Tanh)
+}
+
+fn has_trig(expr: &Expr) -> bool {
+    match expr {
+        Expr::Func(func) if is_trig(func.name) => true,
+        expr => {
+            let found = Cell::new(false);
+            expr.map(|e| {
+                found.set(found.get() || has_trig(e));
+                e.clone()
+            });
+            found.get()
+        }
+    }
+}
+
+/// Number of nodes in expr
+fn size(expr: &Expr) -> usize {
+    let count = Cell::new(1);
+    expr.map(|e| {
+        count.set(count.get() + size(e));
+        e.clone()
+    });
+    count.get()
+}
+
+#[test]
+fn test_trig() {
+    use crate::parser::latex_to_expr;
+    let tests = [
+        ["\\sin^{2}x+\\cos^{2}x", "1"],
+        ["\\tan x\\cos x", "sin(x)"],
+        ["1-\\cos^{2}x", "sin(x)^{2}"],
+        ["\\frac{\\sin x}{\\cos x}", "tan(x)"],
+        ["\\sin^{2}\\frac{x}{2}+\\cos^{2}\\frac{x}{2}", "1"],
+        ["\\sec^{2}x-\\tan^{2}x", "1"],
+        ["\\cosh^{2}x-\\sinh^{2}x", "1"],
+        ["\\sin(-x)+\\sin x", "0"],
+        ["\\sec^{2}x-1", "tan(x)^{2}"],
+        ["\\csc^{2}x-1", "cot(x)^{2}"],
+        ["\\sin 2x-2\\sin x\\cos x", "0"],
+        ["\\frac{1}{\\cos^{2}x}-\\tan^{2}x", "1"],
+        ["\\frac{1-\\cos^{2}x}{\\sin x}", "sin(x)"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).trigsimp().to_string(), test[1]);
+    });
+
+    let tests = [
+        ["\\sin(2x)", "2*sin(x)*cos(x)"],
+        ["\\cos(2x)", "cos(x)^{2}+-1*sin(x)^{2}"],
+        ["\\cos(x-y)", "cos(x)*cos(y)+sin(x)*sin(y)"],
+        ["\\tan(x+y)", "(tan(x)+tan(y))*(1+-1*tan(x)*tan(y))^{-1}"],
+        ["\\cos(3x)", "cos(x)^{3}+-3*cos(x)*sin(x)^{2}"],
+        ["\\sinh(x+y)", "sinh(x)*cosh(y)+cosh(x)*sinh(y)"],
+        ["\\sin(-2x)", "-2*sin(x)*cos(x)"],
+        ["\\cos(-2x)", "cos(x)^{2}+-1*sin(x)^{2}"],
+        ["\\sin\\frac{x}{2}", "sin(1/2*x)"],
+        ["\\sin^{2}\\frac{x}{2}", "1/2+-1/2*cos(x)"],
+        ["\\cos^{4}\\frac{x}{2}", "1/4+1/2*cos(x)+1/4*cos(x)^{2}"],
+        ["\\tan\\frac{x}{2}", "sin(x)*(1+cos(x))^{-1}"],
+        [
+            "\\tan\\frac{3x}{2}",
+            "(3*sin(x)*cos(x)^{2}+-1*sin(x)^{3})*(1+cos(x)^{3}+-3*cos(x)*sin(x)^{2})^{-1}",
+        ],
+        ["\\sin^{3}\\frac{x}{2}", "sin(1/2*x)^{3}"],
+        [
+            "\\sin\\frac{3x}{2}",
+            "3*sin(1/2*x)*cos(1/2*x)^{2}+-1*sin(1/2*x)^{3}",
+        ],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).expand_trig().to_string(), test[1]);
+    });
+    let tests = [
+        ["\\cos(-x)-\\cos x", "0"],
+        ["\\tan(-x)", "-1*tan(x)"],
+        ["\\sin\\frac{\\pi}{6}", "1/2"],
+        ["\\cos\\frac{5\\pi}{4}", "-1/2*2^{1/2}"],
+        ["\\tan\\frac{2\\pi}{3}", "-1*3^{1/2}"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).collect().to_string(), test[1]);
+    });
+    let expanded = latex_to_expr("\\cos(13x)").expand_trig().to_string();
+    assert!(expanded.starts_with("cos(x)^{13}+-78*cos(x)^{11}*sin(x)^{2}+"));
+
+    let tests = [
+        ["\\sin x\\cos y", "1/2*sin(x+y)+1/2*sin(x+-1*y)"],
+        ["\\sin^{2}x", "1/2+-1/2*cos(2*x)"],
+        ["\\sin^{2}x\\cos x", "1/4*cos(x)+-1/4*cos(3*x)"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).product_to_sum().to_string(), test[1]);
+    });
+
+    let tests = [
+        ["\\sin x+\\sin y", "2*sin(1/2*x+1/2*y)*cos(1/2*x+-1/2*y)"],
+        ["3\\sin x-3\\sin y", "6*cos(1/2*x+1/2*y)*sin(1/2*x+-1/2*y)"],
+        ["\\cos(3x)+\\cos x", "2*cos(2*x)*cos(x)"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(latex_to_expr(test[0]).sum_to_product().to_string(), test[1]);
+    });
+}
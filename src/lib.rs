@@ -1,15 +1,36 @@
+pub mod codegen;
 mod expr;
 pub mod parser;
-pub use expr::{Add, Expr, Mul, Num, Pow, Sym};
-use parser::{latex_to_expr, serialize};
-use wasm_bindgen::prelude::*;
-
-#[wasm_bindgen]
-pub fn collect(input: String) -> String {
-    match latex_to_expr(&input) {
-        Expr::Add(add) => serialize(&Expr::Add(add.collect())),
-        Expr::Mul(mul) => serialize(&Expr::Mul(mul.collect())),
-        Expr::Num(num) => serialize(&Expr::Num(num)),
-        _ => input,
-    }
+pub mod solver;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub use expr::{
+    Add, Assumptions, Cmp, Condition, Const, Delimiter, Direction, Expr, Func, FuncName, Integral,
+    Limit, Matrix, Mul, Num, Piecewise, Pow, Property, Relation, Series, Sum, SumOp, Sym,
+};
+pub use parser::LatexStyle;
+
+/// Expr of LaTeX input such as x+y^{2} as written without simplification
+pub fn parse(input: &str) -> Expr<'_> {
+    parser::latex_to_expr(input)
+}
+
+/// expr with like terms collected and integrals, limits and sums evaluated if possible
+pub fn collect<'a>(expr: &Expr<'a>) -> Expr<'a> {
+    expr.collect()
+}
+
+/// LaTeX of expr in the default style, see LatexStyle for other styles
+pub fn serialize(expr: &Expr) -> String {
+    parser::serialize(expr)
+}
+
+#[test]
+fn test_api() {
+    let expr = collect(&parse("x+y^{2}+2y^{2}"));
+    assert_eq!(serialize(&expr), "x +3y ^{2}");
+    assert_eq!(
+        serialize(&collect(&parse("\\int_{0}^{1}x^{2}dx"))),
+        "\\frac{1}{3}"
+    );
 }
@@ -0,0 +1,795 @@
+use super::precedence::{Printer, ADD, ATOM, MUL, POW};
+use super::serializer::{is_root, leading_minus, negate, reciprocal, split_fractions};
+use crate::expr::func::call;
+use crate::{
+    Cmp, Condition, Const, Delimiter, Direction, Expr, FuncName, Integral, Limit, Matrix, Num,
+    Piecewise, Sum, SumOp, Sym,
+};
+
+/// Greek letters spelled out in ASCII such as alpha for \alpha
+pub(super) const GREEK: [&str; 34] = [
+    "\\alpha",
+    "\\beta",
+    "\\gamma",
+    "\\delta",
+    "\\epsilon",
+    "\\varepsilon",
+    "\\zeta",
+    "\\eta",
+    "\\theta",
+    "\\iota",
+    "\\kappa",
+    "\\lambda",
+    "\\mu",
+    "\\nu",
+    "\\xi",
+    "\\rho",
+    "\\sigma",
+    "\\tau",
+    "\\upsilon",
+    "\\phi",
+    "\\varphi",
+    "\\chi",
+    "\\psi",
+    "\\omega",
+    "\\Gamma",
+    "\\Delta",
+    "\\Theta",
+    "\\Lambda",
+    "\\Xi",
+    "\\Pi",
+    "\\Sigma",
+    "\\Phi",
+    "\\Psi",
+    "\\Omega",
+];
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Token<'a> {
+    Num(Num),
+    Ident(&'a str),
+    /// One of + - * / ^ ( ) [ ] | , where ** is read as ^
+    Op(char),
+    /// One of < <= > >= = != in conditions of piecewise
+    Cmp(Cmp),
+    Eof,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token<'_>>, &'static str> {
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '0'..='9' | '.' => {
+                let mut end = start + 1;
+                while let Some(&(i, c)) = chars.peek() {
+                    let gap = c.is_whitespace()
+                        && input[i..]
+                            .trim_start()
+                            .starts_with(|c: char| c.is_ascii_digit());
+                    if !c.is_ascii_digit() && c != '.' && !gap {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                let digits: String = input[start..end].split_whitespace().collect();
+                let mut num = decimal(&digits)?;
+                if let Some(digits) = exponent(&input[end..]) {
+                    for _ in 0..digits.len() {
+                        chars.next();
+                    }
+                    num = scientific(num, &digits[1..])?;
+                }
+                tokens.push(Token::Num(num));
+            }
+            c if c.is_ascii_alphabetic() => {
+                let mut end = start + 1;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !c.is_ascii_alphanumeric() && c != '_' {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                tokens.push(Token::Ident(&input[start..end]));
+            }
+            '*' if chars.peek().is_some_and(|&(_, c)| c == '*') => {
+                chars.next();
+                tokens.push(Token::Op('^'));
+            }
+            '+' | '-' | '*' | '/' | '^' | '(' | ')' | '[' | ']' | '|' | ',' => {
+                tokens.push(Token::Op(c))
+            }
+            '<' | '>' | '=' | '!' => {
+                let equal = chars.next_if(|&(_, c)| c == '=').is_some();
+                tokens.push(Token::Cmp(match (c, equal) {
+                    ('<', false) => Cmp::Lt,
+                    ('<', true) => Cmp::Le,
+                    ('>', false) => Cmp::Gt,
+                    ('>', true) => Cmp::Ge,
+                    ('=', _) => Cmp::Eq,
+                    ('!', true) => Cmp::Ne,
+                    _ => return Err("Unexpected character"),
+                }));
+            }
+            _ => return Err("Unexpected character"),
+        }
+    }
+    tokens.push(Token::Eof);
+    Ok(tokens)
+}
+
+/// 12.5->25/2
+fn decimal(digits: &str) -> Result<Num, &'static str> {
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    if frac.contains('.') || (int.is_empty() && frac.is_empty()) {
+        return Err("Invalid number");
+    }
+    let den = 10_i64
+        .checked_pow(frac.len() as u32)
+        .ok_or("Number is too long")?;
+    let num = format!("{}{}", int, frac)
+        .parse::<i64>()
+        .map_err(|_| "Number is too long")?;
+    Ok(Num::frac(num, den))
+}
+
+/// Exponent such as e5 or e-3 right after the digits of a number including its sign, so that
+/// 2e5 is 200000 while 2e and 2 e5 are products with e
+fn exponent(rest: &str) -> Option<&str> {
+    let digits = rest.strip_prefix(['e', 'E'])?;
+    let sign = usize::from(digits.starts_with(['+', '-']));
+    let len = digits[sign..]
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len() - sign);
+    (len > 0).then(|| &rest[..1 + sign + len])
+}
+
+/// mantissa*10^{exponent}
+fn scientific(mantissa: Num, exponent: &str) -> Result<Num, &'static str> {
+    let exponent = exponent.parse::<i64>().map_err(|_| "Number is too long")?;
+    let power = Num::new(10)
+        .powi(exponent.abs())
+        .map_err(|_| "Number is too long")?;
+    match exponent < 0 {
+        true => mantissa.checked_div(power),
+        false => mantissa.checked_mul(power),
+    }
+    .ok_or("Number is too long")
+}
+
+struct AsciiParser<'a> {
+    tokens: Vec<Token<'a>>,
+    cursor: usize,
+}
+
+impl<'a> AsciiParser<'a> {
+    fn peek(&self) -> Token<'a> {
+        self.tokens[self.cursor]
+    }
+
+    fn next(&mut self) -> Token<'a> {
+        let token = self.tokens[self.cursor];
+        if token != Token::Eof {
+            self.cursor += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, op: char) -> Result<(), &'static str> {
+        match self.next() {
+            Token::Op(c) if c == op => Ok(()),
+            _ => Err(match op {
+                '(' => "Expected (",
+                ')' => "Expected )",
+                '[' => "Expected [",
+                ']' => "Expected ]",
+                '|' => "Expected |",
+                _ => "Expected ,",
+            }),
+        }
+    }
+
+    /// Terms separated by + and -
+    fn parse_expr(&mut self) -> Result<Expr<'a>, &'static str> {
+        let mut expr = self.parse_term()?;
+        loop {
+            match self.peek() {
+                Token::Op('+') => {
+                    self.next();
+                    expr = expr + self.parse_term()?;
+                }
+                Token::Op('-') => {
+                    self.next();
+                    expr = expr - self.parse_term()?;
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    /// Factors separated by *, / or nothing as in 2x
+    fn parse_term(&mut self) -> Result<Expr<'a>, &'static str> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Token::Op('*') => {
+                    self.next();
+                    expr = expr * self.parse_unary()?;
+                }
+                Token::Op('/') => {
+                    self.next();
+                    expr = match (expr, self.parse_unary()?) {
+                        (_, Expr::Num(n)) if n.is_zero() => return Err("Division by zero"),
+                        (expr, divisor) => expr / divisor,
+                    };
+                }
+                Token::Num(_) | Token::Ident(_) | Token::Op('(') | Token::Op('[') => {
+                    expr = expr * self.parse_power()?;
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr<'a>, &'static str> {
+        match self.peek() {
+            Token::Op('-') => {
+                self.next();
+                Ok(-self.parse_unary()?)
+            }
+            Token::Op('+') => {
+                self.next();
+                self.parse_unary()
+            }
+            _ => self.parse_power(),
+        }
+    }
+
+    /// x^y^z->x^(y^z) where the exponent may have a sign as in 2^-x
+    fn parse_power(&mut self) -> Result<Expr<'a>, &'static str> {
+        let base = self.parse_primary()?;
+        match self.peek() {
+            Token::Op('^') => {
+                self.next();
+                Ok(base ^ self.parse_unary()?)
+            }
+            _ => Ok(base),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr<'a>, &'static str> {
+        match self.next() {
+            Token::Num(n) => Ok(Expr::Num(n)),
+            Token::Op('(') => {
+                let expr = self.parse_expr()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Token::Op('|') => {
+                let expr = self.parse_expr()?;
+                self.expect('|')?;
+                Ok(call(FuncName::Abs, expr))
+            }
+            Token::Op('[') => self.parse_matrix(),
+            Token::Ident(name) if self.peek() == Token::Op('(') => self.parse_call(name),
+            Token::Ident(name) if is_call(name) => Err("Expected ( after function name"),
+            Token::Ident(name) => Ok(ident(name)),
+            Token::Eof => Err("Unexpected end of input"),
+            Token::Op(_) | Token::Cmp(_) => Err("Unexpected operator"),
+        }
+    }
+
+    /// [[a, b], [c, d]]
+    fn parse_matrix(&mut self) -> Result<Expr<'a>, &'static str> {
+        let mut rows = vec![];
+        loop {
+            self.expect('[')?;
+            rows.push(self.parse_args()?);
+            self.expect(']')?;
+            match self.next() {
+                Token::Op(',') => continue,
+                Token::Op(']') => break,
+                _ => return Err("Expected ]"),
+            }
+        }
+        if rows.iter().any(|row| row.len() != rows[0].len()) {
+            return Err("Every row of matrix must have the same length");
+        }
+        Ok(Expr::Matrix(Matrix::new(rows, Delimiter::Bracket)))
+    }
+
+    fn parse_args(&mut self) -> Result<Vec<Expr<'a>>, &'static str> {
+        let mut args = vec![self.parse_expr()?];
+        // A trailing , + or , - is the direction of a limit
+        let direction = |p: &Self| {
+            matches!(p.tokens[p.cursor + 1], Token::Op('+' | '-'))
+                && p.tokens.get(p.cursor + 2) == Some(&Token::Op(')'))
+        };
+        while self.peek() == Token::Op(',') && !direction(self) {
+            self.next();
+            args.push(self.parse_expr()?);
+        }
+        Ok(args)
+    }
+
+    /// (value, lhs cmp rhs) or (value, otherwise) separated by , until )
+    fn parse_pieces(&mut self) -> Result<Expr<'a>, &'static str> {
+        let mut pieces = vec![];
+        loop {
+            self.expect('(')?;
+            let value = self.parse_expr()?;
+            self.expect(',')?;
+            let cond = match self.peek() {
+                Token::Ident("otherwise") => {
+                    self.next();
+                    None
+                }
+                _ => {
+                    let lhs = self.parse_expr()?;
+                    let Token::Cmp(cmp) = self.next() else {
+                        return Err("Expected comparison");
+                    };
+                    Some(Condition::new(lhs, cmp, self.parse_expr()?))
+                }
+            };
+            self.expect(')')?;
+            pieces.push((value, cond));
+            match self.next() {
+                Token::Op(',') => continue,
+                Token::Op(')') => break,
+                _ => return Err("Expected )"),
+            }
+        }
+        Ok(Expr::Piecewise(Piecewise::new(pieces)))
+    }
+
+    /// f(x) for functions, sqrt(x), integrate(f, x[, a, b]), limit(f, x, a[, +|-]),
+    /// sum(f, k, a, b), product(f, k, a, b) and piecewise((f, cond), ...)
+    /// while any other name is multiplied
+    fn parse_call(&mut self, name: &'a str) -> Result<Expr<'a>, &'static str> {
+        self.expect('(')?;
+        if name == "piecewise" {
+            return self.parse_pieces();
+        }
+        if let "limit" | "lim" = name {
+            let args = self.parse_args()?;
+            let direction = match self.peek() {
+                Token::Op(',') => {
+                    self.next();
+                    match self.next() {
+                        Token::Op('+') => Direction::Plus,
+                        Token::Op('-') => Direction::Minus,
+                        _ => return Err("Direction of limit must be + or -"),
+                    }
+                }
+                _ => Direction::Both,
+            };
+            self.expect(')')?;
+            return match &args[..] {
+                [expr, Expr::Sym(var), point] => Ok(Expr::Limit(Limit::new(
+                    expr.clone(),
+                    *var,
+                    point.clone(),
+                    direction,
+                ))),
+                _ => Err("limit expects an expr, a symbol and a point"),
+            };
+        }
+        let args = self.parse_args()?;
+        self.expect(')')?;
+        let func = match (name, &args[..]) {
+            ("sqrt", [arg]) => return Ok(arg.clone() ^ Expr::Num(Num::frac(1, 2))),
+            ("integrate" | "int", [integrand, Expr::Sym(var)]) => {
+                return Ok(Expr::Integral(Integral::new(integrand.clone(), *var, None)))
+            }
+            ("integrate" | "int", [integrand, Expr::Sym(var), a, b]) => {
+                let bounds = Some((a.clone(), b.clone()));
+                return Ok(Expr::Integral(Integral::new(
+                    integrand.clone(),
+                    *var,
+                    bounds,
+                )));
+            }
+            ("sum" | "product", [body, Expr::Sym(var), lower, upper]) => {
+                let op = if name == "sum" {
+                    SumOp::Sum
+                } else {
+                    SumOp::Product
+                };
+                let (body, lower, upper) = (body.clone(), lower.clone(), upper.clone());
+                return Ok(Expr::Sum(Sum::new(op, body, *var, lower, upper)));
+            }
+            ("integrate" | "int" | "sum" | "product", _) => {
+                return Err("Bound variable must be a symbol")
+            }
+            (name, [arg]) => match func_name(name) {
+                Some(func) => return Ok(call(func, arg.clone())),
+                None => arg.clone(),
+            },
+            _ => return Err("Function expects one argument"),
+        };
+        Ok(ident(name) * func)
+    }
+}
+
+/// Constant, imaginary unit, Greek letter or symbol with subscript after _
+/// which may also be a Greek letter
+fn ident(name: &str) -> Expr<'_> {
+    match name {
+        "pi" => Expr::Const(Const::Pi),
+        "e" => Expr::Const(Const::E),
+        "inf" | "infinity" | "oo" => Expr::Const(Const::Infinity),
+        "i" => Expr::Num(Num::i()),
+        name => {
+            let (symbol, sub) = name.split_once('_').unwrap_or((name, ""));
+            Expr::Sym(Sym::new(greek(symbol)).set_sub(greek(sub)))
+        }
+    }
+}
+
+/// alpha->\alpha for Greek letters
+fn greek(name: &str) -> &str {
+    GREEK.iter().find(|g| g[1..] == *name).map_or(name, |g| *g)
+}
+
+/// Names which must be followed by their arguments in parentheses
+fn is_call(name: &str) -> bool {
+    func_name(name).is_some()
+        || matches!(
+            name,
+            "sqrt" | "integrate" | "int" | "limit" | "lim" | "sum" | "product" | "piecewise"
+        )
+}
+
+fn func_name(name: &str) -> Option<FuncName> {
+    match name {
+        "sin" => Some(FuncName::Sin),
+        "cos" => Some(FuncName::Cos),
+        "tan" => Some(FuncName::Tan),
+        "cot" => Some(FuncName::Cot),
+        "sec" => Some(FuncName::Sec),
+        "csc" => Some(FuncName::Csc),
+        "asin" | "arcsin" => Some(FuncName::Arcsin),
+        "acos" | "arccos" => Some(FuncName::Arccos),
+        "atan" | "arctan" => Some(FuncName::Arctan),
+        "sinh" => Some(FuncName::Sinh),
+        "cosh" => Some(FuncName::Cosh),
+        "tanh" => Some(FuncName::Tanh),
+        "exp" => Some(FuncName::Exp),
+        "ln" | "log" => Some(FuncName::Ln),
+        "abs" => Some(FuncName::Abs),
+        "sign" | "sgn" => Some(FuncName::Sign),
+        "re" => Some(FuncName::Re),
+        "im" => Some(FuncName::Im),
+        "conj" | "conjugate" => Some(FuncName::Conj),
+        _ => None,
+    }
+}
+
+/// Conventional ASCII math such as 2*x^2 + 3*(y+1)/4, sin(x)**2 or 1.5e-3 where juxtaposition
+/// also multiplies and multi-letter names other than functions and constants are symbols,
+/// while digits separated by whitespace are one number as in LaTeX so that 2 3 is 23
+pub fn ascii_to_expr(input: &str) -> Result<Expr<'_>, &'static str> {
+    let mut parser = AsciiParser {
+        tokens: tokenize(input)?,
+        cursor: 0,
+    };
+    let expr = parser.parse_expr()?;
+    match parser.peek() {
+        Token::Eof => Ok(expr),
+        Token::Op(')') => Err("Unmatched )"),
+        _ => Err("Unexpected token"),
+    }
+}
+
+/// ASCII math with explicit * readable by ascii_to_expr
+pub fn to_ascii(expr: &Expr) -> String {
+    match expr {
+        Expr::Add(add) => {
+            let mut result = to_ascii(&add.exprs[0]);
+            add.exprs[1..].iter().for_each(|term| {
+                result = match leading_minus(term) {
+                    true => format!("{} - {}", result, Ascii.wrap(&negate(term), MUL)),
+                    false => format!("{} + {}", result, to_ascii(term)),
+                }
+            });
+            result
+        }
+        Expr::Mul(mul)
+            if expr.detach_coeff().0.is_real()
+                && (expr.detach_coeff().0.den > 1
+                    || mul.exprs.iter().any(|e| reciprocal(e).is_some())) =>
+        {
+            let (negative, fractions) = split_fractions(expr);
+            let factors: Vec<String> = fractions
+                .iter()
+                .map(|(num, den)| match den {
+                    Expr::Num(n) if n.is_one() => Ascii.wrap(num, MUL),
+                    den => format!("{}/{}", Ascii.wrap(num, MUL), Ascii.wrap(den, POW)),
+                })
+                .collect();
+            let sign = if negative { "-" } else { "" };
+            format!("{}{}", sign, factors.join("*"))
+        }
+        Expr::Mul(mul) => {
+            let factors: Vec<String> = mul
+                .exprs
+                .iter()
+                .enumerate()
+                .map(|(i, e)| match e {
+                    Expr::Num(x) if i == 0 && x.is_real() => to_ascii(e),
+                    e => Ascii.wrap(e, MUL),
+                })
+                .collect();
+            match &factors[..] {
+                [minus_one, rest @ ..] if minus_one == "-1" && !rest.is_empty() => {
+                    format!("-{}", rest.join("*"))
+                }
+                factors => factors.join("*"),
+            }
+        }
+        Expr::Pow(_) if reciprocal(expr).is_some() => {
+            format!("1/{}", Ascii.wrap(&reciprocal(expr).unwrap(), POW))
+        }
+        Expr::Pow(pow) if is_root(expr) && matches!(*pow.pow, Expr::Num(n) if n.den == 2) => {
+            let root = format!("sqrt({})", to_ascii(&pow.body));
+            match *pow.pow {
+                Expr::Num(n) if n.num == 1 => root,
+                Expr::Num(n) => format!("{}^{}", root, n.num),
+                _ => unreachable!(),
+            }
+        }
+        Expr::Pow(pow) => format!(
+            "{}^{}",
+            Ascii.wrap(&pow.body, ATOM),
+            Ascii.wrap(&pow.pow, ATOM)
+        ),
+        Expr::Func(func) => {
+            let name = match func.name {
+                FuncName::Arcsin => "asin",
+                FuncName::Arccos => "acos",
+                FuncName::Arctan => "atan",
+                FuncName::Abs => "abs",
+                FuncName::Sign => "sign",
+                FuncName::Re => "re",
+                FuncName::Im => "im",
+                FuncName::Conj => "conj",
+                name => &name.command()[1..],
+            };
+            format!("{}({})", name, to_ascii(&func.arg))
+        }
+        Expr::Integral(integral) => {
+            let var = to_ascii(&Expr::Sym(integral.var));
+            match &integral.bounds {
+                Some(b) => format!(
+                    "integrate({}, {}, {}, {})",
+                    to_ascii(&integral.integrand),
+                    var,
+                    to_ascii(&b.0),
+                    to_ascii(&b.1)
+                ),
+                None => format!("integrate({}, {})", to_ascii(&integral.integrand), var),
+            }
+        }
+        Expr::Limit(limit) => {
+            let direction = match limit.direction {
+                Direction::Both => "",
+                Direction::Plus => ", +",
+                Direction::Minus => ", -",
+            };
+            format!(
+                "limit({}, {}, {}{})",
+                to_ascii(&limit.expr),
+                to_ascii(&Expr::Sym(limit.var)),
+                to_ascii(&limit.point),
+                direction
+            )
+        }
+        Expr::Sum(sum) => format!(
+            "{}({}, {}, {}, {})",
+            match sum.op {
+                SumOp::Sum => "sum",
+                SumOp::Product => "product",
+            },
+            to_ascii(&sum.body),
+            to_ascii(&Expr::Sym(sum.var)),
+            to_ascii(&sum.lower),
+            to_ascii(&sum.upper)
+        ),
+        Expr::Piecewise(piecewise) => {
+            let pieces: Vec<String> = piecewise
+                .pieces
+                .iter()
+                .map(|(value, cond)| {
+                    let cond = match cond {
+                        Some(c) => {
+                            let cmp = match c.cmp.command() {
+                                "\\leq" => "<=",
+                                "\\geq" => ">=",
+                                "\\neq" => "!=",
+                                cmp => cmp,
+                            };
+                            format!("{} {} {}", to_ascii(&c.lhs), cmp, to_ascii(&c.rhs))
+                        }
+                        None => String::from("otherwise"),
+                    };
+                    format!("({}, {})", to_ascii(value), cond)
+                })
+                .collect();
+            format!("piecewise({})", pieces.join(", "))
+        }
+        Expr::Matrix(matrix) => {
+            let rows: Vec<String> = matrix
+                .rows
+                .iter()
+                .map(|row| {
+                    let row: Vec<String> = row.iter().map(to_ascii).collect();
+                    format!("[{}]", row.join(", "))
+                })
+                .collect();
+            format!("[{}]", rows.join(", "))
+        }
+        Expr::Sym(x) => {
+            let symbol = x.symbol().trim_start_matches('\\');
+            match x.sub() {
+                "" => symbol.to_string(),
+                sub => format!("{}_{}", symbol, sub.trim_start_matches('\\')),
+            }
+        }
+        Expr::Const(Const::Pi) => String::from("pi"),
+        Expr::Const(Const::E) => String::from("e"),
+        Expr::Const(Const::Infinity) => String::from("inf"),
+        Expr::Num(x) if !x.is_real() => {
+            let im = match x.im() {
+                im if im.is_one() => String::from("i"),
+                im if im == Num::new(-1) => String::from("-i"),
+                im => format!("{}*i", to_ascii(&Expr::Num(im))),
+            };
+            match x.re() {
+                re if re.is_zero() => im,
+                re => match im.strip_prefix('-') {
+                    Some(im) => format!("{} - {}", to_ascii(&Expr::Num(re)), im),
+                    None => format!("{} + {}", to_ascii(&Expr::Num(re)), im),
+                },
+            }
+        }
+        Expr::Num(x) if x.is_integer() => x.num.to_string(),
+        Expr::Num(x) => format!("{}/{}", x.num, x.den),
+    }
+}
+
+/// ASCII of exprs parenthesized by binding
+struct Ascii;
+
+impl<'a> Printer<Expr<'a>> for Ascii {
+    type Output = String;
+
+    fn print(&self, expr: &Expr<'a>) -> (String, u8) {
+        (to_ascii(expr), binding(expr))
+    }
+
+    fn parens(&self, ascii: String) -> String {
+        format!("({})", ascii)
+    }
+}
+
+/// Precedence of the ASCII of expr where calls such as integrate(f, x) are atoms
+fn binding(expr: &Expr) -> u8 {
+    match expr {
+        _ if leading_minus(expr) => ADD,
+        Expr::Add(_) => ADD,
+        Expr::Num(x) if !x.is_real() && !x.re().is_zero() => ADD,
+        Expr::Num(x) if !x.is_integer() && *x != Num::i() => MUL,
+        Expr::Mul(_) => MUL,
+        Expr::Pow(_) if reciprocal(expr).is_some() => MUL,
+        Expr::Pow(pow)
+            if is_root(expr) && matches!(*pow.pow, Expr::Num(n) if n.num == 1 && n.den == 2) =>
+        {
+            ATOM
+        }
+        Expr::Pow(_) => POW,
+        _ => ATOM,
+    }
+}
+
+#[test]
+fn test_ascii() {
+    let tests = [
+        ["2*x^2 + 3*(y+1)/4", "2*x^{2}+3*(y+1)*4^{-1}"],
+        ["2x**2-x", "2*x^{2}+-1*x"],
+        ["-x^2", "-1*x^{2}"],
+        ["2^-x", "2^{-1*x}"],
+        ["x^y^z", "x^{y^{z}}"],
+        ["sin(x)^2 + log(2pi)", "sin(x)^{2}+ln(2*\\pi)"],
+        ["sqrt(x_1) + |alpha|", "x_{1}^{1/2}+|\\alpha|"],
+        ["0.25e + 3i", "1/4*e+3*i"],
+        ["f(x+1)", "f*(x+1)"],
+        ["1e5 + 2.5e-3x + 2e", "100000+1/400*x+2*e"],
+        ["x_alpha*varepsilon", "x_{\\alpha}*\\varepsilon"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(ascii_to_expr(test[0]).expect(test[0]).to_string(), test[1]);
+    });
+    let int = ascii_to_expr("integrate(x^2, x, 0, 1)").unwrap();
+    assert_eq!(int.collect().to_string(), "1/3");
+    let lim = ascii_to_expr("limit(sin(x)/x, x, 0, +)").unwrap();
+    assert!(matches!(lim, Expr::Limit(ref l) if l.direction == Direction::Plus));
+    let matrix = ascii_to_expr("[[1, x], [y, 2]]").unwrap();
+    assert_eq!(
+        super::serialize(&matrix),
+        "\\begin{bmatrix}1&x \\\\y &2\\end{bmatrix}"
+    );
+    let errors = [
+        "1/0",
+        "(x+1",
+        "x+",
+        "2 $ 3",
+        "sin(x, y)",
+        "[[1], [1, 2]]",
+        "sin x",
+        "sqrt 2",
+        "1e30",
+        "piecewise((1, x))",
+    ];
+    errors.iter().for_each(|input| {
+        assert!(ascii_to_expr(input).is_err(), "{}", input);
+    });
+
+    let printed = [
+        ["2*x^2 + 3*(y+1)/4", "2*x^2 + 3*(y + 1)/4"],
+        ["x - 2*y - 1/2", "x - 2*y - 1/2"],
+        ["-x/(2*y)", "-x/(2*y)"],
+        ["(x+1)^(1/2) + x^(-2)", "sqrt(x + 1) + 1/x^2"],
+        ["(-2)^x*(1+i)", "(1 + i)*(-2)^x"],
+        ["x^(y+1) - asin(x)", "x^(y + 1) - asin(x)"],
+        ["(x^2)^y + alpha_1", "(x^2)^y + alpha_1"],
+        ["2 - 3i", "2 - 3*i"],
+        ["x_alpha + varphi", "x_alpha + varphi"],
+        ["x/y*z - 1/(2*x)*y", "x/y*z - 1/(2*x)*y"],
+        [
+            "piecewise((x^2, x <= 1), (1, x != 2), (0, otherwise))",
+            "piecewise((x^2, x <= 1), (1, x != 2), (0, otherwise))",
+        ],
+    ];
+    printed.iter().for_each(|test| {
+        let expr = ascii_to_expr(test[0]).unwrap().collect();
+        assert_eq!(to_ascii(&expr), test[1]);
+        assert_eq!(ascii_to_expr(&to_ascii(&expr)).unwrap().collect(), expr);
+    });
+}
+
+#[test]
+fn test_ascii_parsing() {
+    let tests = [
+        ["2 3", "23"],
+        ["2 3x + 1 000", "23*x+1000"],
+        ["x^2 3", "x^{23}"],
+        ["2 e + 2e", "2*e+2*e"],
+        ["1E+2 + 3e-1y", "100+3/10*y"],
+        ["exp(2) + abs(x)", "exp(2)+|x|"],
+        [
+            "piecewise((x, x < 0), (-x, otherwise))",
+            "piecewise(x if x<0, -1*x otherwise)",
+        ],
+        ["theta_beta + Omega_1", "\\theta_{\\beta}+\\Omega_{1}"],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(ascii_to_expr(test[0]).expect(test[0]).to_string(), test[1]);
+    });
+    let errors = [
+        ["exp 2", "Expected ( after function name"],
+        ["abs x", "Expected ( after function name"],
+        ["lim x", "Expected ( after function name"],
+        ["piecewise(1, x < 0)", "Expected ("],
+        ["piecewise((1, x < 0)", "Expected )"],
+        ["piecewise((1, x))", "Expected comparison"],
+        ["1e30", "Number is too long"],
+        ["1.2.3", "Invalid number"],
+    ];
+    errors.iter().for_each(|test| {
+        assert_eq!(ascii_to_expr(test[0]), Err(test[1]), "{}", test[0]);
+    });
+}
@@ -0,0 +1,676 @@
+use crate::{
+    Add, Cmp, Condition, Const, Delimiter, Direction, Expr, Func, FuncName, Integral, Limit,
+    Matrix, Mul, Num, Piecewise, Pow, Sum, SumOp, Sym,
+};
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+/// Version of the JSON AST, bumped whenever a node changes shape
+pub const JSON_VERSION: i64 = 1;
+
+/// Nesting of arrays and objects beyond which parsing stops before the stack overflows
+const MAX_DEPTH: usize = 256;
+
+/// Symbol names decoded from escaped strings such as "\\beta", kept once each for the program
+static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/// Parsed JSON where strings without escapes borrow from the input
+#[derive(PartialEq, Clone, Debug)]
+enum Json<'a> {
+    Null,
+    Bool(bool),
+    Number(&'a str),
+    Str(Cow<'a, str>),
+    Array(Vec<Json<'a>>),
+    Object(Vec<(Cow<'a, str>, Json<'a>)>),
+}
+
+struct JsonParser<'a> {
+    input: &'a str,
+    cursor: usize,
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn peek(&mut self) -> Option<u8> {
+        while self
+            .input
+            .as_bytes()
+            .get(self.cursor)
+            .is_some_and(|c| c.is_ascii_whitespace())
+        {
+            self.cursor += 1;
+        }
+        self.input.as_bytes().get(self.cursor).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), &'static str> {
+        match self.peek() {
+            Some(next) if next == c => {
+                self.cursor += 1;
+                Ok(())
+            }
+            _ => Err(match c {
+                b':' => "Expected :",
+                b'"' => "Expected \"",
+                _ => "Unexpected end of JSON",
+            }),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Json<'a>) -> Result<Json<'a>, &'static str> {
+        match self.input[self.cursor..].starts_with(keyword) {
+            true => {
+                self.cursor += keyword.len();
+                Ok(value)
+            }
+            false => Err("Invalid JSON"),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json<'a>, &'static str> {
+        if self.depth == MAX_DEPTH {
+            return Err("JSON is nested too deeply");
+        }
+        self.depth += 1;
+        let value = self.nested();
+        self.depth -= 1;
+        value
+    }
+
+    fn nested(&mut self) -> Result<Json<'a>, &'static str> {
+        match self.peek().ok_or("Unexpected end of JSON")? {
+            b'n' => self.keyword("null", Json::Null),
+            b't' => self.keyword("true", Json::Bool(true)),
+            b'f' => self.keyword("false", Json::Bool(false)),
+            b'"' => Ok(Json::Str(self.string()?)),
+            b'[' => {
+                self.cursor += 1;
+                let mut items = vec![];
+                if self.peek() == Some(b']') {
+                    self.cursor += 1;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    match self.peek() {
+                        Some(b',') => self.cursor += 1,
+                        Some(b']') => {
+                            self.cursor += 1;
+                            return Ok(Json::Array(items));
+                        }
+                        _ => return Err("Expected , or ]"),
+                    }
+                }
+            }
+            b'{' => {
+                self.cursor += 1;
+                let mut fields = vec![];
+                if self.peek() == Some(b'}') {
+                    self.cursor += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    let key = self.string()?;
+                    self.expect(b':')?;
+                    fields.push((key, self.value()?));
+                    match self.peek() {
+                        Some(b',') => self.cursor += 1,
+                        Some(b'}') => {
+                            self.cursor += 1;
+                            return Ok(Json::Object(fields));
+                        }
+                        _ => return Err("Expected , or }"),
+                    }
+                }
+            }
+            b'-' | b'0'..=b'9' => {
+                let start = self.cursor;
+                let bytes = self.input.as_bytes();
+                while bytes
+                    .get(self.cursor)
+                    .is_some_and(|c| c.is_ascii_digit() || b"+-.eE".contains(c))
+                {
+                    self.cursor += 1;
+                }
+                Ok(Json::Number(&self.input[start..self.cursor]))
+            }
+            _ => Err("Invalid JSON"),
+        }
+    }
+
+    /// Strings without escapes are borrowed from the input and the others are decoded
+    fn string(&mut self) -> Result<Cow<'a, str>, &'static str> {
+        self.expect(b'"')?;
+        let start = self.cursor;
+        let rest = &self.input[start..];
+        let end = rest.find(['"', '\\']).ok_or("Unterminated string")?;
+        if rest.as_bytes()[end] == b'"' {
+            self.cursor += end + 1;
+            return Ok(Cow::Borrowed(&rest[..end]));
+        }
+        let mut string = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.cursor += i + 1;
+                    return Ok(Cow::Owned(string));
+                }
+                '\\' => string.push(match chars.next().ok_or("Unterminated string")?.1 {
+                    '"' => '"',
+                    '\\' => '\\',
+                    '/' => '/',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => {
+                        let hex: String =
+                            (0..4).filter_map(|_| chars.next()).map(|x| x.1).collect();
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or("Invalid unicode escape")?
+                    }
+                    _ => return Err("Invalid escape"),
+                }),
+                c => string.push(c),
+            }
+        }
+        Err("Unterminated string")
+    }
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    text.chars().for_each(|c| match c {
+        '"' => quoted.push_str("\\\""),
+        '\\' => quoted.push_str("\\\\"),
+        '\n' => quoted.push_str("\\n"),
+        c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+        c => quoted.push(c),
+    });
+    quoted.push('"');
+    quoted
+}
+
+/// -3/4 or 2 with the denominator omitted for integers
+fn rational(x: Num) -> String {
+    match x.den {
+        1 => x.num.to_string(),
+        den => format!("{}/{}", x.num, den),
+    }
+}
+
+/// Integers are limited to -i64::MAX..=i64::MAX so that negating them never overflows
+fn parse_rational(text: &str) -> Result<Num, &'static str> {
+    let parse = |text: &str| {
+        text.parse::<i64>()
+            .ok()
+            .filter(|n| *n != i64::MIN)
+            .ok_or("Invalid number")
+    };
+    let (num, den) = text.split_once('/').unwrap_or((text, "1"));
+    Num::checked_frac(parse(num)?, parse(den)?)
+}
+
+fn func_name(name: FuncName) -> &'static str {
+    match name {
+        FuncName::Sin => "sin",
+        FuncName::Cos => "cos",
+        FuncName::Tan => "tan",
+        FuncName::Cot => "cot",
+        FuncName::Sec => "sec",
+        FuncName::Csc => "csc",
+        FuncName::Arcsin => "arcsin",
+        FuncName::Arccos => "arccos",
+        FuncName::Arctan => "arctan",
+        FuncName::Sinh => "sinh",
+        FuncName::Cosh => "cosh",
+        FuncName::Tanh => "tanh",
+        FuncName::Exp => "exp",
+        FuncName::Ln => "ln",
+        FuncName::Abs => "abs",
+        FuncName::Sign => "sign",
+        FuncName::Re => "re",
+        FuncName::Im => "im",
+        FuncName::Conj => "conj",
+    }
+}
+
+const FUNC_NAMES: [FuncName; 19] = [
+    FuncName::Sin,
+    FuncName::Cos,
+    FuncName::Tan,
+    FuncName::Cot,
+    FuncName::Sec,
+    FuncName::Csc,
+    FuncName::Arcsin,
+    FuncName::Arccos,
+    FuncName::Arctan,
+    FuncName::Sinh,
+    FuncName::Cosh,
+    FuncName::Tanh,
+    FuncName::Exp,
+    FuncName::Ln,
+    FuncName::Abs,
+    FuncName::Sign,
+    FuncName::Re,
+    FuncName::Im,
+    FuncName::Conj,
+];
+
+fn cmp_name(cmp: Cmp) -> &'static str {
+    match cmp {
+        Cmp::Lt => "lt",
+        Cmp::Le => "le",
+        Cmp::Gt => "gt",
+        Cmp::Ge => "ge",
+        Cmp::Eq => "eq",
+        Cmp::Ne => "ne",
+    }
+}
+
+fn node(kind: &str, fields: &[(&str, String)]) -> String {
+    let mut json = format!("{{\"type\":{}", quote(kind));
+    fields
+        .iter()
+        .for_each(|(key, value)| json.push_str(&format!(",{}:{}", quote(key), value)));
+    json.push('}');
+    json
+}
+
+fn array(items: impl Iterator<Item = String>) -> String {
+    format!("[{}]", items.collect::<Vec<_>>().join(","))
+}
+
+fn children(exprs: &[&Expr]) -> (&'static str, String) {
+    ("children", array(exprs.iter().map(|e| encode(e))))
+}
+
+fn sym(x: &Sym) -> String {
+    node(
+        "sym",
+        &[("name", quote(x.symbol())), ("sub", quote(x.sub()))],
+    )
+}
+
+fn encode(expr: &Expr) -> String {
+    match expr {
+        Expr::Num(x) => node(
+            "num",
+            &[
+                ("re", quote(&rational(x.re()))),
+                ("im", quote(&rational(x.im()))),
+            ],
+        ),
+        Expr::Sym(x) => sym(x),
+        Expr::Const(c) => {
+            let name = match c {
+                Const::Pi => "pi",
+                Const::E => "e",
+                Const::Infinity => "infinity",
+            };
+            node("const", &[("name", quote(name))])
+        }
+        Expr::Add(add) => node("add", &[children(&add.exprs.iter().collect::<Vec<_>>())]),
+        Expr::Mul(mul) => node("mul", &[children(&mul.exprs.iter().collect::<Vec<_>>())]),
+        Expr::Pow(pow) => node("pow", &[children(&[&pow.body, &pow.pow])]),
+        Expr::Func(func) => node(
+            "func",
+            &[
+                ("name", quote(func_name(func.name))),
+                children(&[&func.arg]),
+            ],
+        ),
+        Expr::Matrix(matrix) => {
+            let delimiter = match matrix.delimiter {
+                Delimiter::Paren => "paren",
+                Delimiter::Bracket => "bracket",
+                Delimiter::Vert => "vert",
+            };
+            let rows = matrix.rows.iter().map(|row| array(row.iter().map(encode)));
+            node(
+                "matrix",
+                &[("delimiter", quote(delimiter)), ("rows", array(rows))],
+            )
+        }
+        Expr::Integral(integral) => {
+            let exprs = match &integral.bounds {
+                Some(bounds) => vec![&*integral.integrand, &bounds.0, &bounds.1],
+                None => vec![&*integral.integrand],
+            };
+            node("integral", &[("var", sym(&integral.var)), children(&exprs)])
+        }
+        Expr::Limit(limit) => {
+            let direction = match limit.direction {
+                Direction::Both => "both",
+                Direction::Plus => "plus",
+                Direction::Minus => "minus",
+            };
+            node(
+                "limit",
+                &[
+                    ("var", sym(&limit.var)),
+                    ("direction", quote(direction)),
+                    children(&[&limit.expr, &limit.point]),
+                ],
+            )
+        }
+        Expr::Sum(sum) => {
+            let op = match sum.op {
+                SumOp::Sum => "sum",
+                SumOp::Product => "product",
+            };
+            node(
+                "sum",
+                &[
+                    ("op", quote(op)),
+                    ("var", sym(&sum.var)),
+                    children(&[&sum.body, &sum.lower, &sum.upper]),
+                ],
+            )
+        }
+        Expr::Piecewise(piecewise) => {
+            let pieces = piecewise.pieces.iter().map(|(value, cond)| {
+                let condition = match cond {
+                    Some(c) => format!(
+                        "{{\"lhs\":{},\"cmp\":{},\"rhs\":{}}}",
+                        encode(&c.lhs),
+                        quote(cmp_name(c.cmp)),
+                        encode(&c.rhs)
+                    ),
+                    None => String::from("null"),
+                };
+                format!(
+                    "{{\"value\":{},\"condition\":{}}}",
+                    encode(value),
+                    condition
+                )
+            });
+            node("piecewise", &[("pieces", array(pieces))])
+        }
+    }
+}
+
+/// Versioned JSON AST such as
+/// {"version":1,"expr":{"type":"pow","children":[{"type":"sym","name":"x","sub":""},...]}}
+/// where exact numbers are strings of the real and imaginary parts
+pub fn to_json(expr: &Expr) -> String {
+    format!("{{\"version\":{},\"expr\":{}}}", JSON_VERSION, encode(expr))
+}
+
+fn field<'b, 'a>(json: &'b Json<'a>, key: &str) -> Result<&'b Json<'a>, &'static str> {
+    match json {
+        Json::Object(fields) => fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+            .ok_or("Missing field"),
+        _ => Err("Expected an object"),
+    }
+}
+
+fn text<'b>(json: &'b Json, key: &str) -> Result<&'b str, &'static str> {
+    match field(json, key)? {
+        Json::Str(s) => Ok(s),
+        _ => Err("Expected a string"),
+    }
+}
+
+/// String borrowed from the input or interned when decoded as symbols outlive the parsed JSON
+fn name<'a>(json: &Json<'a>, key: &str) -> Result<&'a str, &'static str> {
+    match field(json, key)? {
+        Json::Str(Cow::Borrowed(s)) => Ok(s),
+        Json::Str(Cow::Owned(s)) => Ok(intern(s)),
+        _ => Err("Expected a string"),
+    }
+}
+
+fn intern(name: &str) -> &'static str {
+    let mut names = NAMES.lock().unwrap_or_else(|err| err.into_inner());
+    match names.get(name) {
+        Some(interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(name.into());
+            names.insert(interned);
+            interned
+        }
+    }
+}
+
+fn array_of<'b, 'a>(json: &'b Json<'a>, key: &str) -> Result<&'b [Json<'a>], &'static str> {
+    match field(json, key)? {
+        Json::Array(items) => Ok(items),
+        _ => Err("Expected an array"),
+    }
+}
+
+fn decode_sym<'a>(json: &Json<'a>) -> Result<Sym<'a>, &'static str> {
+    match text(json, "type")? {
+        "sym" => Ok(Sym::new(name(json, "name")?).set_sub(name(json, "sub")?)),
+        _ => Err("Expected a sym node"),
+    }
+}
+
+fn decode<'a>(json: &Json<'a>) -> Result<Expr<'a>, &'static str> {
+    let children = || -> Result<Vec<Expr<'a>>, &'static str> {
+        array_of(json, "children")?.iter().map(decode).collect()
+    };
+    let expr = match text(json, "type")? {
+        "num" => Expr::Num(Num::complex(
+            parse_rational(text(json, "re")?)?,
+            parse_rational(text(json, "im")?)?,
+        )),
+        "sym" => Expr::Sym(decode_sym(json)?),
+        "const" => Expr::Const(match text(json, "name")? {
+            "pi" => Const::Pi,
+            "e" => Const::E,
+            "infinity" => Const::Infinity,
+            _ => return Err("Unknown constant"),
+        }),
+        "add" => match children()? {
+            exprs if exprs.len() >= 2 => Expr::Add(Add { exprs }),
+            _ => return Err("add expects 2 or more children"),
+        },
+        "mul" => match children()? {
+            exprs if exprs.len() >= 2 => Expr::Mul(Mul { exprs }),
+            _ => return Err("mul expects 2 or more children"),
+        },
+        "pow" => match <[Expr; 2]>::try_from(children()?) {
+            Ok([body, pow]) => Expr::Pow(Pow {
+                body: Box::new(body),
+                pow: Box::new(pow),
+            }),
+            Err(_) => return Err("pow expects 2 children"),
+        },
+        "func" => {
+            let name = text(json, "name")?;
+            let name = FUNC_NAMES
+                .into_iter()
+                .find(|f| func_name(*f) == name)
+                .ok_or("Unknown function")?;
+            match <[Expr; 1]>::try_from(children()?) {
+                Ok([arg]) => Expr::Func(Func::new(name, arg)),
+                Err(_) => return Err("func expects 1 child"),
+            }
+        }
+        "matrix" => {
+            let delimiter = match text(json, "delimiter")? {
+                "paren" => Delimiter::Paren,
+                "bracket" => Delimiter::Bracket,
+                "vert" => Delimiter::Vert,
+                _ => return Err("Unknown delimiter"),
+            };
+            let rows = array_of(json, "rows")?
+                .iter()
+                .map(|row| match row {
+                    Json::Array(row) => row.iter().map(decode).collect(),
+                    _ => Err("Expected an array"),
+                })
+                .collect::<Result<Vec<Vec<_>>, _>>()?;
+            if rows.is_empty() || rows.iter().any(|row| row.len() != rows[0].len()) {
+                return Err("Matrix rows must have the same positive length");
+            }
+            Expr::Matrix(Matrix::new(rows, delimiter))
+        }
+        "integral" => {
+            let var = decode_sym(field(json, "var")?)?;
+            let mut exprs = children()?.into_iter();
+            match (exprs.next(), exprs.next(), exprs.next(), exprs.next()) {
+                (Some(integrand), None, None, None) => {
+                    Expr::Integral(Integral::new(integrand, var, None))
+                }
+                (Some(integrand), Some(lower), Some(upper), None) => {
+                    Expr::Integral(Integral::new(integrand, var, Some((lower, upper))))
+                }
+                _ => return Err("integral expects 1 or 3 children"),
+            }
+        }
+        "limit" => {
+            let var = decode_sym(field(json, "var")?)?;
+            let direction = match text(json, "direction")? {
+                "both" => Direction::Both,
+                "plus" => Direction::Plus,
+                "minus" => Direction::Minus,
+                _ => return Err("Unknown direction"),
+            };
+            match <[Expr; 2]>::try_from(children()?) {
+                Ok([expr, point]) => Expr::Limit(Limit::new(expr, var, point, direction)),
+                Err(_) => return Err("limit expects 2 children"),
+            }
+        }
+        "sum" => {
+            let op = match text(json, "op")? {
+                "sum" => SumOp::Sum,
+                "product" => SumOp::Product,
+                _ => return Err("Unknown sum op"),
+            };
+            let var = decode_sym(field(json, "var")?)?;
+            match <[Expr; 3]>::try_from(children()?) {
+                Ok([body, lower, upper]) => Expr::Sum(Sum::new(op, body, var, lower, upper)),
+                Err(_) => return Err("sum expects 3 children"),
+            }
+        }
+        "piecewise" => {
+            let pieces = array_of(json, "pieces")?
+                .iter()
+                .map(|piece| {
+                    let value = decode(field(piece, "value")?)?;
+                    let condition = match field(piece, "condition")? {
+                        Json::Null => None,
+                        c => {
+                            let cmp = match text(c, "cmp")? {
+                                "lt" => Cmp::Lt,
+                                "le" => Cmp::Le,
+                                "gt" => Cmp::Gt,
+                                "ge" => Cmp::Ge,
+                                "eq" => Cmp::Eq,
+                                "ne" => Cmp::Ne,
+                                _ => return Err("Unknown comparison"),
+                            };
+                            let (lhs, rhs) = (decode(field(c, "lhs")?)?, decode(field(c, "rhs")?)?);
+                            Some(Condition::new(lhs, cmp, rhs))
+                        }
+                    };
+                    Ok((value, condition))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if pieces.is_empty() {
+                return Err("piecewise expects a piece");
+            }
+            Expr::Piecewise(Piecewise::new(pieces))
+        }
+        _ => return Err("Unknown node type"),
+    };
+    Ok(expr)
+}
+
+/// Expr of the JSON AST produced by to_json, kept as is without collecting
+pub fn json_to_expr(input: &str) -> Result<Expr<'_>, &'static str> {
+    let mut parser = JsonParser {
+        input,
+        cursor: 0,
+        depth: 0,
+    };
+    let json = parser.value()?;
+    if parser.peek().is_some() {
+        return Err("Unexpected text after JSON");
+    }
+    match field(&json, "version")? {
+        Json::Number(version) if version.parse() == Ok(JSON_VERSION) => {}
+        _ => return Err("Unsupported JSON AST version"),
+    }
+    decode(field(&json, "expr")?)
+}
+
+#[test]
+fn test_json() {
+    use super::latex_to_expr;
+    assert_eq!(
+        to_json(&latex_to_expr("x_{1}^{2}")),
+        "{\"version\":1,\"expr\":{\"type\":\"pow\",\"children\":[\
+         {\"type\":\"sym\",\"name\":\"x\",\"sub\":\"1\"},\
+         {\"type\":\"num\",\"re\":\"2\",\"im\":\"0\"}]}}"
+    );
+    assert_eq!(
+        to_json(&latex_to_expr("\\frac{3}{4}i")),
+        "{\"version\":1,\"expr\":{\"type\":\"num\",\"re\":\"0\",\"im\":\"3/4\"}}"
+    );
+    let tests = [
+        "2x^{2}+3\\sin(\\alpha_{k})-\\pi",
+        "\\int_{0}^{1}e^{-t}dt",
+        "\\lim_{x\\to 0^{-}}\\frac{|x|}{x}",
+        "\\prod_{n=1}^{\\infty}\\left(1-\\frac{1}{n^{2}}\\right)",
+        "\\begin{bmatrix}1&x\\\\\\overline{z}&0\\end{bmatrix}",
+        "\\begin{cases}x&x\\geq 0\\\\-x&\\text{otherwise}\\end{cases}",
+        "\\varepsilon+\\ell x_{\\varphi}",
+    ];
+    tests.iter().for_each(|test| {
+        let expr = latex_to_expr(test);
+        let json = to_json(&expr);
+        assert_eq!(json_to_expr(&json), Ok(expr));
+    });
+    let json = " { \"expr\" : {\"type\": \"sym\", \"name\": \"\\\\beta\", \"sub\": \"\"},\n \"version\": 1 } ";
+    assert_eq!(json_to_expr(json), Ok(latex_to_expr("\\beta")));
+    let json = "{\"version\":1,\"expr\":{\"type\":\"sym\",\"name\":\"\\u0071\",\"sub\":\"\"}}";
+    assert_eq!(json_to_expr(json), Ok(latex_to_expr("q")));
+    // Decoded names which do not appear in the input as they are
+    let json = "{\"version\":1,\"expr\":{\"type\":\"sym\",\"name\":\"\\u0078y\",\"sub\":\"\\u00e9\\\\0\"}}";
+    let sym = Sym::new("xy").set_sub("\u{e9}\\0");
+    assert_eq!(json_to_expr(json), Ok(Expr::Sym(sym)));
+    let nested = format!("{}{}", "[".repeat(200_000), "]".repeat(200_000));
+    assert_eq!(json_to_expr(&nested), Err("JSON is nested too deeply"));
+    let errors = [
+        [
+            "{\"version\":2,\"expr\":{}}",
+            "Unsupported JSON AST version",
+        ],
+        [
+            "{\"version\":1,\"expr\":{\"type\":\"num\",\"re\":\"1/0\",\"im\":\"0\"}}",
+            "Division by zero",
+        ],
+        [
+            "{\"version\":1,\"expr\":{\"type\":\"pow\",\"children\":[]}}",
+            "pow expects 2 children",
+        ],
+        [
+            "{\"version\":1,\"expr\":{\"type\":\"add\",\"children\":[]}}",
+            "add expects 2 or more children",
+        ],
+        [
+            "{\"version\":1,\"expr\":{\"type\":\"num\",\"re\":\"-9223372036854775808\",\"im\":\"0\"}}",
+            "Invalid number",
+        ],
+        [
+            "{\"version\":1,\"expr\":{\"type\":\"sym\"",
+            "Expected , or }",
+        ],
+    ];
+    errors.iter().for_each(|[json, error]| {
+        assert_eq!(json_to_expr(json), Err(*error));
+    });
+}
@@ -7,7 +7,22 @@ pub enum Token<'a> {
     RCurlyBrace,
     RParen,
     LParen,
+    LBracket,
+    RBracket,
+    /// | which opens or closes an absolute value by position
+    Bar,
+    LBar,
+    RBar,
+    Less,
+    Greater,
+    Equal,
+    Ampersand,
+    Newline,
+    Begin(&'a str),
+    End(&'a str),
     Num(Num),
+    /// Digits of an integer beyond i64
+    BigNum(&'a str),
     Sym(&'a str),
     Error(&'a str),
     Eof,
@@ -16,7 +31,9 @@ pub enum Token<'a> {
 #[derive(Clone, PartialEq, Debug)]
 pub enum Infix {
     Add,
+    Sub,
     Mul,
+    Div,
     Underscore,
     Circumflex,
 }
@@ -52,7 +69,18 @@ impl<'a> Lexer<'a> {
         self.skip_whitespace();
         let token = match self.cur() {
             '+' => Token::Infix(Infix::Add),
+            '-' => Token::Infix(Infix::Sub),
             '*' => Token::Infix(Infix::Mul),
+            '/' => Token::Infix(Infix::Div),
+            '=' => Token::Equal,
+            '<' => Token::Less,
+            '>' => Token::Greater,
+            '|' => Token::Bar,
+            '&' => Token::Ampersand,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
             '{' => Token::LCurlyBrace,
             '}' => Token::RCurlyBrace,
             '^' => Token::Infix(Infix::Circumflex),
@@ -60,7 +88,7 @@ impl<'a> Lexer<'a> {
             '\u{0}' => return Token::Eof,
             '\\' => return self.read_command(),
             c if c.is_ascii_alphabetic() => Token::Sym(&self.input[self.cursor..self.cursor + 1]),
-            c if c.is_ascii_digit() => return Token::Num(self.read_number()),
+            c if c.is_ascii_digit() => return self.read_number(),
             _ => panic!("non ascii char is not allowed"),
         };
         self.read_char();
@@ -69,6 +97,15 @@ impl<'a> Lexer<'a> {
 
     fn read_command(&mut self) -> Token<'a> {
         self.read_char();
+        if self.cur() == '\\' {
+            self.read_char();
+            return Token::Newline;
+        }
+        // Spacing commands \, \; \! and \  are ignored
+        if let ',' | ';' | '!' | ' ' = self.cur() {
+            self.read_char();
+            return self.next_token();
+        }
         let mut offset = 0;
         while self.cur().is_ascii_alphabetic() {
             self.read_char();
@@ -79,6 +116,7 @@ impl<'a> Lexer<'a> {
                 self.skip_whitespace();
                 match self.read_char() {
                     '(' => Token::LParen,
+                    '|' => Token::LBar,
                     _ => Token::Error("Unexpected left command"),
                 }
             }
@@ -86,22 +124,37 @@ impl<'a> Lexer<'a> {
                 self.skip_whitespace();
                 match self.read_char() {
                     ')' => Token::RParen,
+                    '|' => Token::RBar,
                     _ => Token::Error("Unexpected right command"),
                 }
             }
+            "cdot" | "times" => Token::Infix(Infix::Mul),
+            "begin" | "end" => {
+                let command = &self.input[self.cursor - offset..self.cursor];
+                self.skip_whitespace();
+                if self.cur() != '{' {
+                    return Token::Error("Expected environment name");
+                }
+                match command {
+                    "begin" => Token::Begin(self.arg_to_string()),
+                    _ => Token::End(self.arg_to_string()),
+                }
+            }
             _ => Token::Sym(&self.input[self.cursor - 1 - offset..self.cursor]),
         }
     }
 
-    fn read_number(&mut self) -> Num {
+    /// Digits separated by whitespace such as 3 2 are one number as TeX typesets them
+    fn read_number(&mut self) -> Token<'a> {
+        let start = self.cursor;
         let mut number = String::new();
         while self.cur().is_ascii_digit() {
             number.push(self.read_char());
             self.skip_whitespace();
         }
-        match number.parse::<i32>() {
-            Ok(num) => Num::new(num),
-            Err(_) => panic!("Unexpected number parse error"),
+        match number.parse::<i64>() {
+            Ok(num) => Token::Num(Num::new(num)),
+            Err(_) => Token::BigNum(self.input[start..self.cursor].trim_end()),
         }
     }
 
@@ -134,4 +187,46 @@ fn test_lexer() {
     assert_eq!(lexer.next_token(), Token::Sym("c"));
     assert_eq!(lexer.next_token(), Token::Sym("\\x"));
     assert_eq!(lexer.next_token(), Token::Eof);
+
+    let mut lexer = Lexer::new("(x-1)=y");
+    assert_eq!(lexer.next_token(), Token::LParen);
+    assert_eq!(lexer.next_token(), Token::Sym("x"));
+    assert_eq!(lexer.next_token(), Token::Infix(Infix::Sub));
+    assert_eq!(lexer.next_token(), Token::Num(Num::new(1)));
+    assert_eq!(lexer.next_token(), Token::RParen);
+    assert_eq!(lexer.next_token(), Token::Equal);
+    assert_eq!(lexer.next_token(), Token::Sym("y"));
+    assert_eq!(lexer.next_token(), Token::Eof);
+
+    let mut lexer = Lexer::new("\\begin {pmatrix}1&x\\\\y\\end{pmatrix}");
+    assert_eq!(lexer.next_token(), Token::Begin("pmatrix"));
+    assert_eq!(lexer.next_token(), Token::Num(Num::new(1)));
+    assert_eq!(lexer.next_token(), Token::Ampersand);
+    assert_eq!(lexer.next_token(), Token::Sym("x"));
+    assert_eq!(lexer.next_token(), Token::Newline);
+    assert_eq!(lexer.next_token(), Token::Sym("y"));
+    assert_eq!(lexer.next_token(), Token::End("pmatrix"));
+    assert_eq!(lexer.next_token(), Token::Eof);
+
+    let mut lexer = Lexer::new("|x|\\left|y\\right|<0>");
+    assert_eq!(lexer.next_token(), Token::Bar);
+    assert_eq!(lexer.next_token(), Token::Sym("x"));
+    assert_eq!(lexer.next_token(), Token::Bar);
+    assert_eq!(lexer.next_token(), Token::LBar);
+    assert_eq!(lexer.next_token(), Token::Sym("y"));
+    assert_eq!(lexer.next_token(), Token::RBar);
+    assert_eq!(lexer.next_token(), Token::Less);
+    assert_eq!(lexer.next_token(), Token::Num(Num::new(0)));
+    assert_eq!(lexer.next_token(), Token::Greater);
+
+    let mut lexer = Lexer::new("2\\cdot 3\\times x");
+    assert_eq!(lexer.next_token(), Token::Num(Num::new(2)));
+    assert_eq!(lexer.next_token(), Token::Infix(Infix::Mul));
+    assert_eq!(lexer.next_token(), Token::Num(Num::new(3)));
+    assert_eq!(lexer.next_token(), Token::Infix(Infix::Mul));
+    assert_eq!(lexer.next_token(), Token::Sym("x"));
+
+    let mut lexer = Lexer::new("123456789 0123456789 0+1");
+    assert_eq!(lexer.next_token(), Token::BigNum("123456789 0123456789 0"));
+    assert_eq!(lexer.next_token(), Token::Infix(Infix::Add));
 }
@@ -0,0 +1,495 @@
+use super::precedence::{absorbs, Printer, ATOM, MUL};
+use super::serializer::{is_root, reciprocal, split_fractions, LatexStyle};
+use crate::{Cmp, Const, Delimiter, Direction, Expr, FuncName, Num, SumOp, Sym};
+
+const NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+const MINUS: &str = "<mo>-</mo>";
+/// Invisible times and function application
+const TIMES: &str = "<mo>&#x2062;</mo>";
+const APPLY: &str = "<mo>&#x2061;</mo>";
+
+/// Presentation MathML of expr parenthesized by the same precedence as serialize
+pub fn to_mathml(expr: &Expr) -> String {
+    format!("<math xmlns=\"{}\">{}</math>", NAMESPACE, element(expr))
+}
+
+/// Content MathML of expr
+pub fn to_content_mathml(expr: &Expr) -> String {
+    format!("<math xmlns=\"{}\">{}</math>", NAMESPACE, content(expr))
+}
+
+/// Greek letter commands such as \alpha->α otherwise the symbol itself
+pub(super) fn unicode(symbol: &str) -> &str {
+    match symbol {
+        "\\alpha" => "α",
+        "\\beta" => "β",
+        "\\gamma" => "γ",
+        "\\delta" => "δ",
+        "\\epsilon" | "\\varepsilon" => "ε",
+        "\\zeta" => "ζ",
+        "\\eta" => "η",
+        "\\theta" => "θ",
+        "\\iota" => "ι",
+        "\\kappa" => "κ",
+        "\\lambda" => "λ",
+        "\\mu" => "μ",
+        "\\nu" => "ν",
+        "\\xi" => "ξ",
+        "\\rho" => "ρ",
+        "\\sigma" => "σ",
+        "\\tau" => "τ",
+        "\\upsilon" => "υ",
+        "\\phi" | "\\varphi" => "φ",
+        "\\chi" => "χ",
+        "\\psi" => "ψ",
+        "\\omega" => "ω",
+        "\\Gamma" => "Γ",
+        "\\Delta" => "Δ",
+        "\\Theta" => "Θ",
+        "\\Lambda" => "Λ",
+        "\\Xi" => "Ξ",
+        "\\Pi" => "Π",
+        "\\Sigma" => "Σ",
+        "\\Phi" => "Φ",
+        "\\Psi" => "Ψ",
+        "\\Omega" => "Ω",
+        "\\pi" => "π",
+        "\\infty" => "∞",
+        symbol => symbol.trim_start_matches('\\'),
+    }
+}
+
+/// Name of func without the LaTeX command syntax such as sin and sgn
+pub(super) fn func_name(name: FuncName) -> &'static str {
+    match name {
+        FuncName::Sign => "sgn",
+        name => name.command().trim_start_matches('\\'),
+    }
+}
+
+fn element(expr: &Expr) -> String {
+    match &row(expr)[..] {
+        [item] => item.clone(),
+        items => format!("<mrow>{}</mrow>", items.concat()),
+    }
+}
+
+fn fenced(inner: &str, open: &str, close: &str) -> String {
+    format!("<mrow><mo>{}</mo>{}<mo>{}</mo></mrow>", open, inner, close)
+}
+
+/// MathML elements of exprs parenthesized by the same precedence as serialize
+struct MathMl;
+
+impl<'a> Printer<Expr<'a>> for MathMl {
+    type Output = String;
+
+    fn print(&self, expr: &Expr<'a>) -> (String, u8) {
+        (element(expr), LatexStyle::default().binding(expr))
+    }
+
+    fn parens(&self, element: String) -> String {
+        fenced(&element, "(", ")")
+    }
+}
+
+fn tag(name: &str, children: &[String]) -> String {
+    format!("<{0}>{1}</{0}>", name, children.concat())
+}
+
+fn sym(x: &Sym) -> String {
+    let base = format!("<mi>{}</mi>", unicode(x.symbol()));
+    match x.sub() {
+        "" => base,
+        sub if sub.chars().all(|c| c.is_ascii_digit()) => {
+            format!("<msub>{}<mn>{}</mn></msub>", base, sub)
+        }
+        sub => format!("<msub>{}<mi>{}</mi></msub>", base, unicode(sub)),
+    }
+}
+
+/// Children of the mrow of expr where a leading minus is a separate <mo>
+fn row(expr: &Expr) -> Vec<String> {
+    match expr {
+        Expr::Add(add) => {
+            let mut items = vec![];
+            add.exprs.iter().enumerate().for_each(|(i, term)| {
+                let term = row(term);
+                if i > 0 && term[0] != MINUS {
+                    items.push(String::from("<mo>+</mo>"));
+                }
+                items.extend(term);
+            });
+            items
+        }
+        Expr::Mul(mul)
+            if mul.exprs.iter().any(|e| reciprocal(e).is_some())
+                && expr.detach_coeff().0.is_real() =>
+        {
+            let (negative, fractions) = split_fractions(expr);
+            let mut items: Vec<String> =
+                negative.then(|| String::from(MINUS)).into_iter().collect();
+            fractions.iter().enumerate().for_each(|(i, (num, den))| {
+                if i > 0 {
+                    items.push(String::from(TIMES));
+                }
+                items.push(match den {
+                    Expr::Num(n) if n.is_one() => MathMl.wrap(num, MUL),
+                    den => tag("mfrac", &[element(num), element(den)]),
+                });
+            });
+            items
+        }
+        Expr::Mul(mul) => {
+            let mut items: Vec<String> = vec![];
+            mul.exprs.iter().enumerate().for_each(|(i, expr)| {
+                let factor = match expr {
+                    Expr::Num(x) if i == 0 && *x == Num::new(-1) => vec![String::from(MINUS)],
+                    Expr::Num(x) if i == 0 && x.is_real() => row(expr),
+                    expr if absorbs(mul, i) => vec![fenced(&element(expr), "(", ")")],
+                    expr => vec![MathMl.wrap(expr, MUL)],
+                };
+                let digits = items.last().is_some_and(|last| last.starts_with("<mn>"))
+                    && factor[0].trim_start_matches("<msup>").starts_with("<mn>");
+                match &items[..] {
+                    [] => {}
+                    [minus] if minus == MINUS => {}
+                    _ if digits => items.push(String::from("<mo>&#x22C5;</mo>")),
+                    _ => items.push(String::from(TIMES)),
+                }
+                items.extend(factor);
+            });
+            items
+        }
+        Expr::Pow(_) if reciprocal(expr).is_some() => {
+            let den = element(&reciprocal(expr).unwrap());
+            vec![tag("mfrac", &[String::from("<mn>1</mn>"), den])]
+        }
+        Expr::Pow(pow) if is_root(expr) => {
+            let (p, q) = match *pow.pow {
+                Expr::Num(n) => (n.num, n.den),
+                _ => unreachable!(),
+            };
+            let root = match q {
+                2 => tag("msqrt", &[element(&pow.body)]),
+                q => tag("mroot", &[element(&pow.body), format!("<mn>{}</mn>", q)]),
+            };
+            match p {
+                1 => vec![root],
+                p => vec![tag("msup", &[root, format!("<mn>{}</mn>", p)])],
+            }
+        }
+        Expr::Pow(pow) => match &*pow.body {
+            Expr::Func(func) if !matches!(func.name, FuncName::Abs | FuncName::Conj) => {
+                let name = format!("<mi>{}</mi>", func_name(func.name));
+                vec![
+                    tag("msup", &[name, element(&pow.pow)]),
+                    String::from(APPLY),
+                    fenced(&element(&func.arg), "(", ")"),
+                ]
+            }
+            body => vec![tag("msup", &[MathMl.wrap(body, ATOM), element(&pow.pow)])],
+        },
+        Expr::Func(func) if func.name == FuncName::Abs => {
+            vec![fenced(&element(&func.arg), "|", "|")]
+        }
+        Expr::Func(func) if func.name == FuncName::Conj => vec![format!(
+            "<mover accent=\"true\">{}<mo>&#x00AF;</mo></mover>",
+            element(&func.arg)
+        )],
+        Expr::Func(func) => vec![
+            format!("<mi>{}</mi>", func_name(func.name)),
+            String::from(APPLY),
+            fenced(&element(&func.arg), "(", ")"),
+        ],
+        Expr::Integral(integral) => {
+            let int = String::from("<mo>&#x222B;</mo>");
+            let int = match &integral.bounds {
+                Some(b) => tag("msubsup", &[int, element(&b.0), element(&b.1)]),
+                None => int,
+            };
+            let d = format!("<mi>d</mi>{}", sym(&integral.var));
+            vec![int, element(&integral.integrand), d]
+        }
+        Expr::Limit(limit) => {
+            let point = match limit.direction {
+                Direction::Both => element(&limit.point),
+                Direction::Plus => tag("msup", &[element(&limit.point), "<mo>+</mo>".into()]),
+                Direction::Minus => tag("msup", &[element(&limit.point), MINUS.into()]),
+            };
+            let under = tag(
+                "mrow",
+                &[sym(&limit.var), "<mo>&#x2192;</mo>".into(), point],
+            );
+            vec![
+                tag("munder", &["<mo>lim</mo>".into(), under]),
+                MathMl.wrap(&limit.expr, MUL),
+            ]
+        }
+        Expr::Sum(sum) => {
+            let op = match sum.op {
+                SumOp::Sum => "<mo>&#x2211;</mo>",
+                SumOp::Product => "<mo>&#x220F;</mo>",
+            };
+            let lower = tag(
+                "mrow",
+                &[sym(&sum.var), "<mo>=</mo>".into(), element(&sum.lower)],
+            );
+            vec![
+                tag("munderover", &[op.into(), lower, element(&sum.upper)]),
+                MathMl.wrap(&sum.body, MUL),
+            ]
+        }
+        Expr::Piecewise(piecewise) => {
+            let rows: Vec<String> = piecewise
+                .pieces
+                .iter()
+                .map(|(value, cond)| {
+                    let cond = match cond {
+                        Some(c) => {
+                            let cmp = match c.cmp {
+                                Cmp::Lt => "&lt;",
+                                Cmp::Le => "&#x2264;",
+                                Cmp::Gt => "&gt;",
+                                Cmp::Ge => "&#x2265;",
+                                Cmp::Eq => "=",
+                                Cmp::Ne => "&#x2260;",
+                            };
+                            let cmp = format!("<mo>{}</mo>", cmp);
+                            tag("mrow", &[element(&c.lhs), cmp, element(&c.rhs)])
+                        }
+                        None => String::from("<mtext>otherwise</mtext>"),
+                    };
+                    tag("mtr", &[tag("mtd", &[element(value)]), tag("mtd", &[cond])])
+                })
+                .collect();
+            vec![tag("mrow", &["<mo>{</mo>".into(), tag("mtable", &rows)])]
+        }
+        Expr::Matrix(matrix) => {
+            let rows: Vec<String> = matrix
+                .rows
+                .iter()
+                .map(|row| {
+                    let cells: Vec<String> =
+                        row.iter().map(|e| tag("mtd", &[element(e)])).collect();
+                    tag("mtr", &cells)
+                })
+                .collect();
+            let (open, close) = match matrix.delimiter {
+                Delimiter::Paren => ("(", ")"),
+                Delimiter::Bracket => ("[", "]"),
+                Delimiter::Vert => ("|", "|"),
+            };
+            vec![fenced(&tag("mtable", &rows), open, close)]
+        }
+        Expr::Sym(x) => vec![sym(x)],
+        Expr::Const(c) => vec![format!("<mi>{}</mi>", unicode(c.command()))],
+        Expr::Num(x) if !x.is_real() => {
+            let mut items = match x.re() {
+                re if re.is_zero() => vec![],
+                re => row(&Expr::Num(re)),
+            };
+            let im = match x.im() {
+                im if im.is_one() => vec![],
+                im if im == Num::new(-1) => vec![String::from(MINUS)],
+                im => {
+                    let mut im = row(&Expr::Num(im));
+                    im.push(String::from(TIMES));
+                    im
+                }
+            };
+            if !items.is_empty() && im.first().map(|s| &s[..]) != Some(MINUS) {
+                items.push(String::from("<mo>+</mo>"));
+            }
+            items.extend(im);
+            items.push(String::from("<mi>i</mi>"));
+            items
+        }
+        Expr::Num(x) => {
+            let abs = match x.is_integer() {
+                true => format!("<mn>{}</mn>", x.num.abs()),
+                false => format!("<mfrac><mn>{}</mn><mn>{}</mn></mfrac>", x.num.abs(), x.den),
+            };
+            match x.is_negative() {
+                true => vec![String::from(MINUS), abs],
+                false => vec![abs],
+            }
+        }
+    }
+}
+
+fn apply(op: &str, args: Vec<String>) -> String {
+    format!("<apply>{}{}</apply>", op, args.concat())
+}
+
+fn content(expr: &Expr) -> String {
+    match expr {
+        Expr::Add(add) => apply("<plus/>", add.exprs.iter().map(content).collect()),
+        Expr::Mul(mul) => apply("<times/>", mul.exprs.iter().map(content).collect()),
+        Expr::Pow(pow) => apply("<power/>", vec![content(&pow.body), content(&pow.pow)]),
+        Expr::Func(func) => {
+            let op = match func.name {
+                FuncName::Sign => String::from("<csymbol>sgn</csymbol>"),
+                FuncName::Re => String::from("<real/>"),
+                FuncName::Im => String::from("<imaginary/>"),
+                FuncName::Conj => String::from("<conjugate/>"),
+                name => format!("<{}/>", func_name(name)),
+            };
+            apply(&op, vec![content(&func.arg)])
+        }
+        Expr::Integral(integral) => {
+            let mut args = vec![bvar(&integral.var)];
+            if let Some(bounds) = &integral.bounds {
+                args.push(tag("lowlimit", &[content(&bounds.0)]));
+                args.push(tag("uplimit", &[content(&bounds.1)]));
+            }
+            args.push(content(&integral.integrand));
+            apply("<int/>", args)
+        }
+        Expr::Limit(limit) => {
+            let point = content(&limit.point);
+            let approach = match limit.direction {
+                Direction::Both => tag("lowlimit", &[point]),
+                direction => {
+                    let side = match direction {
+                        Direction::Plus => "above",
+                        _ => "below",
+                    };
+                    let op = format!("<tendsto type=\"{}\"/>", side);
+                    let var = content(&Expr::Sym(limit.var));
+                    tag("condition", &[apply(&op, vec![var, point])])
+                }
+            };
+            let args = vec![bvar(&limit.var), approach, content(&limit.expr)];
+            apply("<limit/>", args)
+        }
+        Expr::Sum(sum) => {
+            let op = match sum.op {
+                SumOp::Sum => "<sum/>",
+                SumOp::Product => "<product/>",
+            };
+            let args = vec![
+                bvar(&sum.var),
+                tag("lowlimit", &[content(&sum.lower)]),
+                tag("uplimit", &[content(&sum.upper)]),
+                content(&sum.body),
+            ];
+            apply(op, args)
+        }
+        Expr::Piecewise(piecewise) => {
+            let pieces: Vec<String> = piecewise
+                .pieces
+                .iter()
+                .map(|(value, cond)| match cond {
+                    Some(c) => {
+                        let cmp = match c.cmp {
+                            Cmp::Lt => "<lt/>",
+                            Cmp::Le => "<leq/>",
+                            Cmp::Gt => "<gt/>",
+                            Cmp::Ge => "<geq/>",
+                            Cmp::Eq => "<eq/>",
+                            Cmp::Ne => "<neq/>",
+                        };
+                        let cond = apply(cmp, vec![content(&c.lhs), content(&c.rhs)]);
+                        tag("piece", &[content(value), cond])
+                    }
+                    None => tag("otherwise", &[content(value)]),
+                })
+                .collect();
+            tag("piecewise", &pieces)
+        }
+        Expr::Matrix(matrix) => {
+            let rows: Vec<String> = matrix
+                .rows
+                .iter()
+                .map(|row| tag("matrixrow", &row.iter().map(content).collect::<Vec<_>>()))
+                .collect();
+            tag("matrix", &rows)
+        }
+        Expr::Sym(x) if x.sub().is_empty() => format!("<ci>{}</ci>", unicode(x.symbol())),
+        Expr::Sym(x) => format!("<ci>{}</ci>", sym(x)),
+        Expr::Const(Const::Pi) => String::from("<pi/>"),
+        Expr::Const(Const::E) => String::from("<exponentiale/>"),
+        Expr::Const(Const::Infinity) => String::from("<infinity/>"),
+        Expr::Num(x) if !x.is_real() => {
+            let im = match x.im() {
+                im if im.is_one() => String::from("<imaginaryi/>"),
+                im => apply(
+                    "<times/>",
+                    vec![content(&Expr::Num(im)), String::from("<imaginaryi/>")],
+                ),
+            };
+            match x.re() {
+                re if re.is_zero() => im,
+                re => apply("<plus/>", vec![content(&Expr::Num(re)), im]),
+            }
+        }
+        Expr::Num(x) if x.is_integer() => format!("<cn type=\"integer\">{}</cn>", x.num),
+        Expr::Num(x) => format!("<cn type=\"rational\">{}<sep/>{}</cn>", x.num, x.den),
+    }
+}
+
+fn bvar(x: &Sym) -> String {
+    tag("bvar", &[content(&Expr::Sym(*x))])
+}
+
+#[test]
+fn test_mathml() {
+    use super::latex_to_expr;
+    let mathml = |latex: &str| {
+        let math = to_mathml(&latex_to_expr(latex).collect());
+        let start = math.find('>').unwrap() + 1;
+        math[start..math.len() - "</math>".len()].to_string()
+    };
+    let tests = [
+        ["x^{2}+1", "<mrow><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><mn>1</mn></mrow>"],
+        ["x-2y", "<mrow><mi>x</mi><mo>-</mo><mn>2</mn><mo>&#x2062;</mo><mi>y</mi></mrow>"],
+        [
+            "(x+y)^{2}",
+            "<msup><mrow><mo>(</mo><mrow><mi>x</mi><mo>+</mo><mi>y</mi></mrow><mo>)</mo></mrow><mn>2</mn></msup>",
+        ],
+        [
+            "-\\frac{\\alpha}{2y}",
+            "<mrow><mo>-</mo><mfrac><mi>α</mi><mrow><mn>2</mn><mo>&#x2062;</mo><mi>y</mi></mrow></mfrac></mrow>",
+        ],
+        ["\\sqrt{x_{1}}", "<msqrt><msub><mi>x</mi><mn>1</mn></msub></msqrt>"],
+        [
+            "\\sin^{2}x",
+            "<mrow><msup><mi>sin</mi><mn>2</mn></msup><mo>&#x2061;</mo><mrow><mo>(</mo><mi>x</mi><mo>)</mo></mrow></mrow>",
+        ],
+        ["|x|", "<mrow><mo>|</mo><mi>x</mi><mo>|</mo></mrow>"],
+        ["2-3i", "<mrow><mn>2</mn><mo>-</mo><mn>3</mn><mo>&#x2062;</mo><mi>i</mi></mrow>"],
+        [
+            "2\\cdot 3^{x}",
+            "<mrow><mn>2</mn><mo>&#x22C5;</mo><msup><mn>3</mn><mi>x</mi></msup></mrow>",
+        ],
+        [
+            "\\frac{x}{y}z",
+            "<mrow><mfrac><mi>x</mi><mi>y</mi></mfrac><mo>&#x2062;</mo><mi>z</mi></mrow>",
+        ],
+    ];
+    tests
+        .iter()
+        .for_each(|test| assert_eq!(mathml(test[0]), test[1]));
+    let cases = mathml("\\begin{cases}1&x<0\\\\0&\\text{otherwise}\\end{cases}");
+    assert!(cases.contains("<mo>&lt;</mo>") && cases.contains("<mtext>otherwise</mtext>"));
+    assert!(to_mathml(&Expr::Num(Num::new(1))).starts_with("<math xmlns="));
+
+    let content = |latex: &str| {
+        let math = to_content_mathml(&latex_to_expr(latex).collect());
+        let start = math.find('>').unwrap() + 1;
+        math[start..math.len() - "</math>".len()].to_string()
+    };
+    assert_eq!(
+        content("x^{2}+\\frac{1}{2}"),
+        "<apply><plus/><apply><power/><ci>x</ci><cn type=\"integer\">2</cn></apply><cn type=\"rational\">1<sep/>2</cn></apply>"
+    );
+    assert_eq!(
+        content("\\sin(\\pi x)"),
+        "<apply><sin/><apply><times/><pi/><ci>x</ci></apply></apply>"
+    );
+    let int = to_content_mathml(&latex_to_expr("\\int_{0}^{1}x dx"));
+    assert!(
+        int.contains("<apply><int/><bvar><ci>x</ci></bvar><lowlimit><cn type=\"integer\">0</cn>")
+    );
+}
@@ -1,45 +1,114 @@
 pub mod lexer;
-use crate::{Expr, Sym};
+use crate::expr::func::call;
+use crate::{
+    Add, Assumptions, Cmp, Condition, Const, Delimiter, Direction, Expr, Func, FuncName, Integral,
+    Limit, Matrix, Mul, Num, Piecewise, Pow, Property, Relation, Sum, SumOp, Sym,
+};
 use lexer::{Lexer, Token};
+mod ascii;
+mod json;
+mod mathml;
+pub(crate) mod precedence;
+mod pretty;
 mod serializer;
-pub use serializer::serialize;
+pub use ascii::{ascii_to_expr, to_ascii};
+pub use json::{json_to_expr, to_json, JSON_VERSION};
+pub use mathml::{to_content_mathml, to_mathml};
+pub use pretty::to_pretty;
+pub use serializer::{serialize, serialize_series, FracStyle, LatexStyle, MulSign, TermOrder};
 
 use self::lexer::Infix;
 pub struct Parser<'a> {
     pub lexer: Lexer<'a>,
+    /// Depth of integrands being parsed, where d followed by a symbol is the differential
+    integrals: usize,
+    /// Symbol parsed as the imaginary unit unless subscripted or bound as an index, i by default
+    imaginary_unit: Option<&'a str>,
+    /// First invalid construct which was replaced by an empty symbol to keep parsing
+    pub error: Option<&'static str>,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(input: &'a str) -> Self {
         let lexer = Lexer::new(input);
-        Parser { lexer }
+        Parser {
+            lexer,
+            integrals: 0,
+            imaginary_unit: Some("i"),
+            error: None,
+        }
+    }
+
+    /// Record the first error and return an empty symbol in place of the invalid construct
+    fn fail(&mut self, err: &'static str) -> Expr<'a> {
+        self.error.get_or_insert(err);
+        Expr::Sym(Sym::new(""))
+    }
+
+    /// Use unit such as j as the imaginary unit, or None to parse every symbol as Sym
+    pub fn set_imaginary_unit(&mut self, unit: Option<&'a str>) {
+        self.imaginary_unit = unit;
     }
     pub fn parse(&mut self, end_token: &Token) -> Expr<'a> {
-        let first_token = self.lexer.next_token();
+        self.parse_until(std::slice::from_ref(end_token)).0
+    }
+
+    /// Parse until one of end_tokens and return it with the expr
+    /// Empty input gives Sym("")
+    pub fn parse_until(&mut self, end_tokens: &[Token]) -> (Expr<'a>, Token<'a>) {
         let mut infix_stack: Vec<Infix> = vec![];
-        let mut expr_stack: Vec<Expr> = vec![match first_token {
-            Token::Sym(slice) => Expr::Sym(Sym::new(slice)),
-            Token::Num(num) => Expr::Num(num),
-            Token::Eof => return Expr::Sym(Sym::new("")),
-            _ => panic!("Unexpected first token"),
-        }];
-        loop {
+        let mut expr_stack: Vec<Expr> = vec![];
+        let end_token = loop {
             let next_token = self.lexer.next_token();
             match &next_token {
-                token if *token == *end_token => {
+                // | opens an absolute value where an operand is expected or outside of one
+                Token::Bar
+                    if expr_stack.len() == infix_stack.len()
+                        || !end_tokens.contains(&Token::Bar) =>
+                {
+                    let operand = self.parse_operand(next_token);
+                    self.push_operand(&mut expr_stack, &mut infix_stack, operand);
+                }
+                token
+                    if expr_stack.is_empty()
+                        && (*token == Token::Eof || end_tokens.contains(token)) =>
+                {
+                    return (Expr::Sym(Sym::new("")), next_token)
+                }
+                token if end_tokens.contains(token) => {
                     for _ in 0..infix_stack.len() {
                         self.operate_infix(&mut expr_stack, &mut infix_stack);
                     }
-                    break;
+                    break next_token;
                 }
                 Token::Infix(Infix::Add) => {
-                    while let Some(Infix::Circumflex) | Some(Infix::Mul) | Some(Infix::Add) =
+                    while let Some(Infix::Circumflex | Infix::Mul | Infix::Div | Infix::Add) =
                         infix_stack.last()
                     {
                         self.operate_infix(&mut expr_stack, &mut infix_stack);
                     }
                     infix_stack.push(Infix::Add);
                 }
+                Token::Infix(Infix::Sub) => {
+                    // x-y->x+(-1)*y while leading minus is -x->(-1)*x
+                    if !expr_stack.is_empty() {
+                        while let Some(Infix::Circumflex | Infix::Mul | Infix::Div | Infix::Add) =
+                            infix_stack.last()
+                        {
+                            self.operate_infix(&mut expr_stack, &mut infix_stack);
+                        }
+                        infix_stack.push(Infix::Add);
+                    }
+                    expr_stack.push(Expr::Num(Num::new(-1)));
+                    infix_stack.push(Infix::Mul);
+                }
+                Token::Infix(infix @ (Infix::Mul | Infix::Div)) => {
+                    while let Some(Infix::Circumflex | Infix::Mul | Infix::Div) = infix_stack.last()
+                    {
+                        self.operate_infix(&mut expr_stack, &mut infix_stack);
+                    }
+                    infix_stack.push(infix.clone());
+                }
                 Token::Infix(Infix::Circumflex) => {
                     infix_stack.push(Infix::Circumflex);
                     expr_stack.push(self.parse_arg());
@@ -50,53 +119,551 @@ impl<'a> Parser<'a> {
                     }
                     _ => panic!("Underscore must come after symbol"),
                 },
-                Token::Sym(sym) => {
-                    if expr_stack.len() == infix_stack.len() {
-                        expr_stack.push(Expr::Sym(Sym::new(sym)))
-                    } else {
-                        // Case of implicit mul
-                        while let Some(Infix::Circumflex) | Some(Infix::Mul) = infix_stack.last() {
-                            self.operate_infix(&mut expr_stack, &mut infix_stack);
-                        }
-                        expr_stack.push(Expr::Sym(Sym::new(sym)));
-                        infix_stack.push(Infix::Mul);
-                    }
+                Token::Sym(_)
+                | Token::Num(_)
+                | Token::BigNum(_)
+                | Token::LParen
+                | Token::LBar
+                | Token::Begin(_) => {
+                    let operand = self.parse_operand(next_token);
+                    self.push_operand(&mut expr_stack, &mut infix_stack, operand);
                 }
-                Token::Num(num) => match infix_stack.last().unwrap() {
-                    Infix::Add => expr_stack.push(Expr::Num(*num)),
-                    _ => panic!("Number comes first or after +"),
-                },
+                Token::Eof => panic!("Unexpected end of input"),
                 _ => unimplemented!(),
             };
-        }
+        };
         match expr_stack.pop() {
-            Some(expr) if expr_stack.is_empty() => expr,
+            Some(expr) if expr_stack.is_empty() => (expr, end_token),
             _ => panic!("expr_stack must contain only one expr at last"),
         }
     }
 
+    fn push_operand(
+        &self,
+        expr_stack: &mut Vec<Expr<'a>>,
+        infix_stack: &mut Vec<Infix>,
+        operand: Expr<'a>,
+    ) {
+        if expr_stack.len() != infix_stack.len() {
+            // Case of implicit mul
+            while let Some(Infix::Circumflex | Infix::Mul | Infix::Div) = infix_stack.last() {
+                self.operate_infix(expr_stack, infix_stack);
+            }
+            infix_stack.push(Infix::Mul);
+        }
+        expr_stack.push(operand);
+    }
+
+    /// Sym, Num, \frac{x}{y}, function, integral, matrix environment, |x|
+    /// or expr wrapped by parentheses
+    fn parse_operand(&mut self, token: Token<'a>) -> Expr<'a> {
+        match token {
+            Token::Num(num) => Expr::Num(num),
+            Token::BigNum(digits) => big_num(digits),
+            Token::Begin("cases") => self.parse_cases(),
+            Token::Begin(env) => self.parse_env(env),
+            Token::LParen => self.parse(&Token::RParen),
+            Token::Bar => call(FuncName::Abs, self.parse(&Token::Bar)),
+            Token::LBar => call(FuncName::Abs, self.parse(&Token::RBar)),
+            Token::Sym("\\operatorname") => {
+                self.lexer.skip_whitespace();
+                let name = self.lexer.arg_to_string();
+                match FuncName::from_command(&format!("\\{}", name)) {
+                    Some(name) => self.parse_func(name),
+                    None => self.fail("Unknown operator name"),
+                }
+            }
+            Token::Sym("\\frac") => {
+                let num = self.parse_arg();
+                frac(num, self.parse_arg())
+            }
+            Token::Sym("\\sqrt") => self.parse_sqrt(),
+            Token::Sym("\\int") => self.parse_integral(),
+            Token::Sym("\\lim") => self.parse_limit(),
+            Token::Sym("\\sum") => self.parse_sum(SumOp::Sum),
+            Token::Sym("\\prod") => self.parse_sum(SumOp::Product),
+            Token::Sym(command) if FuncName::from_command(command).is_some() => {
+                self.parse_func(FuncName::from_command(command).unwrap())
+            }
+            Token::Sym(sym) => self.parse_sym(sym),
+            _ => panic!("Unexpected operand"),
+        }
+    }
+
+    /// \sqrt{x}->Pow(x,1/2) and \sqrt[n]{x}->Pow(x,1/n)
+    fn parse_sqrt(&mut self) -> Expr<'a> {
+        let index = match self.peek() {
+            Token::LBracket => {
+                self.lexer.next_token();
+                self.parse(&Token::RBracket)
+            }
+            _ => Expr::Num(Num::new(2)),
+        };
+        let radicand = self.parse_arg();
+        match (&index, &radicand) {
+            (Expr::Num(n), _) if n.is_zero() => self.fail("Index of root must not be 0"),
+            (Expr::Sym(x), _) | (_, Expr::Sym(x)) if x.symbol().is_empty() => {
+                self.fail("Expected argument")
+            }
+            _ => Expr::Pow(Pow::new(radicand, Expr::Num(Num::new(1)) / index)),
+        }
+    }
+
+    /// \sin^{n}x->Pow(Func(sin,x),n) and \log_{b}x->\frac{\ln x}{\ln b}
+    fn parse_func(&mut self, name: FuncName) -> Expr<'a> {
+        let base = match (name, self.peek()) {
+            (FuncName::Ln, Token::Infix(Infix::Underscore)) => {
+                self.lexer.next_token();
+                Some(self.parse_arg())
+            }
+            _ => None,
+        };
+        let pow = match self.peek() {
+            Token::Infix(Infix::Circumflex) => {
+                self.lexer.next_token();
+                Some(self.parse_arg())
+            }
+            _ => None,
+        };
+        let func = match base {
+            Some(base) => log(base, self.parse_func_arg()),
+            None => Expr::Func(Func::new(name, self.parse_func_arg())),
+        };
+        match pow {
+            Some(pow) => Expr::Pow(Pow::new(func, pow)),
+            None => func,
+        }
+    }
+
+    /// Argument of \sin\left(x\right) and \sin{x}, or implicit product such as \sin 2x^{2}y
+    /// or \ln\frac{x}{y}z which ends before operators, commands taking arguments and the differential
+    fn parse_func_arg(&mut self) -> Expr<'a> {
+        let mut token = match self.lexer.next_token() {
+            Token::LParen => return self.parse(&Token::RParen),
+            Token::LCurlyBrace => return self.parse(&Token::RCurlyBrace),
+            token => token,
+        };
+        let mut factors = vec![];
+        loop {
+            let mut factor = match token {
+                Token::Num(num) => Expr::Num(num),
+                Token::BigNum(digits) => big_num(digits),
+                Token::Sym("\\frac" | "\\sqrt") | Token::Bar | Token::LBar
+                    if factors.is_empty() =>
+                {
+                    self.parse_operand(token)
+                }
+                Token::Sym(sym) => self.parse_sym(sym),
+                _ => panic!("Unexpected function argument"),
+            };
+            loop {
+                factor = match (self.peek(), factor) {
+                    (Token::Infix(Infix::Underscore), Expr::Sym(mut sym)) => {
+                        self.lexer.next_token();
+                        Expr::Sym(sym.set_sub(self.lexer.arg_to_string()))
+                    }
+                    (Token::Infix(Infix::Circumflex), factor) => {
+                        self.lexer.next_token();
+                        factor ^ self.parse_arg()
+                    }
+                    (_, factor) => break factors.push(factor),
+                }
+            }
+            token = match self.peek() {
+                Token::Sym("d") if self.integrals > 0 => break,
+                Token::Sym(sym) if !Parser::takes_args(sym) => self.lexer.next_token(),
+                Token::Num(_) | Token::BigNum(_) => self.lexer.next_token(),
+                _ => break,
+            };
+        }
+        Mul::new(factors).to_expr()
+    }
+
+    /// Commands which are not symbols
+    fn takes_args(command: &str) -> bool {
+        FuncName::from_command(command).is_some()
+            || matches!(
+                command,
+                "\\frac" | "\\sqrt" | "\\int" | "\\lim" | "\\sum" | "\\prod"
+            )
+    }
+
+    /// \int_{a}^{b}f dx where the integrand ends at the differential
+    fn parse_integral(&mut self) -> Expr<'a> {
+        let (mut lower, mut upper) = (None, None);
+        loop {
+            match self.peek() {
+                Token::Infix(Infix::Underscore) => {
+                    self.lexer.next_token();
+                    lower = Some(self.parse_arg());
+                }
+                Token::Infix(Infix::Circumflex) => {
+                    self.lexer.next_token();
+                    upper = Some(self.parse_arg());
+                }
+                _ => break,
+            }
+        }
+        self.integrals += 1;
+        let (integrand, end) = self.parse_until(&[Token::Sym("d")]);
+        self.integrals -= 1;
+        let var = match (end, self.lexer.next_token()) {
+            (Token::Sym("d"), Token::Sym(var)) => Sym::new(var),
+            _ => panic!("Expected differential of integral"),
+        };
+        let integrand = match integrand {
+            Expr::Sym(sym) if sym == Sym::new("") => Expr::Num(Num::new(1)),
+            integrand => integrand,
+        };
+        let bounds = match (lower, upper) {
+            (Some(lower), Some(upper)) => Some((lower, upper)),
+            (None, None) => None,
+            _ => panic!("Definite integral needs both bounds"),
+        };
+        Expr::Integral(Integral::new(integrand, var, bounds))
+    }
+
+    /// \lim_{x\to a}f where a may be followed by ^{+} or ^{-} and f is the following product
+    fn parse_limit(&mut self) -> Expr<'a> {
+        let (Token::Infix(Infix::Underscore), Token::LCurlyBrace) =
+            (self.lexer.next_token(), self.lexer.next_token())
+        else {
+            panic!("Expected subscript of limit");
+        };
+        let var = match (self.lexer.next_token(), self.lexer.next_token()) {
+            (Token::Sym(var), Token::Sym("\\to" | "\\rightarrow")) => Sym::new(var),
+            _ => panic!("Expected x\\to a in subscript of limit"),
+        };
+        let ends = [Token::RCurlyBrace, Token::Infix(Infix::Circumflex)];
+        let (point, end) = self.parse_until(&ends);
+        let direction = match end {
+            Token::RCurlyBrace => Direction::Both,
+            _ => {
+                let braced = self.peek() == Token::LCurlyBrace;
+                if braced {
+                    self.lexer.next_token();
+                }
+                let direction = match self.lexer.next_token() {
+                    Token::Infix(Infix::Add) => Direction::Plus,
+                    Token::Infix(Infix::Sub) => Direction::Minus,
+                    _ => panic!("Expected + or - as direction of limit"),
+                };
+                if braced {
+                    self.lexer.next_token();
+                }
+                match self.lexer.next_token() {
+                    Token::RCurlyBrace => direction,
+                    _ => panic!("Expected end of subscript of limit"),
+                }
+            }
+        };
+        Expr::Limit(Limit::new(self.parse_term(), var, point, direction))
+    }
+
+    /// \sum_{k=a}^{b}f where scripts may come in either order and f is the following product
+    fn parse_sum(&mut self, op: SumOp) -> Expr<'a> {
+        let (mut lower, mut upper) = (None, None);
+        for _ in 0..2 {
+            match self.lexer.next_token() {
+                Token::Infix(Infix::Underscore) => {
+                    let var = match (self.lexer.next_token(), self.lexer.next_token()) {
+                        (Token::LCurlyBrace, Token::Sym(var)) => Sym::new(var),
+                        _ => panic!("Expected index of {}", op.command()),
+                    };
+                    match self.lexer.next_token() {
+                        Token::Equal => lower = Some((var, self.parse(&Token::RCurlyBrace))),
+                        _ => panic!("Expected = in subscript of {}", op.command()),
+                    }
+                }
+                Token::Infix(Infix::Circumflex) => upper = Some(self.parse_arg()),
+                _ => panic!("Expected bounds of {}", op.command()),
+            }
+        }
+        match (lower, upper) {
+            (Some((var, lower)), Some(upper)) => {
+                // Index shadows the imaginary unit in the body such as \sum_{i=1}^{n}i
+                let unit = self.imaginary_unit;
+                if unit.map(Sym::new) == Some(var) {
+                    self.imaginary_unit = None;
+                }
+                let body = self.parse_term();
+                self.imaginary_unit = unit;
+                Expr::Sum(Sum::new(op, body, var, lower, upper))
+            }
+            _ => panic!("Expected bounds of {}", op.command()),
+        }
+    }
+
+    /// Implicit product of operands with their scripts which ends before operators
+    fn parse_term(&mut self) -> Expr<'a> {
+        let mut factors = vec![];
+        loop {
+            match self.peek() {
+                Token::Sym("d") if self.integrals > 0 => break,
+                Token::Sym(_)
+                | Token::Num(_)
+                | Token::BigNum(_)
+                | Token::LParen
+                | Token::LBar
+                | Token::Begin(_) => {}
+                // Later bar may close an enclosing absolute value
+                Token::Bar if factors.is_empty() => {}
+                _ => break,
+            }
+            let token = self.lexer.next_token();
+            let mut factor = self.parse_operand(token);
+            loop {
+                factor = match (self.peek(), factor) {
+                    (Token::Infix(Infix::Underscore), Expr::Sym(mut sym)) => {
+                        self.lexer.next_token();
+                        Expr::Sym(sym.set_sub(self.lexer.arg_to_string()))
+                    }
+                    (Token::Infix(Infix::Circumflex), factor) => {
+                        self.lexer.next_token();
+                        factor ^ self.parse_arg()
+                    }
+                    (_, factor) => break factors.push(factor),
+                }
+            }
+        }
+        if factors.is_empty() {
+            panic!("Expected operand");
+        }
+        Mul::new(factors).to_expr()
+    }
+
+    /// Rows of value&condition separated by \\ where the condition is a comparison
+    /// such as x\geq 0 or \text{otherwise}
+    fn parse_cases(&mut self) -> Expr<'a> {
+        let ends = [Token::Ampersand, Token::Newline, Token::End("cases")];
+        let mut pieces = vec![];
+        loop {
+            let (value, end) = self.parse_until(&ends);
+            match (&value, end) {
+                (Expr::Sym(sym), Token::End(_)) if *sym == Sym::new("") => break,
+                (_, Token::Ampersand) => {}
+                _ => panic!("Expected condition after &"),
+            }
+            let (cond, end) = self.parse_condition();
+            pieces.push((value, cond));
+            if end == Token::End("cases") {
+                break;
+            }
+        }
+        Expr::Piecewise(Piecewise::new(pieces))
+    }
+
+    /// lhs cmp rhs or \text{otherwise} as None followed by \\ or \end{cases}
+    fn parse_condition(&mut self) -> (Option<Condition<'a>>, Token<'a>) {
+        let ends = [Token::Newline, Token::End("cases")];
+        if self.peek() == Token::Sym("\\text") {
+            self.lexer.next_token();
+            self.lexer.skip_whitespace();
+            if self.lexer.arg_to_string().trim() != "otherwise" {
+                panic!("Expected \\text{{otherwise}}");
+            }
+            return match self.lexer.next_token() {
+                end if ends.contains(&end) => (None, end),
+                _ => panic!("Expected end of row"),
+            };
+        }
+        let cmps = [Token::Less, Token::Greater, Token::Equal]
+            .into_iter()
+            .chain(
+                [
+                    "\\lt", "\\gt", "\\leq", "\\le", "\\geq", "\\ge", "\\neq", "\\ne",
+                ]
+                .map(Token::Sym),
+            )
+            .collect::<Vec<_>>();
+        let (lhs, cmp) = self.parse_until(&cmps);
+        let cmp = match cmp {
+            Token::Less => Cmp::Lt,
+            Token::Greater => Cmp::Gt,
+            Token::Equal => Cmp::Eq,
+            Token::Sym(cmp) => Cmp::from_command(cmp).unwrap(),
+            _ => panic!("Expected comparison"),
+        };
+        let (rhs, end) = self.parse_until(&ends);
+        (Some(Condition::new(lhs, cmp, rhs)), end)
+    }
+
+    /// Cells are separated by & and rows by \\ where empty cell is 0
+    fn parse_env(&mut self, env: &'a str) -> Expr<'a> {
+        let delimiter = match Delimiter::from_env(env) {
+            Some(delimiter) => delimiter,
+            None => panic!("Unsupported environment {}", env),
+        };
+        let ends = [Token::Ampersand, Token::Newline, Token::End(env)];
+        let mut rows = vec![vec![]];
+        loop {
+            let (cell, end) = self.parse_until(&ends);
+            let row = rows.last_mut().unwrap();
+            match cell {
+                Expr::Sym(sym) if sym == Sym::new("") => {
+                    if !(row.is_empty() && end == Token::End(env)) {
+                        row.push(Expr::Num(Num::new(0)))
+                    }
+                }
+                cell => row.push(cell),
+            }
+            match end {
+                Token::Ampersand => {}
+                Token::Newline => rows.push(vec![]),
+                _ => break,
+            }
+        }
+        if rows.len() > 1 && rows.last().unwrap().is_empty() {
+            rows.pop();
+        }
+        if rows.iter().any(|row| row.len() != rows[0].len()) {
+            return self.fail("Every row of matrix must have the same length");
+        }
+        Expr::Matrix(Matrix::new(rows, delimiter))
+    }
+
     fn operate_infix(&self, expr_stack: &mut Vec<Expr>, infix_stack: &mut Vec<Infix>) {
         let right = expr_stack.pop().unwrap();
         let left = expr_stack.pop().unwrap();
         expr_stack.push(match infix_stack.pop().unwrap() {
             Infix::Mul => left * right,
+            Infix::Div => left / right,
             Infix::Add => left + right,
             Infix::Circumflex => left ^ right,
             _ => unimplemented!(),
         })
     }
 
+    /// {expr} or a single Num or Sym such as x^2
     fn parse_arg(&mut self) -> Expr<'a> {
         match self.lexer.next_token() {
             Token::LCurlyBrace => self.parse(&Token::RCurlyBrace),
-            _ => unimplemented!(),
+            Token::Num(num) => Expr::Num(num),
+            Token::BigNum(digits) => big_num(digits),
+            Token::Sym(sym) => self.parse_sym(sym),
+            _ => self.fail("Expected argument"),
+        }
+    }
+
+    /// Imaginary unit is Num and \pi, e and \infty are Const unless subscripted like e_{1}
+    fn parse_sym(&self, sym: &'a str) -> Expr<'a> {
+        match self.peek() {
+            Token::Infix(Infix::Underscore) => Expr::Sym(Sym::new(sym)),
+            _ if self.imaginary_unit == Some(sym) => Expr::Num(Num::i()),
+            _ if Const::from_command(sym).is_some() => {
+                Expr::Const(Const::from_command(sym).unwrap())
+            }
+            _ => Expr::Sym(Sym::new(sym)),
+        }
+    }
+
+    fn peek(&self) -> Token<'a> {
+        self.lexer.clone().next_token()
+    }
+}
+
+/// Digits beyond i64 as the sum of 18-digit chunks times powers of 10, left unevaluated
+/// by collect as their terms overflow
+fn big_num<'a>(digits: &str) -> Expr<'a> {
+    let digits: String = digits.chars().filter(char::is_ascii_digit).collect();
+    let terms = (0..digits.len().div_ceil(18))
+        .rev()
+        .map(|k| {
+            let end = digits.len() - 18 * k;
+            let chunk = digits[end.saturating_sub(18)..end].parse().unwrap();
+            Expr::Num(Num::new(chunk))
+                * (Expr::Num(Num::new(10)) ^ Expr::Num(Num::new(18 * k as i64)))
+        })
+        .collect();
+    Add::new(terms).to_expr()
+}
+
+/// num*den^{-1} where numbers of den divide the coefficient of num and other factors are
+/// inverted one by one, such as \frac{3x}{2y^{2}}->3/2*x*y^{-2} the way a product is collected
+fn frac<'a>(num: Expr<'a>, den: Expr<'a>) -> Expr<'a> {
+    let (mut coef, rest) = num.detach_coeff();
+    let mut factors = rest.exprs;
+    let den = match den {
+        Expr::Mul(mul) => mul.exprs,
+        den => vec![den],
+    };
+    for factor in den {
+        match factor {
+            Expr::Num(n) if coef.checked_div(n).is_some() => coef = coef / n,
+            Expr::Pow(pow) if matches!(*pow.pow, Expr::Num(_)) => {
+                factors.push(Expr::Pow(Pow::new(*pow.body, -*pow.pow)))
+            }
+            factor => factors.push(Expr::Pow(Pow::new(factor, Expr::Num(Num::new(-1))))),
         }
     }
+    Mul::new([vec![Expr::Num(coef)], factors].concat()).to_expr()
 }
 
-pub fn latex_to_expr(latex: &str) -> Expr {
+/// k for \log_{b}x with integers x=b^{k}, otherwise \frac{\ln x}{\ln b}
+fn log<'a>(base: Expr<'a>, arg: Expr<'a>) -> Expr<'a> {
+    if let (Expr::Num(b), Expr::Num(x)) = (&base, &arg) {
+        if b.is_integer() && b.num > 1 && x.is_integer() && x.num > 0 {
+            let (mut power, mut k) = (Num::new(1), 0);
+            while power.num < x.num {
+                match power.checked_mul(*b) {
+                    Some(next) => (power, k) = (next, k + 1),
+                    None => break,
+                }
+            }
+            if power == *x {
+                return Expr::Num(Num::new(k));
+            }
+        }
+    }
+    call(FuncName::Ln, arg) / call(FuncName::Ln, base)
+}
+
+/// Crash on invalid input, see try_latex_to_expr
+pub fn latex_to_expr(latex: &str) -> Expr<'_> {
+    try_latex_to_expr(latex).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Err on invalid input which the parser can recover from such as a ragged matrix
+pub fn try_latex_to_expr(latex: &str) -> Result<Expr<'_>, &'static str> {
     let mut parser = Parser::new(latex);
-    parser.parse(&Token::Eof)
+    let expr = parser.parse(&Token::Eof);
+    parser.error.map_or(Ok(expr), Err)
+}
+
+/// lhs=rhs->Relation(lhs,rhs), Err unless latex has exactly one =
+pub fn latex_to_relation(latex: &str) -> Result<Relation<'_>, &'static str> {
+    if latex.matches('=').count() != 1 {
+        return Err("Relation must have one =");
+    }
+    let mut parser = Parser::new(latex);
+    let lhs = parser.parse(&Token::Equal);
+    let rhs = parser.parse(&Token::Eof);
+    parser.error.map_or(Ok(Relation::new(lhs, rhs)), Err)
+}
+
+/// Comma separated facts such as x>0, y<0, z\neq 0, a\in\mathbb{R} and n\in\mathbb{Z}
+pub fn latex_to_assumptions(latex: &str) -> Result<Assumptions<'_>, &'static str> {
+    let mut assumptions = Assumptions::new();
+    for fact in latex.split(',').filter(|fact| !fact.trim().is_empty()) {
+        let (lhs, op, rhs) = ["\\neq", "\\ne", "\\in", ">", "<"]
+            .iter()
+            .find_map(|op| fact.split_once(op).map(|(lhs, rhs)| (lhs, *op, rhs)))
+            .ok_or("Assumption must be a relation")?;
+        let sym = match try_latex_to_expr(lhs.trim()) {
+            Ok(Expr::Sym(sym)) => sym,
+            _ => return Err("Assumption must be about a symbol"),
+        };
+        let property = match (op, &rhs.split_whitespace().collect::<String>()[..]) {
+            (">", "0") => Property::Positive,
+            ("<", "0") => Property::Negative,
+            ("\\neq" | "\\ne", "0") => Property::Nonzero,
+            ("\\in", "\\mathbb{R}") => Property::Real,
+            ("\\in", "\\mathbb{Z}") => Property::Integer,
+            _ => return Err("Unknown assumption"),
+        };
+        assumptions.assume(sym, property);
+    }
+    Ok(assumptions)
 }
 #[test]
 fn test_parser() {
@@ -118,8 +685,62 @@ fn test_parser() {
         ["2x_{2}^{2}", "2*x_{2}^{2}"],
         ["a_{b}^{c}+d_{e}^{f}", "a_{b}^{c}+d_{e}^{f}"],
         ["a_{b}^{c}+xd_{e}^{f}", "a_{b}^{c}+x*d_{e}^{f}"],
+        ["x-y", "x+-1*y"],
+        ["-2x+3", "-2*x+3"],
+        ["a-b^{2}c", "a+-1*b^{2}*c"],
+        ["2\\left(x+y\\right)", "2*(x+y)"],
+        ["(x+y)^{2}", "(x+y)^{2}"],
+        ["\\frac{1}{2}x", "1/2*x"],
+        ["\\frac{x}{y+1}", "x*(y+1)^{-1}"],
+        ["\\frac{1}{x}", "x^{-1}"],
+        ["\\frac{3a}{2bc^{2}}", "3/2*a*b^{-1}*c^{-2}"],
+        ["|x|+|y|", "|x|+|y|"],
+        ["||x|-1|", "||x|+-1|"],
+        ["2|x||y|", "2*|x|*|y|"],
+        ["\\left|x\\left|y\\right|\\right|", "|x*|y||"],
+        ["\\operatorname{sgn}(x)+\\sgn^{2}x", "sgn(x)+sgn(x)^{2}"],
+        ["\\operatorname{Re}(z)+\\operatorname{Im}(z)", "Re(z)+Im(z)"],
+        [
+            "\\begin{cases}x^{2}&x\\leq 1\\\\2x-1&x>1\\end{cases}",
+            "piecewise(x^{2} if x<=1, 2*x+-1 if x>1)",
+        ],
+        ["2\\cdot x\\times y", "2*x*y"],
+        ["x/y", "x*y^{-1}"],
+        ["1/2x+a/bc", "1/2*x+a*b^{-1}*c"],
+        ["x/(y+1)^{2}", "x*((y+1)^{2})^{-1}"],
     ];
     tests.iter().for_each(|test| {
         assert_eq!(latex_to_expr(test[0]).to_string(), test[1]);
     });
+    assert_eq!(
+        latex_to_relation("2x-y=3").map(|r| r.to_string()),
+        Ok(String::from("2*x+-1*y=3"))
+    );
+    assert!(latex_to_relation("2x-y").is_err());
+    assert!(latex_to_relation("x=y=1").is_err());
+    assert_eq!(
+        try_latex_to_expr("\\operatorname{foo}(x)"),
+        Err("Unknown operator name")
+    );
+    assert_eq!(
+        latex_to_relation("\\operatorname{foo}(x)=1").map(|r| r.to_string()),
+        Err("Unknown operator name")
+    );
+    let mut parser = Parser::new("i+j^{2}+j_{1}");
+    parser.set_imaginary_unit(Some("j"));
+    assert_eq!(
+        parser.parse(&Token::Eof).collect().to_string(),
+        "i+-1+j_{1}"
+    );
+}
+
+#[test]
+fn test_assumptions() {
+    let assumptions = latex_to_assumptions("x>0, y \\neq 0,n\\in\\mathbb{Z}").unwrap();
+    let expr = latex_to_expr("x^{2}y^{2}+n");
+    assert_eq!(assumptions.is_integer(&expr), None);
+    assert_eq!(assumptions.is_real(&latex_to_expr("x+n")), Some(true));
+    assert_eq!(assumptions.is_nonzero(&latex_to_expr("xy")), Some(true));
+    assert!(latex_to_assumptions("x+1>0").is_err());
+    assert!(latex_to_assumptions("x\\geq 0").is_err());
 }
@@ -0,0 +1,35 @@
+use crate::{Expr, Mul};
+
+/// Binding strength of printed exprs where operands of lower precedence are wrapped
+pub(crate) const ADD: u8 = 1;
+pub(crate) const MUL: u8 = 2;
+pub(crate) const POW: u8 = 3;
+pub(crate) const ATOM: u8 = 4;
+
+/// Printer of items such as exprs or graph nodes into its output
+pub(crate) trait Printer<T: ?Sized> {
+    type Output;
+
+    /// Output of item and its precedence
+    fn print(&self, item: &T) -> (Self::Output, u8);
+
+    fn parens(&self, output: Self::Output) -> Self::Output;
+
+    /// item wrapped by parentheses if it binds weaker than precedence
+    fn wrap(&self, item: &T, precedence: u8) -> Self::Output {
+        match self.print(item) {
+            (output, binding) if binding < precedence => self.parens(output),
+            (output, _) => output,
+        }
+    }
+}
+
+/// Whether the i-th factor of mul is the body of an integral, limit or sum which would
+/// absorb the following factors
+pub(crate) fn absorbs(mul: &Mul, i: usize) -> bool {
+    let body = matches!(
+        mul.exprs[i],
+        Expr::Integral(_) | Expr::Limit(_) | Expr::Sum(_)
+    );
+    body && i + 1 < mul.exprs.len()
+}
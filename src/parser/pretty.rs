@@ -0,0 +1,607 @@
+use super::mathml::{func_name, unicode};
+use super::precedence::{absorbs, Printer, ATOM, MUL};
+use super::serializer::{is_root, leading_minus, negate, reciprocal, split_fractions, LatexStyle};
+use crate::{Cmp, Const, Delimiter, Direction, Expr, FuncName, Num, SumOp, Sym};
+
+/// Lines of text where the baseline is the line aligned with neighbouring blocks
+#[derive(Clone, Debug)]
+struct Block {
+    lines: Vec<String>,
+    baseline: usize,
+}
+
+impl Block {
+    fn text(text: &str) -> Self {
+        Block {
+            lines: vec![text.to_string()],
+            baseline: 0,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Blocks side by side with their baselines aligned
+    fn hcat(blocks: &[Block]) -> Self {
+        let above = blocks.iter().map(|b| b.baseline).max().unwrap_or(0);
+        let below = blocks
+            .iter()
+            .map(|b| b.height() - b.baseline - 1)
+            .max()
+            .unwrap_or(0);
+        let mut lines = vec![String::new(); above + below + 1];
+        blocks.iter().for_each(|b| {
+            let width = b.width();
+            let offset = above - b.baseline;
+            lines.iter_mut().enumerate().for_each(|(i, line)| {
+                let text = match i.checked_sub(offset) {
+                    Some(j) if j < b.height() => &b.lines[j][..],
+                    _ => "",
+                };
+                line.push_str(&pad(text, width));
+            });
+        });
+        Block {
+            lines,
+            baseline: above,
+        }
+    }
+
+    /// Blocks from top to bottom centered horizontally
+    fn vcat(blocks: &[Block], baseline: usize) -> Self {
+        let width = blocks.iter().map(|b| b.width()).max().unwrap_or(0);
+        let lines = blocks
+            .iter()
+            .flat_map(|b| b.lines.iter().map(|line| center(line, width)))
+            .collect();
+        Block { lines, baseline }
+    }
+
+    fn frac(num: Block, den: Block) -> Self {
+        let width = num.width().max(den.width());
+        let baseline = num.height();
+        let bar = Block::text(&"─".repeat(width));
+        Block::vcat(&[num, bar, den], baseline)
+    }
+
+    /// Delimiters stretched to the height of the block
+    fn delimit(self, open: [&str; 4], close: [&str; 4]) -> Self {
+        let stretch = |symbols: [&str; 4]| {
+            let lines = match self.height() {
+                1 => vec![symbols[0].to_string()],
+                h => (0..h)
+                    .map(|i| match i {
+                        0 => symbols[1],
+                        i if i + 1 == h => symbols[3],
+                        _ => symbols[2],
+                    })
+                    .map(String::from)
+                    .collect(),
+            };
+            Block {
+                lines,
+                baseline: self.baseline,
+            }
+        };
+        Block::hcat(&[stretch(open), self.clone(), stretch(close)])
+    }
+
+    fn parens(self) -> Self {
+        self.delimit(["(", "⎛", "⎜", "⎝"], [")", "⎞", "⎟", "⎠"])
+    }
+
+    /// base with exponent in superscript characters without spaces if possible otherwise raised
+    fn sup(base: Block, exponent: Block) -> Self {
+        let raised = match &exponent.lines[..] {
+            [line] => line
+                .chars()
+                .filter(|&c| c != ' ')
+                .map(superscript)
+                .collect::<Option<String>>(),
+            _ => None,
+        };
+        // Superscript characters sit on the top line of the base, other exponents above it
+        let (base, mut lines) = match raised {
+            Some(raised) => (base, vec![raised]),
+            None => {
+                let mut lines = vec![String::new(); exponent.height()];
+                lines.extend(base.lines.iter().cloned());
+                let baseline = exponent.height() + base.baseline;
+                (Block { lines, baseline }, exponent.lines)
+            }
+        };
+        lines.extend(vec![String::new(); base.height() - lines.len()]);
+        let baseline = base.baseline;
+        Block::hcat(&[base, Block { lines, baseline }])
+    }
+
+    /// ╲╱ with an overbar and the index of the root if any on the left of the overbar
+    fn radical(radicand: Block, index: Option<i64>) -> Self {
+        let h = radicand.height();
+        let index = index.map_or(String::new(), |q| q.to_string());
+        let prefix = (h + 1).max(index.chars().count() + 1);
+        let mut lines = vec![format!(
+            "{}{}",
+            pad(&index, prefix),
+            "_".repeat(radicand.width())
+        )];
+        radicand.lines.iter().enumerate().for_each(|(i, line)| {
+            let foot = if i + 1 == h { "╲" } else { " " };
+            let indent = " ".repeat(prefix - 2 - i);
+            lines.push(format!("{}{}╱{}{}", indent, foot, " ".repeat(i), line));
+        });
+        Block {
+            lines,
+            baseline: radicand.baseline + 1,
+        }
+    }
+}
+
+fn pad(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width - text.chars().count()))
+}
+
+fn center(text: &str, width: usize) -> String {
+    let left = (width - text.chars().count()) / 2;
+    pad(&format!("{}{}", " ".repeat(left), text), width)
+}
+
+fn superscript(c: char) -> Option<char> {
+    let digits = "⁰¹²³⁴⁵⁶⁷⁸⁹";
+    match c {
+        '0'..='9' => digits.chars().nth(c as usize - '0' as usize),
+        '+' => Some('⁺'),
+        '-' => Some('⁻'),
+        '=' => Some('⁼'),
+        '(' => Some('⁽'),
+        ')' => Some('⁾'),
+        'a'..='z' if c != 'q' => {
+            let letters = "ᵃᵇᶜᵈᵉᶠᵍʰⁱʲᵏˡᵐⁿᵒᵖʳˢᵗᵘᵛʷˣʸᶻ";
+            let skip = if c > 'q' { 1 } else { 0 };
+            letters.chars().nth(c as usize - 'a' as usize - skip)
+        }
+        _ => None,
+    }
+}
+
+fn subscript(c: char) -> Option<char> {
+    let digits = "₀₁₂₃₄₅₆₇₈₉";
+    match c {
+        '0'..='9' => digits.chars().nth(c as usize - '0' as usize),
+        'a' => Some('ₐ'),
+        'e' => Some('ₑ'),
+        'i' => Some('ᵢ'),
+        'j' => Some('ⱼ'),
+        'k' => Some('ₖ'),
+        'm' => Some('ₘ'),
+        'n' => Some('ₙ'),
+        't' => Some('ₜ'),
+        'x' => Some('ₓ'),
+        '+' => Some('₊'),
+        '-' => Some('₋'),
+        _ => None,
+    }
+}
+
+fn sym(x: &Sym) -> String {
+    let symbol = unicode(x.symbol());
+    match x.sub().chars().map(subscript).collect::<Option<String>>() {
+        Some(sub) => format!("{}{}", symbol, sub),
+        None => format!("{}_{}", symbol, unicode(x.sub())),
+    }
+}
+
+/// Blocks of exprs parenthesized by the same precedence as serialize
+struct Pretty;
+
+impl<'a> Printer<Expr<'a>> for Pretty {
+    type Output = Block;
+
+    fn print(&self, expr: &Expr<'a>) -> (Block, u8) {
+        (render(expr), LatexStyle::default().binding(expr))
+    }
+
+    fn parens(&self, block: Block) -> Block {
+        block.parens()
+    }
+}
+
+/// Terms of an Add with the sign joining each to the previous one
+fn terms(exprs: &[Expr]) -> Vec<Block> {
+    let mut blocks = vec![render(&exprs[0])];
+    exprs[1..].iter().for_each(|term| {
+        blocks.push(match leading_minus(term) {
+            true => Block::hcat(&[Block::text(" - "), Pretty.wrap(&negate(term), MUL)]),
+            false => Block::hcat(&[Block::text(" + "), render(term)]),
+        })
+    });
+    blocks
+}
+
+fn render(expr: &Expr) -> Block {
+    match expr {
+        Expr::Add(add) => Block::hcat(&terms(&add.exprs)),
+        Expr::Mul(mul)
+            if expr.detach_coeff().0.is_real()
+                && (expr.detach_coeff().0.den > 1
+                    || mul.exprs.iter().any(|e| reciprocal(e).is_some())) =>
+        {
+            let (negative, fractions) = split_fractions(expr);
+            let mut blocks: Vec<Block> = negative.then(|| Block::text("-")).into_iter().collect();
+            fractions.iter().enumerate().for_each(|(i, (num, den))| {
+                if i > 0 {
+                    blocks.push(Block::text("⋅"));
+                }
+                blocks.push(match den {
+                    Expr::Num(n) if n.is_one() => Pretty.wrap(num, MUL),
+                    den => Block::frac(render(num), render(den)),
+                });
+            });
+            Block::hcat(&blocks)
+        }
+        Expr::Mul(mul) => {
+            let mut blocks = vec![];
+            mul.exprs.iter().enumerate().for_each(|(i, e)| {
+                let factor = match e {
+                    Expr::Num(x) if i == 0 && *x == Num::new(-1) => Block::text("-"),
+                    Expr::Num(x) if i == 0 && x.is_real() => render(e),
+                    e if absorbs(mul, i) => render(e).parens(),
+                    e => Pretty.wrap(e, MUL),
+                };
+                if i > 0 && !(i == 1 && mul.exprs[0] == Expr::Num(Num::new(-1))) {
+                    blocks.push(Block::text("⋅"));
+                }
+                blocks.push(factor);
+            });
+            Block::hcat(&blocks)
+        }
+        Expr::Pow(_) if reciprocal(expr).is_some() => {
+            Block::frac(Block::text("1"), render(&reciprocal(expr).unwrap()))
+        }
+        Expr::Pow(pow) if is_root(expr) => {
+            let (p, q) = match *pow.pow {
+                Expr::Num(n) => (n.num, n.den),
+                _ => unreachable!(),
+            };
+            let root = Block::radical(render(&pow.body), Some(q).filter(|&q| q > 2));
+            match p {
+                1 => root,
+                p => Block::sup(root.parens(), Block::text(&p.to_string())),
+            }
+        }
+        Expr::Pow(pow) => match &*pow.body {
+            Expr::Func(func) if !matches!(func.name, FuncName::Abs | FuncName::Conj) => {
+                let name = Block::sup(Block::text(func_name(func.name)), render(&pow.pow));
+                Block::hcat(&[name, render(&func.arg).parens()])
+            }
+            body => Block::sup(Pretty.wrap(body, ATOM), render(&pow.pow)),
+        },
+        Expr::Func(func) if func.name == FuncName::Abs => {
+            render(&func.arg).delimit(["│"; 4], ["│"; 4])
+        }
+        Expr::Func(func) if func.name == FuncName::Conj => {
+            let arg = render(&func.arg);
+            let bar = Block::text(&"_".repeat(arg.width()));
+            let baseline = arg.baseline + 1;
+            Block::vcat(&[bar, arg], baseline)
+        }
+        Expr::Func(func) => Block::hcat(&[
+            Block::text(func_name(func.name)),
+            render(&func.arg).parens(),
+        ]),
+        Expr::Integral(integral) => {
+            let integrand = render(&integral.integrand);
+            let mut lines = vec![String::from("⌠")];
+            lines.extend(vec![String::from("⎮"); integrand.height()]);
+            lines.push(String::from("⌡"));
+            let mut sign = Block {
+                lines,
+                baseline: integrand.baseline + 1,
+            };
+            if let Some(bounds) = &integral.bounds {
+                let (lower, upper) = (render(&bounds.0), render(&bounds.1));
+                let baseline = sign.baseline + upper.height();
+                let stacked = [upper, sign, lower].map(|b| {
+                    let width = b.width();
+                    Block {
+                        lines: b.lines.iter().map(|l| pad(l, width)).collect(),
+                        baseline: 0,
+                    }
+                });
+                let lines = stacked.iter().flat_map(|b| b.lines.clone()).collect();
+                sign = Block { lines, baseline };
+            }
+            let d = Block::text(&format!(" d{}", sym(&integral.var)));
+            Block::hcat(&[sign, Block::text(" "), integrand, d])
+        }
+        Expr::Limit(limit) => {
+            let direction = match limit.direction {
+                Direction::Both => "",
+                Direction::Plus => "⁺",
+                Direction::Minus => "⁻",
+            };
+            let point = Block::hcat(&[render(&limit.point), Block::text(direction)]);
+            let under = Block::hcat(&[Block::text(&format!("{}→", sym(&limit.var))), point]);
+            let lim = Block::vcat(&[Block::text("lim"), under], 0);
+            Block::hcat(&[lim, Block::text(" "), Pretty.wrap(&limit.expr, MUL)])
+        }
+        Expr::Sum(sum) => {
+            let op = match sum.op {
+                SumOp::Sum => "∑",
+                SumOp::Product => "∏",
+            };
+            let lower = Block::hcat(&[
+                Block::text(&format!("{}=", sym(&sum.var))),
+                render(&sum.lower),
+            ]);
+            let upper = render(&sum.upper);
+            let baseline = upper.height();
+            let op = Block::vcat(&[upper, Block::text(op), lower], baseline);
+            Block::hcat(&[op, Block::text(" "), Pretty.wrap(&sum.body, MUL)])
+        }
+        Expr::Piecewise(piecewise) => {
+            let pieces: Vec<(Block, Block)> = piecewise
+                .pieces
+                .iter()
+                .map(|(value, cond)| {
+                    let cond = match cond {
+                        Some(c) => {
+                            let cmp = match c.cmp {
+                                Cmp::Lt => " < ",
+                                Cmp::Le => " ≤ ",
+                                Cmp::Gt => " > ",
+                                Cmp::Ge => " ≥ ",
+                                Cmp::Eq => " = ",
+                                Cmp::Ne => " ≠ ",
+                            };
+                            let cmp = Block::text(cmp);
+                            Block::hcat(&[Block::text("for "), render(&c.lhs), cmp, render(&c.rhs)])
+                        }
+                        None => Block::text("otherwise"),
+                    };
+                    (render(value), cond)
+                })
+                .collect();
+            let width = pieces
+                .iter()
+                .map(|(value, _)| value.width())
+                .max()
+                .unwrap_or(0);
+            let rows: Vec<Block> = pieces
+                .into_iter()
+                .map(|(value, cond)| {
+                    let lines = value.lines.iter().map(|l| pad(l, width + 2)).collect();
+                    let value = Block {
+                        lines,
+                        baseline: value.baseline,
+                    };
+                    Block::hcat(&[value, cond])
+                })
+                .collect();
+            let values = left(&rows);
+            let h = values.height();
+            let brace = match h {
+                1 => vec![String::from("{")],
+                2 => vec![String::from("⎰"), String::from("⎱")],
+                h => (0..h)
+                    .map(|i| match i {
+                        0 => "⎧",
+                        i if i + 1 == h => "⎩",
+                        i if i == h / 2 => "⎨",
+                        _ => "⎪",
+                    })
+                    .map(String::from)
+                    .collect(),
+            };
+            let baseline = h / 2;
+            let brace = Block {
+                lines: brace,
+                baseline,
+            };
+            let values = Block {
+                lines: values.lines,
+                baseline,
+            };
+            Block::hcat(&[brace, values])
+        }
+        Expr::Matrix(matrix) => {
+            let cells: Vec<Vec<Block>> = matrix
+                .rows
+                .iter()
+                .map(|row| row.iter().map(render).collect())
+                .collect();
+            let widths: Vec<usize> = (0..cells[0].len())
+                .map(|j| cells.iter().map(|row| row[j].width()).max().unwrap_or(0))
+                .collect();
+            let rows: Vec<Block> = cells
+                .iter()
+                .map(|row| {
+                    let mut blocks = vec![];
+                    row.iter().enumerate().for_each(|(j, cell)| {
+                        if j > 0 {
+                            blocks.push(Block::text("  "));
+                        }
+                        let lines = cell.lines.iter().map(|l| center(l, widths[j])).collect();
+                        blocks.push(Block {
+                            lines,
+                            baseline: cell.baseline,
+                        });
+                    });
+                    Block::hcat(&blocks)
+                })
+                .collect();
+            let body = left(&rows);
+            let body = Block {
+                baseline: body.height() / 2,
+                lines: body.lines,
+            };
+            match matrix.delimiter {
+                Delimiter::Paren => body.parens(),
+                Delimiter::Bracket => body.delimit(["[", "⎡", "⎢", "⎣"], ["]", "⎤", "⎥", "⎦"]),
+                Delimiter::Vert => body.delimit(["│"; 4], ["│"; 4]),
+            }
+        }
+        Expr::Sym(x) => Block::text(&sym(x)),
+        Expr::Const(Const::E) => Block::text("e"),
+        Expr::Const(c) => Block::text(unicode(c.command())),
+        Expr::Num(x) if !x.is_real() => {
+            let im = match x.im() {
+                im if im.is_one() => Block::text("i"),
+                im if im == Num::new(-1) => Block::text("-i"),
+                im => Block::hcat(&[render(&Expr::Num(im)), Block::text("⋅i")]),
+            };
+            match x.re() {
+                re if re.is_zero() => im,
+                re => match im.lines[im.baseline].starts_with('-') {
+                    true => {
+                        let abs = render(&Expr::Num(Num::complex(Num::new(0), -x.im())));
+                        Block::hcat(&[render(&Expr::Num(re)), Block::text(" - "), abs])
+                    }
+                    false => Block::hcat(&[render(&Expr::Num(re)), Block::text(" + "), im]),
+                },
+            }
+        }
+        Expr::Num(x) if x.is_integer() => Block::text(&x.num.to_string()),
+        Expr::Num(x) => {
+            let frac = Block::frac(
+                Block::text(&x.num.abs().to_string()),
+                Block::text(&x.den.to_string()),
+            );
+            match x.is_negative() {
+                true => Block::hcat(&[Block::text("-"), frac]),
+                false => frac,
+            }
+        }
+    }
+}
+
+/// Blocks from top to bottom aligned on the left with baseline at the first block,
+/// or an empty line if there are none
+fn left(blocks: &[Block]) -> Block {
+    if blocks.is_empty() {
+        return Block::text("");
+    }
+    let width = blocks.iter().map(|b| b.width()).max().unwrap_or(0);
+    let lines = blocks
+        .iter()
+        .flat_map(|b| b.lines.iter().map(|l| pad(l, width)))
+        .collect();
+    Block {
+        lines,
+        baseline: blocks.first().map_or(0, |b| b.baseline),
+    }
+}
+
+/// Multi-line Unicode layout of fractions, powers, radicals and matrices
+/// where sums wider than width are broken between terms
+pub fn to_pretty(expr: &Expr, width: usize) -> String {
+    let groups = match expr {
+        Expr::Add(add) => {
+            let mut groups: Vec<Vec<Block>> = vec![vec![]];
+            let mut used = 0;
+            terms(&add.exprs).into_iter().for_each(|term| {
+                let w = term.width();
+                if used > 0 && used + w > width {
+                    groups.push(vec![]);
+                    used = 0;
+                }
+                used += w;
+                groups.last_mut().unwrap().push(term);
+            });
+            groups.iter().map(|g| Block::hcat(g)).collect()
+        }
+        expr => vec![render(expr)],
+    };
+    let separator = match groups.iter().any(|g| g.height() > 1) && groups.len() > 1 {
+        true => "\n\n",
+        false => "\n",
+    };
+    groups
+        .iter()
+        .map(|g| {
+            g.lines
+                .iter()
+                .map(|line| line.trim_end())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+#[test]
+fn test_pretty() {
+    use super::latex_to_expr;
+    let pretty = |latex: &str| to_pretty(&latex_to_expr(latex).collect(), 80);
+    assert_eq!(pretty("x+3y^{2}"), "x + 3⋅y²");
+    assert_eq!(pretty("\\alpha_{1}x^{n+1}-\\pi"), "α₁⋅xⁿ⁺¹ - π");
+    assert_eq!(pretty("\\frac{x+1}{2y}"), "x + 1\n─────\n 2⋅y");
+    assert_eq!(pretty("1+\\frac{1}{x}"), "    1\n1 + ─\n    x");
+    assert_eq!(pretty("-\\frac{x}{y}z"), " x\n-─⋅z\n y");
+    assert_eq!(pretty("\\sqrt{x+1}"), "  _____\n╲╱x + 1");
+    assert_eq!(pretty("\\sqrt[3]{x}"), "3 _\n╲╱x");
+    assert_eq!(
+        to_pretty(&latex_to_expr("\\sqrt{\\frac{1}{x}+1}"), 80),
+        "    _____\n   ╱1\n  ╱ ─ + 1\n╲╱  x"
+    );
+    assert_eq!(pretty("(x+y)^{z^{2}}"), "       z²\n(x + y)");
+    assert_eq!(pretty("\\sin^{2}x|y|"), "sin²(x)⋅│y│");
+    assert_eq!(
+        pretty("\\begin{pmatrix}1&x^{2}\\\\\\frac{1}{2}&y\\end{pmatrix}"),
+        "⎛1  x²⎞\n⎜1    ⎟\n⎜─  y ⎟\n⎝2    ⎠"
+    );
+    assert_eq!(
+        pretty("\\begin{cases}x&x\\geq 0\\\\-x&\\text{otherwise}\\end{cases}"),
+        "⎰x   for x ≥ 0\n⎱-x  otherwise"
+    );
+    assert_eq!(
+        pretty("\\sum_{k=1}^{n}k^{2}"),
+        "n³   n²   n\n── + ── + ─\n3    2    6"
+    );
+    let sum = latex_to_expr("\\sum_{k=1}^{n}k^{2}");
+    assert_eq!(to_pretty(&sum, 80), " n\n ∑  k²\nk=1");
+    let integral = latex_to_expr("\\int_{0}^{1}\\frac{1}{x+1}dx");
+    assert_eq!(
+        to_pretty(&integral, 80),
+        "1\n⌠\n⎮   1\n⎮ ───── dx\n⎮ x + 1\n⌡\n0"
+    );
+    let long = latex_to_expr("x^{3}+3x^{2}y+3xy^{2}+y^{3}");
+    assert_eq!(to_pretty(&long, 12), "x³ + 3⋅x²⋅y\n + 3⋅x⋅y²\n + y³");
+}
+
+#[test]
+fn test_pretty_edge_cases() {
+    use super::latex_to_expr;
+    use crate::Piecewise;
+    // Terms wider than width get a line each and empty nodes still print
+    let tests = [
+        ("x+y", 0, "x\n + y"),
+        (
+            "\\frac{1}{x}+\\frac{1}{y}",
+            1,
+            "1\n─\nx\n\n   1\n + ─\n   y",
+        ),
+        ("", 80, ""),
+        ("\\begin{pmatrix}\\end{pmatrix}", 80, "()"),
+        ("x_{ab}+y_{\\beta}", 80, "x_ab + y_β"),
+        (
+            "\\begin{cases}1&\\text{otherwise}\\end{cases}",
+            80,
+            "{1  otherwise",
+        ),
+    ];
+    tests.iter().for_each(|&(latex, width, pretty)| {
+        assert_eq!(to_pretty(&latex_to_expr(latex), width), pretty);
+    });
+    let empty = Expr::Piecewise(Piecewise::new(vec![]));
+    assert_eq!(to_pretty(&empty, 80), "{");
+}
@@ -1,27 +1,706 @@
-use crate::Expr;
+use super::precedence::{absorbs, Printer, ADD, ATOM, MUL, POW};
+use crate::{Direction, Expr, FuncName, Mul, Num, Pow, Series};
+use std::cmp::Ordering;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
 
+/// Sign between factors where Implicit only writes \cdot between adjacent digits
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum MulSign {
+    #[default]
+    Implicit,
+    Cdot,
+    Times,
+}
+
+/// Quotients as \frac{x}{y}, x/y or xy^{-1}
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum FracStyle {
+    #[default]
+    Frac,
+    Slash,
+    Power,
+}
+
+/// Terms as collected, by descending degree or lexicographic in symbols
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum TermOrder {
+    #[default]
+    Collected,
+    Degree,
+    Lex,
+}
+
+/// Options of LaTeX output where the default is the output of serialize
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct LatexStyle {
+    pub mul: MulSign,
+    pub frac: FracStyle,
+    pub order: TermOrder,
+    /// Space after every symbol and command otherwise only where a command needs it
+    pub spacing: bool,
+    /// \left( \right) and \left| \right| otherwise bare delimiters
+    pub left_right: bool,
+}
+
+impl Default for LatexStyle {
+    fn default() -> Self {
+        LatexStyle {
+            mul: MulSign::default(),
+            frac: FracStyle::default(),
+            order: TermOrder::default(),
+            spacing: true,
+            left_right: true,
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl LatexStyle {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> Self {
+        LatexStyle::default()
+    }
+}
+
+/// LaTeX of expr with \left(\right) exactly where the parser needs them
 pub fn serialize(expr: &Expr) -> String {
-    match expr {
-        Expr::Add(add) => {
-            let mut result = serialize(&add.exprs[0]);
-            (1..add.exprs.len()).for_each(|i| {
-                result = match &serialize(&add.exprs[i])[..1] {
-                    "-" => format!("{}{}", result, serialize(&add.exprs[i])),
-                    _ => format!("{}+{}", result, serialize(&add.exprs[i])),
+    LatexStyle::default().serialize(expr)
+}
+
+/// Power series followed by +O\left(x^{n}\right)
+pub fn serialize_series(series: &Series) -> String {
+    LatexStyle::default().serialize_series(series)
+}
+
+impl LatexStyle {
+    pub fn serialize(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Add(add) => {
+                let mut result = String::new();
+                self.order_terms(&add.exprs).iter().for_each(|term| {
+                    let term = self.serialize(term);
+                    result = match result.is_empty() || term.starts_with('-') {
+                        true => format!("{}{}", result, term),
+                        false => format!("{}+{}", result, term),
+                    }
+                });
+                result
+            }
+            Expr::Mul(mul)
+                if self.frac != FracStyle::Power
+                    && mul.exprs.iter().any(|e| reciprocal(e).is_some())
+                    && expr.detach_coeff().0.is_real() =>
+            {
+                let (negative, fractions) = split_fractions(expr);
+                let sign = negative.then(|| String::from("-"));
+                let factors = fractions.iter().map(|(num, den)| match den {
+                    Expr::Num(n) if n.is_one() => self.wrap(num, MUL),
+                    den => self.frac(num, den),
+                });
+                self.product(sign.into_iter().chain(factors))
+            }
+            Expr::Mul(mul) => {
+                let factors = mul.exprs.iter().enumerate().map(|(i, expr)| match expr {
+                    Expr::Num(x) if i == 0 && *x == Num::new(-1) => String::from("-"),
+                    Expr::Num(x) if i == 0 && x.is_real() => self.serialize(expr),
+                    expr if absorbs(mul, i) => self.paren(&self.serialize(expr)),
+                    expr => self.wrap(expr, MUL),
+                });
+                self.product(factors)
+            }
+            Expr::Pow(_) if self.frac != FracStyle::Power && reciprocal(expr).is_some() => {
+                self.frac(&Expr::Num(Num::new(1)), &reciprocal(expr).unwrap())
+            }
+            // x^{p/q}->\sqrt[q]{x}^{p} since \sqrt[q]{x^{p}} would not collect back
+            Expr::Pow(pow) if is_root(expr) => {
+                let (p, q) = match *pow.pow {
+                    Expr::Num(n) => (n.num, n.den),
+                    _ => unreachable!(),
+                };
+                let root = match q {
+                    2 => format!("\\sqrt{{{}}}", self.serialize(&pow.body)),
+                    q => format!("\\sqrt[{}]{{{}}}", q, self.serialize(&pow.body)),
+                };
+                match p {
+                    1 => root,
+                    p => format!("{}^{{{}}}", root, p),
+                }
+            }
+            Expr::Pow(pow) => match &*pow.body {
+                Expr::Func(func) if !matches!(func.name, FuncName::Abs | FuncName::Conj) => {
+                    format!(
+                        "{}^{{{}}}{}",
+                        func.name.command(),
+                        self.serialize(&pow.pow),
+                        self.paren(&self.serialize(&func.arg))
+                    )
+                }
+                body => format!("{}^{{{}}}", self.wrap(body, ATOM), self.serialize(&pow.pow)),
+            },
+            Expr::Func(func) if func.name == FuncName::Abs => match self.left_right {
+                true => format!("\\left|{}\\right|", self.serialize(&func.arg)),
+                false => format!("|{}|", self.serialize(&func.arg)),
+            },
+            Expr::Func(func) if func.name == FuncName::Conj => {
+                format!("\\overline{{{}}}", self.serialize(&func.arg))
+            }
+            Expr::Func(func) => format!(
+                "{}{}",
+                func.name.command(),
+                self.paren(&self.serialize(&func.arg))
+            ),
+            Expr::Integral(integral) => {
+                let bounds = match &integral.bounds {
+                    Some(b) => {
+                        format!("_{{{}}}^{{{}}}", self.serialize(&b.0), self.serialize(&b.1))
+                    }
+                    None => String::new(),
+                };
+                let integrand = self.join(&self.serialize(&integral.integrand), "d");
+                let var = self.serialize(&Expr::Sym(integral.var));
+                format!("\\int{} {}{}", bounds, integrand, var)
+            }
+            Expr::Limit(limit) => {
+                let direction = match limit.direction {
+                    Direction::Both => "",
+                    Direction::Plus => "^{+}",
+                    Direction::Minus => "^{-}",
+                };
+                format!(
+                    "\\lim_{{{}\\to {}{}}}{}",
+                    self.serialize(&Expr::Sym(limit.var)),
+                    self.serialize(&limit.point),
+                    direction,
+                    self.body(&limit.expr)
+                )
+            }
+            Expr::Sum(sum) => format!(
+                "{}_{{{}={}}}^{{{}}}{}",
+                sum.op.command(),
+                self.serialize(&Expr::Sym(sum.var)),
+                self.serialize(&sum.lower),
+                self.serialize(&sum.upper),
+                self.body(&sum.body)
+            ),
+            Expr::Piecewise(piecewise) => {
+                let rows = piecewise
+                    .pieces
+                    .iter()
+                    .map(|(value, cond)| {
+                        let cond = match cond {
+                            Some(c) => {
+                                let lhs = self.serialize(&c.lhs) + c.cmp.command();
+                                self.join(&lhs, &self.serialize(&c.rhs))
+                            }
+                            None => String::from("\\text{otherwise}"),
+                        };
+                        format!("{}&{}", self.serialize(value), cond)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\\\\");
+                format!("\\begin{{cases}}{}\\end{{cases}}", rows)
+            }
+            Expr::Matrix(matrix) => {
+                let rows = matrix
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|e| self.serialize(e))
+                            .collect::<Vec<_>>()
+                            .join("&")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\\\\");
+                format!(
+                    "\\begin{{{0}}}{1}\\end{{{0}}}",
+                    matrix.delimiter.env(),
+                    rows
+                )
+            }
+            Expr::Sym(x) if self.spacing => format!("{} ", x),
+            Expr::Sym(x) => x.to_string(),
+            Expr::Const(c) if self.spacing => format!("{} ", c),
+            Expr::Const(c) => c.to_string(),
+            Expr::Num(x) if !x.is_real() => {
+                let im = match x.im() {
+                    im if im.is_one() => String::from("i"),
+                    im if im == Num::new(-1) => String::from("-i"),
+                    im => format!("{}i", self.serialize(&Expr::Num(im))),
+                };
+                match x.re() {
+                    re if re.is_zero() => im,
+                    re if im.starts_with('-') => {
+                        format!("{}{}", self.serialize(&Expr::Num(re)), im)
+                    }
+                    re => format!("{}+{}", self.serialize(&Expr::Num(re)), im),
+                }
+            }
+            Expr::Num(x) if x.is_integer() => x.to_string(),
+            Expr::Num(x) if self.frac == FracStyle::Slash => format!("{}/{}", x.num, x.den),
+            Expr::Num(x) if x.is_negative() => format!("-\\frac{{{}}}{{{}}}", -x.num, x.den),
+            Expr::Num(x) => format!("\\frac{{{}}}{{{}}}", x.num, x.den),
+        }
+    }
+
+    /// Power series followed by +O\left(x^{n}\right)
+    pub fn serialize_series(&self, series: &Series) -> String {
+        let order = format!("O{}", self.paren(&self.serialize(&series.order_term())));
+        match series.coeffs.is_empty() {
+            true => order,
+            false => format!("{}+{}", self.serialize(&series.to_expr()), order),
+        }
+    }
+
+    /// Serialized factors joined by the sign of multiplication after a leading -
+    fn product(&self, factors: impl Iterator<Item = String>) -> String {
+        let mut result = String::new();
+        factors.for_each(|factor| {
+            // Adjacent digits such as 2\cdot 3^{x} would be read as one number
+            let digits = result.ends_with(|c: char| c.is_ascii_digit())
+                && factor.starts_with(|c: char| c.is_ascii_digit());
+            result = match self.mul {
+                _ if result.is_empty() || result == "-" => format!("{}{}", result, factor),
+                MulSign::Implicit if !digits => self.join(&result, &factor),
+                MulSign::Implicit | MulSign::Cdot => {
+                    self.join(&format!("{}\\cdot", result), &factor)
                 }
-            });
-            result
+                MulSign::Times => self.join(&format!("{}\\times", result), &factor),
+            };
+        });
+        result
+    }
+
+    /// \frac{num}{den} or num/den
+    fn frac(&self, num: &Expr, den: &Expr) -> String {
+        match self.frac {
+            FracStyle::Slash => format!("{}/{}", self.wrap(num, MUL), self.wrap(den, POW)),
+            _ => format!(
+                "\\frac{{{}}}{{{}}}",
+                self.serialize(num),
+                self.serialize(den)
+            ),
+        }
+    }
+
+    fn paren(&self, latex: &str) -> String {
+        match self.left_right {
+            true => format!("\\left({}\\right)", latex),
+            false => format!("({})", latex),
+        }
+    }
+
+    /// latex followed by next with a space after a trailing command if needed
+    fn join(&self, latex: &str, next: &str) -> String {
+        let letters = latex.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let command = letters.len() < latex.len() && letters.ends_with('\\');
+        match command && (self.spacing || next.starts_with(|c: char| c.is_ascii_alphabetic())) {
+            true => format!("{} {}", latex, next),
+            false => format!("{}{}", latex, next),
+        }
+    }
+
+    /// Body of a limit or sum which the parser reads as an implicit product, so that a
+    /// product with \cdot, \times or / is wrapped
+    fn body(&self, expr: &Expr) -> String {
+        let latex = self.serialize(expr);
+        let explicit = ["\\cdot", "\\times", "/"]
+            .iter()
+            .any(|op| latex.contains(op));
+        match self.binding(expr) {
+            ADD => self.paren(&latex),
+            MUL if explicit => self.paren(&latex),
+            _ => latex,
+        }
+    }
+
+    /// Precedence of the serialized expr where a leading minus binds as weak as Add
+    pub(super) fn binding(&self, expr: &Expr) -> u8 {
+        match expr {
+            _ if leading_minus(expr) => ADD,
+            Expr::Add(_) => ADD,
+            Expr::Num(x) if !x.is_real() && !x.re().is_zero() => ADD,
+            Expr::Num(x) if !x.is_integer() && *x != Num::i() => MUL,
+            Expr::Mul(_) | Expr::Integral(_) | Expr::Limit(_) | Expr::Sum(_) => MUL,
+            Expr::Pow(_) if self.frac != FracStyle::Power && reciprocal(expr).is_some() => MUL,
+            Expr::Pow(_) if is_root(expr) => ATOM,
+            Expr::Pow(_) => POW,
+            _ => ATOM,
+        }
+    }
+
+    fn order_terms<'b, 'a>(&self, terms: &'b [Expr<'a>]) -> Vec<&'b Expr<'a>> {
+        let mut terms: Vec<_> = terms.iter().collect();
+        match self.order {
+            TermOrder::Collected => {}
+            TermOrder::Degree => {
+                terms.sort_by(|a, b| compare(degree(b), degree(a)).then_with(|| lex(a, b)))
+            }
+            TermOrder::Lex => terms.sort_by(|a, b| lex(a, b)),
         }
+        terms
+    }
+}
+
+impl<'a> Printer<Expr<'a>> for LatexStyle {
+    type Output = String;
+
+    fn print(&self, expr: &Expr<'a>) -> (String, u8) {
+        (self.serialize(expr), self.binding(expr))
+    }
+
+    fn parens(&self, latex: String) -> String {
+        self.paren(&latex)
+    }
+}
+
+/// Whether the serialized expr starts with -
+pub(super) fn leading_minus(expr: &Expr) -> bool {
+    match expr {
+        Expr::Num(x) if x.is_real() => x.is_negative(),
+        Expr::Num(x) => x.re().is_negative() || (x.re().is_zero() && x.im().is_negative()),
+        Expr::Add(add) => leading_minus(&add.exprs[0]),
+        Expr::Mul(mul) => mul.exprs.first().is_some_and(leading_minus),
+        _ => false,
+    }
+}
+
+/// -expr for exprs with leading minus such as -3x->3x
+pub(super) fn negate<'a>(expr: &Expr<'a>) -> Expr<'a> {
+    match expr {
+        Expr::Num(x) => Expr::Num(-*x),
         Expr::Mul(mul) => {
-            let mut result = String::new();
-            mul.exprs
-                .iter()
-                .for_each(|expr| result = format!("{}{}", result, serialize(expr)));
-            result
-        }
-        Expr::Pow(pow) => format!("{}^{{{}}}", serialize(&pow.body), serialize(&pow.pow)),
-        Expr::Sym(x) => format!("{} ", x),
-        Expr::Num(x) if x.num == -1 => String::from("-"),
-        Expr::Num(x) => x.to_string(),
+            let mut exprs = mul.exprs.clone();
+            exprs[0] = negate(&exprs[0]);
+            Mul::new(exprs).to_expr()
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// x^{p/q} for positive p and q>1 written by \sqrt
+pub(super) fn is_root(expr: &Expr) -> bool {
+    matches!(expr, Expr::Pow(pow)
+        if matches!(*pow.pow, Expr::Num(n) if n.is_real() && n.den > 1 && n.num > 0))
+}
+
+/// Pow(x,-n)->Some(Pow(x,n)) for positive n otherwise None
+pub(super) fn reciprocal<'a>(expr: &Expr<'a>) -> Option<Expr<'a>> {
+    match expr {
+        Expr::Pow(pow) => match *pow.pow {
+            Expr::Num(n) if n.is_negative() => {
+                Some(Pow::new(*pow.body.clone(), Expr::Num(-n)).collect())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Sign of a product with real coefficient and its factors as fractions num/den in their
+/// order, so that parsing them back gives the same product. A fraction ends before a factor
+/// following a reciprocal, the coefficient belongs to the first one and den=1 for the rest.
+pub(super) fn split_fractions<'a>(expr: &Expr<'a>) -> (bool, Vec<(Expr<'a>, Expr<'a>)>) {
+    let (coef, body) = expr.detach_coeff();
+    let mut fractions = vec![(
+        vec![Expr::Num(Num::new(coef.num.abs()))],
+        vec![Expr::Num(Num::new(coef.den))],
+    )];
+    let mut closed = false;
+    body.exprs.iter().for_each(|e| match reciprocal(e) {
+        Some(e) => {
+            fractions.last_mut().unwrap().1.push(e);
+            closed = true;
+        }
+        None if closed => {
+            fractions.push((vec![e.clone()], vec![]));
+            closed = false;
+        }
+        None => fractions.last_mut().unwrap().0.push(e.clone()),
+    });
+    let fractions = fractions
+        .into_iter()
+        .map(|(num, den)| (Mul::new(num).to_expr(), Mul::new(den).to_expr()))
+        .collect();
+    (coef.is_negative(), fractions)
+}
+
+fn compare(a: Num, b: Num) -> Ordering {
+    match a - b {
+        d if d.is_zero() => Ordering::Equal,
+        d if d.is_negative() => Ordering::Less,
+        _ => Ordering::Greater,
+    }
+}
+
+/// Sum of real exponents of symbols such as 3 for x^{2}y
+fn degree(expr: &Expr) -> Num {
+    match expr {
+        Expr::Sym(_) => Num::new(1),
+        Expr::Pow(pow) => match *pow.pow {
+            Expr::Num(n) if n.is_real() => degree(&pow.body) * n,
+            _ => Num::new(0),
+        },
+        Expr::Mul(mul) => mul.exprs.iter().fold(Num::new(0), |d, e| d + degree(e)),
+        _ => Num::new(0),
+    }
+}
+
+/// Symbols with their real exponents such as [(x,2),(y,1)] for x^{2}y
+fn exponents(expr: &Expr, result: &mut Vec<(String, Num)>) {
+    match expr {
+        Expr::Sym(x) => result.push((x.to_string(), Num::new(1))),
+        Expr::Pow(pow) => match (&*pow.body, &*pow.pow) {
+            (Expr::Sym(x), Expr::Num(n)) if n.is_real() => result.push((x.to_string(), *n)),
+            _ => {}
+        },
+        Expr::Mul(mul) => mul.exprs.iter().for_each(|e| exponents(e, result)),
+        _ => {}
+    }
+}
+
+/// Lexicographic order where higher powers of alphabetically earlier symbols come first
+fn lex(a: &Expr, b: &Expr) -> Ordering {
+    let (mut ea, mut eb) = (vec![], vec![]);
+    exponents(a, &mut ea);
+    exponents(b, &mut eb);
+    let mut syms: Vec<&String> = ea.iter().chain(&eb).map(|(x, _)| x).collect();
+    syms.sort();
+    syms.dedup();
+    let power = |e: &Vec<(String, Num)>, x: &String| {
+        e.iter()
+            .filter(|(y, _)| y == x)
+            .fold(Num::new(0), |p, (_, n)| p + *n)
+    };
+    syms.iter()
+        .map(|x| compare(power(&eb, x), power(&ea, x)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+#[test]
+fn test_serialize() {
+    use super::latex_to_expr;
+    use crate::Sym;
+    let tests = [
+        ["\\frac{1}{2}x-\\frac{3}{4}", "\\frac{1}{2}x -\\frac{3}{4}"],
+        ["\\frac{x}{y}", "\\frac{x }{y }"],
+        ["\\frac{2a}{b^{2}c}", "\\frac{2a }{b ^{2}c }"],
+        ["-\\frac{x}{2y}", "-\\frac{x }{2y }"],
+        ["\\frac{1}{x+1}", "\\frac{1}{x +1}"],
+        ["\\frac{1-i}{2}", "\\frac{1}{2}-\\frac{1}{2}i"],
+        ["(2+i)x", "\\left(2+i\\right)x "],
+        ["\\overline{z}-3i", "\\overline{z }-3i"],
+        ["2\\pi e", "2\\pi e "],
+        ["\\sqrt{12}", "2\\sqrt{3}"],
+        ["\\frac{1}{\\sqrt{2}}", "\\frac{1}{2}\\sqrt{2}"],
+        ["\\sqrt[3]{x^{2}}", "\\sqrt[3]{x ^{2}}"],
+        ["\\frac{1}{\\sqrt{x}}", "\\frac{1}{\\sqrt{x }}"],
+        ["|-2x|", "\\left|2x \\right|"],
+        ["\\sgn(x)", "\\operatorname{sgn}\\left(x \\right)"],
+        [
+            "\\begin{cases}x&x\\geq 0\\\\-x&\\text{otherwise}\\end{cases}",
+            "\\begin{cases}x &x \\geq 0\\\\-x &\\text{otherwise}\\end{cases}",
+        ],
+    ];
+    tests.iter().for_each(|test| {
+        assert_eq!(serialize(&latex_to_expr(test[0]).collect()), test[1]);
+    });
+    assert_eq!(serialize(&Expr::Num(Num::new(-1))), "-1");
+    assert_eq!(serialize(&latex_to_expr("x-y")), "x -y ");
+    let func = "\\sin^{2}\\left(x \\right)+\\ln\\left(2x \\right)";
+    assert_eq!(serialize(&latex_to_expr("\\sin^{2}x+\\ln 2x")), func);
+    let int = "\\int_{0}^{1} \\exp\\left(x ^{2}\\right)dx ";
+    assert_eq!(
+        serialize(&latex_to_expr("\\int_{0}^{1}\\exp{x^{2}}dx")),
+        int
+    );
+    let sin = latex_to_expr("\\sin x").series(Sym::new("x"), &Expr::Num(Num::new(0)), 4);
+    assert_eq!(
+        serialize_series(&sin.unwrap()),
+        "x -\\frac{1}{6}x ^{3}+O\\left(x ^{4}\\right)"
+    );
+    let lim = "\\lim_{x \\to 0^{+}}\\frac{1}{x }";
+    assert_eq!(serialize(&latex_to_expr(lim)), lim);
+    let sum = "\\sum_{k =1}^{n }\\left(k +1\\right)";
+    assert_eq!(serialize(&latex_to_expr(sum)), sum);
+    let matrix = "\\begin{bmatrix}1&x \\\\\\frac{1}{2}&y \\end{bmatrix}";
+    assert_eq!(serialize(&latex_to_expr(matrix)), matrix);
+}
+
+#[test]
+fn test_round_trip() {
+    use super::latex_to_expr;
+    let tests = [
+        ["(x+y)^{2}", "\\left(x +y \\right)^{2}"],
+        ["2(x+y)", "2\\left(x +y \\right)"],
+        ["(x+1)(x-1)", "\\left(x +1\\right)\\left(x -1\\right)"],
+        ["(-2)^{x}", "\\left(-2\\right)^{x }"],
+        ["(\\frac{1}{2})^{x}", "\\left(\\frac{1}{2}\\right)^{x }"],
+        ["(1+i)^{x}", "\\left(1+i\\right)^{x }"],
+        ["(2x)^{y}", "\\left(2x \\right)^{y }"],
+        ["(x^{2})^{y}", "\\left(x ^{2}\\right)^{y }"],
+        ["2\\cdot 3^{x}", "2\\cdot 3^{x }"],
+        ["x^{y^{z}}", "x ^{y ^{z }}"],
+        ["e^{x+1}", "e ^{x +1}"],
+        ["|x|^{2}", "\\left|x \\right|^{2}"],
+        ["x^{\\frac{3}{2}}", "\\sqrt{x }^{3}"],
+    ];
+    tests.iter().for_each(|test| {
+        let expr = latex_to_expr(test[0]).collect();
+        assert_eq!(serialize(&expr), test[1]);
+        assert_eq!(latex_to_expr(&serialize(&expr)), expr);
+    });
+    let exprs = [
+        "\\frac{x+1}{y-2}",
+        "-3x^{2}y+\\frac{1}{2}\\sin x",
+        "(x-y)^{-3}",
+        "\\sqrt{x+1}^{3}",
+        "(\\frac{1}{2}+\\frac{1}{3}i)x",
+        "(-i)^{x}",
+        "\\sin^{2}(x+1)\\cos x",
+        "\\begin{pmatrix}x+1&-y\\\\2^{x}&(x+y)^{2}\\end{pmatrix}",
+        "\\sum_{k=1}^{n}(k+1)x",
+        "\\frac{x}{y}z",
+        "x^{-1}y",
+        "-\\frac{2}{3}x^{-1}yz^{-2}w",
+        "2^{x}y^{-1}3^{x}",
+        "x^{-1}",
+        "\\frac{\\frac{a}{b}}{c}",
+        "\\frac{3x}{2}",
+        "\\frac{1}{2x}",
+        "\\frac{1}{\\sqrt{x}}",
+    ];
+    // Parsing the output gives back the collected expr itself without collecting again
+    exprs.iter().for_each(|latex| {
+        let expr = latex_to_expr(latex).collect();
+        assert_eq!(latex_to_expr(&serialize(&expr)), expr, "{}", latex);
+    });
+}
+
+#[test]
+fn test_latex_style() {
+    use super::latex_to_expr;
+    let compact = LatexStyle {
+        spacing: false,
+        left_right: false,
+        ..LatexStyle::default()
+    };
+    let tests = [
+        (MulSign::Cdot, FracStyle::Frac, "2xy", "2\\cdot x\\cdot y"),
+        (
+            MulSign::Times,
+            FracStyle::Frac,
+            "2\\pi x",
+            "2\\times\\pi\\times x",
+        ),
+        (
+            MulSign::Implicit,
+            FracStyle::Frac,
+            "\\sin(x)|y|",
+            "\\sin(x)|y|",
+        ),
+        (MulSign::Implicit, FracStyle::Frac, "\\pi x", "\\pi x"),
+        (
+            MulSign::Implicit,
+            FracStyle::Slash,
+            "\\frac{x+1}{2y}",
+            "(x+1)/(2y)",
+        ),
+        (
+            MulSign::Implicit,
+            FracStyle::Slash,
+            "\\frac{x}{2}-\\frac{\\pi}{y^{2}}",
+            "1/2x-\\pi/y^{2}",
+        ),
+        (
+            MulSign::Implicit,
+            FracStyle::Power,
+            "\\frac{x+1}{y}",
+            "(x+1)y^{-1}",
+        ),
+        (
+            MulSign::Cdot,
+            FracStyle::Power,
+            "\\frac{\\pi}{y^{2}}",
+            "\\pi\\cdot y^{-2}",
+        ),
+    ];
+    tests.iter().for_each(|&(mul, frac, input, latex)| {
+        let style = LatexStyle {
+            mul,
+            frac,
+            ..compact
+        };
+        let expr = latex_to_expr(input).collect();
+        assert_eq!(style.serialize(&expr), latex);
+        assert_eq!(latex_to_expr(latex).collect(), expr);
+    });
+    let cdot = LatexStyle {
+        mul: MulSign::Cdot,
+        ..LatexStyle::default()
+    };
+    assert_eq!(cdot.serialize(&latex_to_expr("2xy")), "2\\cdot x \\cdot y ");
+
+    let order = |order: TermOrder, input: &str| {
+        let style = LatexStyle { order, ..compact };
+        style.serialize(&latex_to_expr(input).collect())
+    };
+    let poly = "1+y^{2}+x+x^{3}+xy";
+    assert_eq!(order(TermOrder::Collected, poly), "1+y^{2}+x+x^{3}+xy");
+    assert_eq!(order(TermOrder::Degree, poly), "x^{3}+xy+y^{2}+x+1");
+    assert_eq!(order(TermOrder::Lex, poly), "x^{3}+xy+x+y^{2}+1");
+}
+
+#[test]
+fn test_latex_style_round_trip() {
+    use super::try_latex_to_expr;
+    let exprs = [
+        "-\\frac{x+1}{2y}",
+        "\\frac{\\frac{a}{b}}{c}",
+        "\\frac{-3}{4}x^{-\\frac{1}{2}}",
+        "\\frac{i}{2}-\\frac{1+i}{x}",
+        "\\frac{\\sin x}{\\cos^{2}x}\\pi",
+        "2^{\\frac{x}{y}}\\sqrt[3]{\\frac{1}{x}}",
+        "\\frac{|x|}{\\ln(2)}e^{-x}",
+        "\\frac{1}{\\int_{0}^{1}x dx}y",
+        "23\\cdot 4^{x}x_{1}",
+        "\\begin{pmatrix}\\frac{1}{x}&-y\\end{pmatrix}",
+        "\\lim_{x\\to 0}\\frac{\\sin x}{x}-\\frac{1}{2}",
+        "\\sum_{k=1}^{n}\\frac{1}{k^{2}}",
+        "\\sum_{k=1}^{n}\\frac{2\\cdot 3^{k}}{k}",
+        "\\lim_{x\\to\\infty}\\frac{2\\sin x}{3y}",
+        "\\begin{cases}\\frac{1}{x}&x>0\\\\-x&\\text{otherwise}\\end{cases}",
+    ];
+    let muls = [MulSign::Implicit, MulSign::Cdot, MulSign::Times];
+    let fracs = [FracStyle::Frac, FracStyle::Slash, FracStyle::Power];
+    let styles = muls.iter().flat_map(|&mul| {
+        fracs.iter().flat_map(move |&frac| {
+            [TermOrder::Collected, TermOrder::Degree, TermOrder::Lex]
+                .into_iter()
+                .flat_map(move |order| {
+                    [(true, true), (false, false)].map(|(spacing, left_right)| LatexStyle {
+                        mul,
+                        frac,
+                        order,
+                        spacing,
+                        left_right,
+                    })
+                })
+        })
+    });
+    let styles: Vec<LatexStyle> = styles.collect();
+    for input in exprs {
+        let expr = try_latex_to_expr(input).unwrap().collect();
+        for style in &styles {
+            let latex = style.serialize(&expr);
+            let parsed = try_latex_to_expr(&latex).map(|e| e.collect());
+            assert_eq!(parsed, Ok(expr.clone()), "{} as {:?}", latex, style);
+        }
     }
 }
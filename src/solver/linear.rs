@@ -0,0 +1,190 @@
+use crate::expr::poly::Poly;
+use crate::{Expr, Num, Relation, Sym};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum LinearSolution<'a> {
+    /// Value of every unknown in the given order
+    Unique(Vec<(Sym<'a>, Expr<'a>)>),
+    /// Pivot unknowns expressed by the free unknowns which can take any value
+    Parametric {
+        solved: Vec<(Sym<'a>, Expr<'a>)>,
+        free: Vec<Sym<'a>>,
+    },
+    Inconsistent,
+}
+
+/// Solve relations which are linear in unknowns by fraction-free (Bareiss) elimination.
+/// Coefficients can be any expression without unknowns and are kept exact,
+/// so a symbolic pivot is assumed to be nonzero.
+/// Err when a coefficient of the elimination overflows.
+pub fn solve_linear<'a>(
+    relations: &[Relation<'a>],
+    unknowns: &[Sym<'a>],
+) -> Result<LinearSolution<'a>, &'static str> {
+    const OVERFLOW: &str = "Overflow";
+    let m = unknowns.len();
+    let exprs: Vec<Expr> = relations
+        .iter()
+        .map(|r| (r.lhs.clone() - r.rhs.clone()).expand())
+        .collect();
+    let mut gens: Vec<Expr> = unknowns.iter().map(|x| Expr::Sym(*x)).collect();
+    exprs.iter().for_each(|e| Poly::find_gens(e, &mut gens));
+    if gens[m..]
+        .iter()
+        .any(|g| unknowns.iter().any(|x| g.contains(x)))
+    {
+        return Err("Unknowns must appear linearly");
+    }
+
+    // Augmented matrix [A|b] of Ax=b where every gen is known so from_expr only fails on overflow
+    let mut rows = vec![];
+    for expr in &exprs {
+        let mut row = vec![Poly::zero(&gens); m + 1];
+        for (mut exps, coef) in Poly::from_expr(expr, &gens).ok_or(OVERFLOW)?.terms {
+            match exps[..m].iter().position(|e| *e > 0) {
+                None => {
+                    let term = Poly::monomial(exps, coef, &gens);
+                    row[m] = row[m].checked_sub(&term).ok_or(OVERFLOW)?
+                }
+                Some(j) if exps[..m].iter().sum::<u32>() == 1 => {
+                    exps[j] = 0;
+                    let term = Poly::monomial(exps, coef, &gens);
+                    row[j] = row[j].checked_add(&term).ok_or(OVERFLOW)?;
+                }
+                _ => return Err("Unknowns must appear linearly"),
+            }
+        }
+        rows.push(row);
+    }
+
+    // Forward elimination where every division by the previous pivot is exact
+    let mut prev = Poly::constant(Num::new(1), &gens);
+    let mut pivots = vec![];
+    for c in 0..m {
+        let r = pivots.len();
+        let Some(i) = (r..rows.len()).find(|i| !rows[*i][c].is_zero()) else {
+            continue;
+        };
+        rows.swap(r, i);
+        for i in r + 1..rows.len() {
+            for j in c + 1..=m {
+                let lhs = rows[r][c].checked_mul(&rows[i][j]).ok_or(OVERFLOW)?;
+                let rhs = rows[i][c].checked_mul(&rows[r][j]).ok_or(OVERFLOW)?;
+                let diff = lhs.checked_sub(&rhs).ok_or(OVERFLOW)?;
+                rows[i][j] = diff
+                    .checked_div_exact(&prev)?
+                    .expect("Bareiss division must be exact");
+            }
+            rows[i][c] = Poly::zero(&gens);
+        }
+        prev = rows[r][c].clone();
+        pivots.push(c);
+    }
+    if rows[pivots.len()..].iter().any(|row| !row[m].is_zero()) {
+        return Ok(LinearSolution::Inconsistent);
+    }
+
+    // Back substitution of numerators N_j=D*x_j where D is the last pivot
+    let denom = prev;
+    let mut numers: Vec<Poly> = (0..m).map(|j| denom.mul(&Poly::gen(j, &gens))).collect();
+    for (r, c) in pivots.iter().enumerate().rev() {
+        let init = denom.checked_mul(&rows[r][m]).ok_or(OVERFLOW)?;
+        let rest = (c + 1..m).try_fold(init, |acc, j| {
+            acc.checked_sub(&rows[r][j].checked_mul(&numers[j])?)
+        });
+        numers[*c] = rest
+            .ok_or(OVERFLOW)?
+            .checked_div_exact(&rows[r][*c])?
+            .expect("Back substitution must be exact");
+    }
+
+    let solved: Vec<(Sym, Expr)> = pivots
+        .iter()
+        .map(|c| (unknowns[*c], Poly::ratio_to_expr(&numers[*c], &denom)))
+        .collect();
+    let free: Vec<Sym> = (0..m)
+        .filter(|j| !pivots.contains(j))
+        .map(|j| unknowns[j])
+        .collect();
+    Ok(match free.is_empty() {
+        true => LinearSolution::Unique(solved),
+        false => LinearSolution::Parametric { solved, free },
+    })
+}
+
+#[cfg(test)]
+mod test_linear {
+    use super::{solve_linear, LinearSolution};
+    use crate::parser::latex_to_relation;
+    use crate::Sym;
+
+    fn solve(relations: &[&'static str], unknowns: &[&'static str]) -> String {
+        let relations: Vec<_> = relations
+            .iter()
+            .map(|r| latex_to_relation(r).unwrap())
+            .collect();
+        let unknowns: Vec<_> = unknowns.iter().map(|x| Sym::new(x)).collect();
+        let show = |solved: Vec<(Sym, crate::Expr)>| {
+            solved
+                .iter()
+                .map(|(x, v)| format!("{}={}", x, v))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        match solve_linear(&relations, &unknowns).unwrap() {
+            LinearSolution::Unique(solved) => show(solved),
+            LinearSolution::Parametric { solved, free } => format!(
+                "{};free:{}",
+                show(solved),
+                free.iter().map(|x| x.to_string()).collect::<String>()
+            ),
+            LinearSolution::Inconsistent => "inconsistent".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_numeric() {
+        assert_eq!(solve(&["2x+3y=5", "x-y=1"], &["x", "y"]), "x=8/5,y=3/5");
+        assert_eq!(
+            solve(&["x+y+z=6", "2y+5z=-4", "2x+5y-z=27"], &["x", "y", "z"]),
+            "x=5,y=3,z=-2"
+        );
+        assert_eq!(solve(&["y=2x", "x=3"], &["x", "y"]), "x=3,y=6");
+        assert_eq!(solve(&["x+y=1", "2x+2y=3"], &["x", "y"]), "inconsistent");
+        assert_eq!(solve(&["x+y=1", "2x+2y=2"], &["x", "y"]), "x=-1*y+1;free:y");
+    }
+
+    #[test]
+    fn test_symbolic() {
+        assert_eq!(
+            solve(&["ax+by=e", "cx+dy=f"], &["x", "y"]),
+            "x=(-1*b*f+e*d)*(a*d+-1*b*c)^{-1},y=(a*f+-1*e*c)*(a*d+-1*b*c)^{-1}"
+        );
+        assert_eq!(solve(&["mx=mg", "y-x=h"], &["x", "y"]), "x=g,y=g+h");
+        assert_eq!(solve(&["2ax=4a^{2}b"], &["x"]), "x=2*a*b");
+    }
+
+    #[test]
+    fn test_nonlinear() {
+        let relations = [latex_to_relation("xy=1").unwrap()];
+        let unknowns = [Sym::new("x"), Sym::new("y")];
+        assert!(solve_linear(&relations, &unknowns).is_err());
+        let relations = [latex_to_relation("x^{2}=1").unwrap()];
+        assert!(solve_linear(&relations, &unknowns).is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let relations = [
+            latex_to_relation("1000000000000x+999999999999y=1").unwrap(),
+            latex_to_relation("999999999998x+1000000000000y=3").unwrap(),
+            latex_to_relation("x+y+z=1").unwrap(),
+        ];
+        let unknowns = [Sym::new("x"), Sym::new("y"), Sym::new("z")];
+        assert_eq!(solve_linear(&relations, &unknowns), Err("Overflow"));
+        assert_eq!(
+            solve(&["1000000000000x=1", "y=x"], &["x", "y"]),
+            "x=1/1000000000000,y=1/1000000000000"
+        );
+    }
+}
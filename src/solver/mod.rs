@@ -0,0 +1,4 @@
+mod linear;
+mod polynomial;
+pub use linear::{solve_linear, LinearSolution};
+pub use polynomial::{coefficients, solve_polynomial};
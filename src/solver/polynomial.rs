@@ -0,0 +1,177 @@
+use crate::expr::poly::Poly;
+use crate::{Expr, Num, Pow, Sym};
+
+/// Coefficients [c0,c1,...,cn] of expr=c0+c1*var+...+cn*var^n
+pub fn coefficients<'a>(expr: &Expr<'a>, var: Sym<'a>) -> Result<Vec<Expr<'a>>, &'static str> {
+    let expr = expr.expand();
+    let mut gens = vec![Expr::Sym(var)];
+    Poly::find_gens(&expr, &mut gens);
+    if gens[1..].iter().any(|g| g.contains(&var)) {
+        return Err("Not a polynomial");
+    }
+    let poly = Poly::from_expr(&expr, &gens).unwrap();
+    let degree = poly.terms.iter().map(|t| t.0[0]).max().unwrap_or(0) as usize;
+    let mut coeffs = vec![Poly::zero(&gens); degree + 1];
+    for (mut exps, coef) in poly.terms {
+        let k = exps[0] as usize;
+        exps[0] = 0;
+        coeffs[k] = coeffs[k].add(&Poly::monomial(exps, coef, &gens));
+    }
+    Ok(coeffs.iter().map(|c| c.to_expr()).collect())
+}
+
+/// Roots of polynomial expr in var with their multiplicities.
+/// Rational roots of a numeric polynomial are found by the rational root theorem
+/// and the rest is solved by the quadratic formula when its degree is at most 2.
+pub fn solve_polynomial<'a>(
+    expr: &Expr<'a>,
+    var: Sym<'a>,
+) -> Result<Vec<(Expr<'a>, usize)>, &'static str> {
+    let mut coeffs = coefficients(expr, var)?;
+    if coeffs.len() == 1 {
+        return Err("Not an equation of the variable");
+    }
+    let mut roots = vec![];
+    let zeros = coeffs.iter().take_while(|c| is_zero(c)).count();
+    if zeros > 0 {
+        roots.push((Expr::Num(Num::new(0)), zeros));
+        coeffs.drain(..zeros);
+    }
+    let nums: Option<Vec<Num>> = coeffs
+        .iter()
+        .map(|c| match c {
+            Expr::Num(n) => Some(*n),
+            _ => None,
+        })
+        .collect();
+    if let Some(mut nums) = nums.filter(|nums| nums.iter().all(|n| n.is_real())) {
+        for (root, m) in rational_roots(&mut nums) {
+            roots.push((Expr::Num(root), m));
+        }
+        coeffs = nums.into_iter().map(Expr::Num).collect();
+    }
+    match coeffs.len() {
+        1 => {}
+        2 => roots.push(((-coeffs[0].clone() / coeffs[1].clone()).collect(), 1)),
+        3 => roots.extend(quadratic(&coeffs[0], &coeffs[1], &coeffs[2])),
+        _ => return Err("Cannot solve polynomial of degree more than 2"),
+    }
+    Ok(roots)
+}
+
+fn is_zero(expr: &Expr) -> bool {
+    matches!(expr, Expr::Num(n) if n.is_zero())
+}
+
+/// Roots of a*x^2+b*x+c=0
+fn quadratic<'a>(c: &Expr<'a>, b: &Expr<'a>, a: &Expr<'a>) -> Vec<(Expr<'a>, usize)> {
+    let two_a = Expr::Num(Num::new(2)) * a.clone();
+    let disc = (b.clone() * b.clone() - Expr::Num(Num::new(4)) * a.clone() * c.clone()).expand();
+    if is_zero(&disc) {
+        return vec![((-b.clone() / two_a).collect(), 2)];
+    }
+    let sqrt = Expr::Pow(Pow::new(disc, Expr::Num(Num::frac(1, 2))));
+    [Num::new(-1), Num::new(1)]
+        .iter()
+        .map(|sign| {
+            let root = (-b.clone() + Expr::Num(*sign) * sqrt.clone()) / two_a.clone();
+            (root.expand(), 1)
+        })
+        .collect()
+}
+
+/// Extract rational roots with multiplicities from coefficients in ascending order
+/// whose constant term is nonzero, leaving the deflated coefficients
+fn rational_roots(coeffs: &mut Vec<Num>) -> Vec<(Num, usize)> {
+    let lcm = coeffs
+        .iter()
+        .fold(1, |acc, c| acc / crate::expr::gcd(acc, c.den) * c.den);
+    *coeffs = coeffs.iter().map(|c| *c * Num::new(lcm)).collect();
+    let mut roots = vec![];
+    for p in divisors(coeffs[0].num) {
+        for q in divisors(coeffs.last().unwrap().num) {
+            for root in [Num::frac(p, q), Num::frac(-p, q)] {
+                if roots.iter().any(|(r, _)| *r == root) {
+                    continue;
+                }
+                let mut m = 0;
+                while coeffs.len() > 1 {
+                    match deflate(coeffs, root) {
+                        Some(quotient) => *coeffs = quotient,
+                        None => break,
+                    }
+                    m += 1;
+                }
+                if m > 0 {
+                    roots.push((root, m));
+                }
+            }
+        }
+    }
+    roots
+}
+
+/// Coefficients of p(x)/(x-root) if root is a root of p
+fn deflate(coeffs: &[Num], root: Num) -> Option<Vec<Num>> {
+    let mut quotient = vec![Num::new(0); coeffs.len() - 1];
+    let mut acc = Num::new(0);
+    for k in (0..coeffs.len()).rev() {
+        acc = acc * root + coeffs[k];
+        if k > 0 {
+            quotient[k - 1] = acc;
+        }
+    }
+    match acc.is_zero() {
+        true => Some(quotient),
+        false => None,
+    }
+}
+
+fn divisors(n: i64) -> Vec<i64> {
+    let n = n.abs();
+    let mut result = vec![];
+    let mut d = 1;
+    while d * d <= n {
+        if n % d == 0 {
+            result.push(d);
+            if d * d != n {
+                result.push(n / d);
+            }
+        }
+        d += 1;
+    }
+    result.sort();
+    result
+}
+
+#[cfg(test)]
+mod test_polynomial {
+    use super::solve_polynomial;
+    use crate::parser::latex_to_expr;
+    use crate::Sym;
+
+    fn roots(latex: &'static str) -> String {
+        solve_polynomial(&latex_to_expr(latex), Sym::new("x"))
+            .unwrap()
+            .iter()
+            .map(|(root, m)| match m {
+                1 => root.to_string(),
+                m => format!("{}^{}", root, m),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    #[test]
+    fn test_solve_polynomial() {
+        assert_eq!(roots("2x-3"), "3/2");
+        assert_eq!(roots("x^{2}-5x+6"), "2,3");
+        assert_eq!(roots("x^{3}-x^{2}"), "0^2,1");
+        assert_eq!(roots("(x-1)^{3}(2x+1)"), "1^3,-1/2");
+        assert_eq!(roots("x^{2}-x-1"), "1/2+-1/2*5^{1/2},1/2+1/2*5^{1/2}");
+        assert_eq!(roots("x^{2}-2ax+a^{2}"), "a^2");
+        assert_eq!(roots("ax+b"), "-1*b*a^{-1}");
+        assert!(solve_polynomial(&latex_to_expr("x^{3}+x+1"), Sym::new("x")).is_err());
+        assert!(solve_polynomial(&latex_to_expr("x^{y}"), Sym::new("x")).is_err());
+    }
+}
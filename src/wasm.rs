@@ -0,0 +1,311 @@
+use crate::codegen::{self, codegen, compile, Language};
+use crate::parser::{
+    ascii_to_expr, json_to_expr, latex_to_assumptions, latex_to_expr, latex_to_relation, to_ascii,
+    to_content_mathml, to_json, to_mathml, to_pretty, try_latex_to_expr, LatexStyle,
+};
+use crate::solver::{solve_linear, LinearSolution};
+use crate::{Expr, Relation, Sym};
+use wasm_bindgen::prelude::*;
+
+/// Every function writes LaTeX in the optional style where omitted is the default style
+#[wasm_bindgen]
+pub fn collect(input: String, style: Option<LatexStyle>) -> String {
+    match parse(&input) {
+        Ok(Expr::Sym(_)) => input,
+        Ok(expr) => style.unwrap_or_default().serialize(&expr.collect()),
+        Err(err) => err,
+    }
+}
+
+/// Expr of input or the parse error as \text{...}
+fn parse(input: &str) -> Result<Expr<'_>, String> {
+    try_latex_to_expr(input).map_err(|err| format!("\\text{{{}}}", err))
+}
+
+/// Equations are separated by newline or \\ and unknowns by comma
+#[wasm_bindgen]
+pub fn linear_solve(equations: String, unknowns: String, style: Option<LatexStyle>) -> String {
+    let style = style.unwrap_or_default();
+    let relations: Result<Vec<Relation>, _> = equations
+        .split('\n')
+        .flat_map(|line| line.split("\\\\"))
+        .filter(|eq| !eq.trim().is_empty())
+        .map(latex_to_relation)
+        .collect();
+    let Ok(relations) = relations else {
+        return String::from("\\text{equation expected}");
+    };
+    let unknowns: Option<Vec<Sym>> = unknowns
+        .split(',')
+        .map(|x| match latex_to_expr(x.trim()) {
+            Expr::Sym(sym) => Some(sym),
+            _ => None,
+        })
+        .collect();
+    let Some(unknowns) = unknowns else {
+        return String::from("\\text{unknown must be a symbol}");
+    };
+    let show = |solved: Vec<(Sym, Expr)>| {
+        solved
+            .iter()
+            .map(|(x, v)| {
+                let x = style.serialize(&Expr::Sym(*x));
+                format!("{}={}", x, style.serialize(v))
+            })
+            .collect::<Vec<String>>()
+            .join("\\\\")
+    };
+    match solve_linear(&relations, &unknowns) {
+        Ok(LinearSolution::Unique(solved)) => show(solved),
+        Ok(LinearSolution::Parametric { solved, .. }) => show(solved),
+        Ok(LinearSolution::Inconsistent) => String::from("\\text{no solution}"),
+        Err(err) => format!("\\text{{{}}}", err),
+    }
+}
+
+/// Operation is one of det, inverse, rank, rref, nullspace, charpoly and eigenvalues
+/// where input is a matrix environment such as \begin{pmatrix}...\end{pmatrix}
+#[wasm_bindgen]
+pub fn linalg(operation: String, input: String, style: Option<LatexStyle>) -> String {
+    let style = style.unwrap_or_default();
+    let matrix = match parse(&input) {
+        Ok(Expr::Matrix(matrix)) => matrix.collect(),
+        Ok(_) => return String::from("\\text{matrix is expected}"),
+        Err(err) => return err,
+    };
+    let (m, n) = matrix.shape();
+    if m != n && !matches!(&operation[..], "rank" | "rref" | "nullspace") {
+        return String::from("\\text{square matrix is expected}");
+    }
+    let join = |exprs: Vec<Expr>| {
+        exprs
+            .iter()
+            .map(|e| style.serialize(e))
+            .collect::<Vec<String>>()
+            .join(",")
+    };
+    match &operation[..] {
+        "det" => style.serialize(&matrix.det()),
+        "inverse" => match matrix.inverse() {
+            Some(inverse) => style.serialize(&Expr::Matrix(inverse)),
+            None => String::from("\\text{matrix is singular}"),
+        },
+        "rank" => matrix.rank().to_string(),
+        "rref" => style.serialize(&Expr::Matrix(matrix.rref())),
+        "nullspace" => match matrix.nullspace() {
+            basis if basis.is_empty() => String::from("\\{\\mathbf{0}\\}"),
+            basis => join(basis.into_iter().map(Expr::Matrix).collect()),
+        },
+        "charpoly" => style.serialize(&matrix.charpoly(Sym::new("\\lambda"))),
+        "eigenvalues" => match matrix.eigenvalues() {
+            Ok(eigenvalues) => join(
+                eigenvalues
+                    .into_iter()
+                    .flat_map(|(value, m)| vec![value; m])
+                    .collect(),
+            ),
+            Err(err) => format!("\\text{{{}}}", err),
+        },
+        _ => format!("\\text{{unknown operation {}}}", operation),
+    }
+}
+
+/// Series of input at var=point up to but not including (var-point)^{order}
+#[wasm_bindgen]
+pub fn series(
+    input: String,
+    var: String,
+    point: String,
+    order: i32,
+    style: Option<LatexStyle>,
+) -> String {
+    let var = match latex_to_expr(var.trim()) {
+        Expr::Sym(sym) => sym,
+        _ => return String::from("\\text{variable must be a symbol}"),
+    };
+    let (expr, point) = match (parse(&input), parse(&point)) {
+        (Ok(expr), Ok(point)) => (expr, point),
+        (Err(err), _) | (_, Err(err)) => return err,
+    };
+    match expr.series(var, &point, order as i64) {
+        Ok(series) => style.unwrap_or_default().serialize_series(&series),
+        Err(err) => format!("\\text{{{}}}", err),
+    }
+}
+
+/// Operation is one of trigsimp, expand_trig, product_to_sum and sum_to_product
+#[wasm_bindgen]
+pub fn trig(operation: String, input: String, style: Option<LatexStyle>) -> String {
+    let style = style.unwrap_or_default();
+    let expr = match parse(&input) {
+        Ok(expr) => expr,
+        Err(err) => return err,
+    };
+    match &operation[..] {
+        "trigsimp" => style.serialize(&expr.trigsimp()),
+        "expand_trig" => style.serialize(&expr.expand_trig()),
+        "product_to_sum" => style.serialize(&expr.product_to_sum()),
+        "sum_to_product" => style.serialize(&expr.sum_to_product()),
+        _ => format!("\\text{{unknown operation {}}}", operation),
+    }
+}
+
+/// Operation is one of expand_log and logcombine where only arguments positive under
+/// assumptions such as x>0,n\in\mathbb{Z} are rewritten
+#[wasm_bindgen]
+pub fn log(
+    operation: String,
+    input: String,
+    assumptions: String,
+    style: Option<LatexStyle>,
+) -> String {
+    let style = style.unwrap_or_default();
+    let assumptions = match latex_to_assumptions(&assumptions) {
+        Ok(assumptions) => assumptions,
+        Err(err) => return format!("\\text{{{}}}", err),
+    };
+    let expr = match parse(&input) {
+        Ok(expr) => expr,
+        Err(err) => return err,
+    };
+    match &operation[..] {
+        "expand_log" => style.serialize(&expr.expand_log(&assumptions, false)),
+        "logcombine" => style.serialize(&expr.logcombine(&assumptions, false)),
+        _ => format!("\\text{{unknown operation {}}}", operation),
+    }
+}
+
+/// Collect input under assumptions such as x>0,n\in\mathbb{Z}
+#[wasm_bindgen]
+pub fn refine(input: String, assumptions: String, style: Option<LatexStyle>) -> String {
+    let style = style.unwrap_or_default();
+    let expr = match parse(&input) {
+        Ok(expr) => expr,
+        Err(err) => return err,
+    };
+    match latex_to_assumptions(&assumptions) {
+        Ok(assumptions) => style.serialize(&expr.refine(&assumptions)),
+        Err(err) => format!("\\text{{{}}}", err),
+    }
+}
+
+/// Presentation MathML of input or Content MathML if content
+#[wasm_bindgen]
+pub fn mathml(input: String, content: bool) -> String {
+    let expr = match parse(&input) {
+        Ok(expr) => expr,
+        Err(err) => return err,
+    };
+    match content {
+        true => to_content_mathml(&expr),
+        false => to_mathml(&expr),
+    }
+}
+
+/// Multi-line Unicode layout of input with sums broken at width columns
+#[wasm_bindgen]
+pub fn pretty(input: String, width: usize) -> String {
+    parse(&input).map_or_else(|err| err, |expr| to_pretty(&expr, width))
+}
+
+/// ASCII math such as 2*x^2 + 3*(y+1)/4 to LaTeX
+#[wasm_bindgen]
+pub fn ascii_to_latex(input: String, style: Option<LatexStyle>) -> String {
+    match ascii_to_expr(&input) {
+        Ok(expr) => style.unwrap_or_default().serialize(&expr),
+        Err(err) => format!("\\text{{{}}}", err),
+    }
+}
+
+#[wasm_bindgen]
+pub fn latex_to_ascii(input: String) -> String {
+    parse(&input).map_or_else(|err| err, |expr| to_ascii(&expr))
+}
+
+/// Versioned JSON AST of input as a JS object
+#[wasm_bindgen]
+pub fn latex_to_ast(input: String) -> Result<JsValue, JsValue> {
+    let expr = try_latex_to_expr(&input).map_err(JsValue::from_str)?;
+    js_sys::JSON::parse(&to_json(&expr))
+}
+
+/// LaTeX of a JS object in the JSON AST format of latex_to_ast
+#[wasm_bindgen]
+pub fn ast_to_latex(ast: JsValue, style: Option<LatexStyle>) -> String {
+    let json = js_sys::JSON::stringify(&ast).map_or(String::new(), String::from);
+    match json_to_expr(&json) {
+        Ok(expr) => style.unwrap_or_default().serialize(&expr),
+        Err(err) => format!("\\text{{{}}}", err),
+    }
+}
+
+/// Function named name of the comma separated args in language rust, c or js
+#[wasm_bindgen]
+pub fn generate_code(
+    input: String,
+    name: String,
+    args: String,
+    language: String,
+) -> Result<String, JsValue> {
+    let args: Vec<Sym> = args
+        .split(',')
+        .filter(|x| !x.trim().is_empty())
+        .map(|x| match latex_to_expr(x.trim()) {
+            Expr::Sym(sym) => Ok(sym),
+            _ => Err(JsValue::from_str("Argument must be a symbol")),
+        })
+        .collect::<Result<_, _>>()?;
+    let language = Language::from_name(&language).ok_or(JsValue::from_str("Unknown language"))?;
+    let expr = try_latex_to_expr(&input).map_err(JsValue::from_str)?;
+    codegen(&expr, &name, &args, language).map_err(JsValue::from_str)
+}
+
+/// Values of input at resolution evenly spaced points of var from from to to inclusive
+/// where out of domain values are NaN
+#[wasm_bindgen]
+pub fn sample_range(
+    input: String,
+    var: String,
+    from: f64,
+    to: f64,
+    resolution: usize,
+) -> Result<Vec<f64>, JsValue> {
+    let expr = try_latex_to_expr(&input).map_err(JsValue::from_str)?;
+    let program = compile(&expr, &[variable(&var)?]).map_err(JsValue::from_str)?;
+    let step = (to - from) / resolution.saturating_sub(1).max(1) as f64;
+    let xs: Vec<f64> = (0..resolution).map(|i| from + step * i as f64).collect();
+    let mut ys = vec![0.0; resolution];
+    program.eval_slices(&[&xs], &mut ys);
+    Ok(ys)
+}
+
+/// Segments of latex simplified as Float64Arrays of x0,y0,x1,y1,... on a grid of n points
+/// of var from from to to refined adaptively and split at discontinuities and poles
+#[wasm_bindgen]
+pub fn sample(
+    latex: String,
+    var: String,
+    from: f64,
+    to: f64,
+    n: usize,
+) -> Result<js_sys::Array, JsValue> {
+    let expr = try_latex_to_expr(&latex)
+        .map_err(JsValue::from_str)?
+        .collect();
+    let program = compile(&expr, &[variable(&var)?]).map_err(JsValue::from_str)?;
+    let segments = codegen::sample(&program, from, to, n);
+    Ok(segments
+        .iter()
+        .map(|segment| {
+            let xy: Vec<f64> = segment.iter().flat_map(|&(x, y)| [x, y]).collect();
+            js_sys::Float64Array::from(&xy[..])
+        })
+        .collect())
+}
+
+fn variable(var: &str) -> Result<Sym<'_>, JsValue> {
+    match try_latex_to_expr(var) {
+        Ok(Expr::Sym(sym)) => Ok(sym),
+        _ => Err(JsValue::from_str("Variable must be a symbol")),
+    }
+}